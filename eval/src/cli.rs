@@ -62,6 +62,18 @@ pub fn report_case(repo_root: &Path, case_id: &str) -> Result<()> {
     if let Some(avg) = summary.avg_duration_secs {
         println!("report: avg_duration_secs={:.2}", avg);
     }
+    let phase_total_ms: u64 = summary.phase_totals_ms.iter().map(|(_, ms)| ms).sum();
+    if phase_total_ms > 0 {
+        println!("report: iterations={}", summary.iterations);
+        for (phase, ms) in &summary.phase_totals_ms {
+            println!(
+                "report: phase {} total_secs={:.2} share={:.1}%",
+                phase,
+                *ms as f64 / 1000.0,
+                *ms as f64 * 100.0 / phase_total_ms as f64
+            );
+        }
+    }
     for (label, (passed, total)) in summary.check_pass_rates {
         println!("report: check {} {}/{}", label, passed, total);
     }
@@ -7,6 +7,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use runner::io::iteration_log::{IterationMeta, PhaseTimings};
 
 use crate::judge::{CheckOutcome, Judgment};
 use crate::results::EvalMeta;
@@ -22,6 +23,10 @@ pub struct ReportSummary {
     pub avg_duration_secs: Option<f64>,
     /// Pass rate per check: label → (passed, total).
     pub check_pass_rates: BTreeMap<String, (usize, usize)>,
+    /// Runner iterations with a readable `meta.json` across all runs.
+    pub iterations: usize,
+    /// Total time per iteration phase across all runs, in execution order.
+    pub phase_totals_ms: Vec<(&'static str, u64)>,
 }
 
 /// Load all run directories for a case.
@@ -98,11 +103,60 @@ pub fn aggregate(case_results_dir: &Path) -> Result<(ReportSummary, Vec<String>)
         });
 
         update_check_pass_rates(&mut summary.check_pass_rates, &judgment);
+        add_iteration_phases(&mut summary, &run_dir.join("iterations"), &mut warnings)?;
     }
 
     Ok((summary, warnings))
 }
 
+/// Add phase timings from every captured `iterations/<run-id>/<iter>/meta.json`.
+///
+/// Archived `<iter>.aborted-<n>` directories are skipped; they never reached `meta.json`.
+fn add_iteration_phases(
+    summary: &mut ReportSummary,
+    iterations_dir: &Path,
+    warnings: &mut Vec<String>,
+) -> Result<()> {
+    if summary.phase_totals_ms.is_empty() {
+        summary.phase_totals_ms = PhaseTimings::default()
+            .entries()
+            .iter()
+            .map(|(name, _)| (*name, 0))
+            .collect();
+    }
+    for run_dir in load_run_dirs(iterations_dir)? {
+        for iter_dir in load_run_dirs(&run_dir)? {
+            let is_iteration = iter_dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.parse::<u32>().is_ok());
+            let meta_path = iter_dir.join("meta.json");
+            if !is_iteration || !meta_path.exists() {
+                continue;
+            }
+            let meta: IterationMeta = match fs::read_to_string(&meta_path)
+                .with_context(|| format!("read {}", meta_path.display()))
+                .and_then(|contents| serde_json::from_str(&contents).context("parse meta"))
+            {
+                Ok(meta) => meta,
+                Err(err) => {
+                    warnings.push(format!("skip timings {}: {err}", meta_path.display()));
+                    continue;
+                }
+            };
+            summary.iterations += 1;
+            for ((_, total), (_, ms)) in summary
+                .phase_totals_ms
+                .iter_mut()
+                .zip(meta.phases.entries())
+            {
+                *total += ms.unwrap_or(0);
+            }
+        }
+    }
+    Ok(())
+}
+
 fn update_check_pass_rates(stats: &mut BTreeMap<String, (usize, usize)>, judgment: &Judgment) {
     for check in &judgment.checks {
         let label = label_for_check(check);
@@ -194,4 +248,52 @@ mod tests {
         let key = "runner_completed".to_string();
         assert_eq!(summary.check_pass_rates.get(&key), Some(&(1, 2)));
     }
+
+    fn write_iteration_meta(run_dir: &Path, iter: &str, phases: PhaseTimings) {
+        let dir = run_dir.join("iterations/run-x").join(iter);
+        fs::create_dir_all(&dir).expect("iter dir");
+        let meta = serde_json::json!({
+            "run_id": "run-x",
+            "iter": 1,
+            "node_id": "root",
+            "status": "done",
+            "guard": "pass",
+            "started_at": null,
+            "ended_at": null,
+            "duration_ms": null,
+            "diffstat": null,
+            "phases": phases,
+        });
+        fs::write(dir.join("meta.json"), meta.to_string()).expect("write iteration meta");
+    }
+
+    #[test]
+    fn aggregates_phase_timings_across_runs() {
+        let temp = tempdir().expect("tempdir");
+        let run1 = temp.path().join("run1");
+        let run2 = temp.path().join("run2");
+        for run in [&run1, &run2] {
+            fs::create_dir_all(run).expect("run dir");
+            write_meta(&run.join("meta.json"), Outcome::Success, 1.0);
+            write_checks(&run.join("checks.json"), true);
+        }
+        let phases = PhaseTimings {
+            agent_exec_ms: Some(1000),
+            guards_ms: Some(200),
+            ..PhaseTimings::default()
+        };
+        write_iteration_meta(&run1, "1", phases);
+        write_iteration_meta(&run1, "2", phases);
+        write_iteration_meta(&run2, "1", phases);
+        // Aborted iterations never wrote meta.json and must not be counted.
+        write_iteration_meta(&run2, "2.aborted-1", phases);
+
+        let (summary, warnings) = aggregate(temp.path()).expect("aggregate");
+        assert!(warnings.is_empty(), "{warnings:?}");
+        assert_eq!(summary.iterations, 3);
+        let totals: BTreeMap<_, _> = summary.phase_totals_ms.iter().copied().collect();
+        assert_eq!(totals["agent_exec"], 3000);
+        assert_eq!(totals["guards"], 600);
+        assert_eq!(totals["commit"], 0);
+    }
 }
@@ -10,6 +10,8 @@ use axum::routing::get;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use runner::io::lock::{LockInfo, holder_state, read_lock};
+
 use crate::state::AppState;
 
 /// Build the API router.
@@ -21,6 +23,7 @@ pub fn api_router() -> Router<AppState> {
         .route("/config", get(get_config))
         .route("/assumptions", get(get_assumptions))
         .route("/questions", get(get_questions))
+        .route("/lock", get(get_lock))
         .route("/iterations", get(list_iterations))
         .route("/iterations/{run_id}/{iter}", get(get_iteration))
         .route("/iterations/{run_id}/{iter}/guard.log", get(get_guard_log))
@@ -74,6 +77,23 @@ async fn get_questions(State(state): State<AppState>) -> Result<String, StatusCo
     fs::read_to_string(&path).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
 }
 
+#[derive(Serialize)]
+struct LockResponse {
+    #[serde(flatten)]
+    info: LockInfo,
+    /// `alive`, `stale` or `unknown` (holder on another host).
+    holder: &'static str,
+}
+
+/// GET /api/lock - returns the runner lock holder, or `null` when no runner holds it.
+async fn get_lock(State(state): State<AppState>) -> Result<Json<Option<LockResponse>>, StatusCode> {
+    let info = read_lock(&state.runner_dir()).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(info.map(|info| {
+        let holder = holder_state(&info).as_str();
+        LockResponse { info, holder }
+    })))
+}
+
 /// Convert a TOML value to a JSON value.
 fn toml_to_json(toml_val: toml::Value) -> Value {
     match toml_val {
@@ -67,6 +67,11 @@ impl From<&ChangeEvent> for SsePayload {
                 run_id: None,
                 iter: None,
             },
+            ChangeEvent::LockChanged => SsePayload {
+                event_type: "lock_changed".to_string(),
+                run_id: None,
+                iter: None,
+            },
         }
     }
 }
@@ -128,10 +133,16 @@ async fn run_file_watcher(state: AppState) -> anyhow::Result<()> {
         notify::Config::default().with_poll_interval(Duration::from_millis(100)),
     )?;
 
-    // Watch .runner/state/ and .runner/iterations/
+    // Watch .runner/ (lock file only), .runner/state/ and .runner/iterations/
+    let runner_dir = state.runner_dir();
     let state_dir = state.state_dir();
     let iter_dir = state.iterations_dir();
 
+    if runner_dir.exists() {
+        watcher.watch(&runner_dir, RecursiveMode::NonRecursive)?;
+        info!(path = %runner_dir.display(), "watching runner directory");
+    }
+
     if state_dir.exists() {
         watcher.watch(&state_dir, RecursiveMode::Recursive)?;
         info!(path = %state_dir.display(), "watching state directory");
@@ -176,6 +187,7 @@ fn process_events(
     let mut config_changed = false;
     let mut assumptions_changed = false;
     let mut questions_changed = false;
+    let mut lock_changed = false;
     let mut new_iterations: Vec<(String, u32)> = Vec::new();
     let mut iteration_completions: std::collections::HashSet<(String, u32)> =
         std::collections::HashSet::new();
@@ -187,9 +199,19 @@ fn process_events(
     let config_path = state.config_path();
     let assumptions_path = state.assumptions_path();
     let questions_path = state.questions_path();
+    let lock_path = state.lock_path();
     let iter_dir = state.iterations_dir();
 
     for event in events {
+        // The lock file is released by deletion, so removals matter for it.
+        if matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        ) && event.paths.contains(&lock_path)
+        {
+            lock_changed = true;
+        }
+
         // Only care about create/modify events
         if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
             continue;
@@ -251,6 +273,10 @@ fn process_events(
         debug!("broadcasting questions change");
         let _ = state.event_tx.send(ChangeEvent::QuestionsChanged);
     }
+    if lock_changed {
+        debug!("broadcasting lock change");
+        let _ = state.event_tx.send(ChangeEvent::LockChanged);
+    }
 
     new_iterations.sort();
     for (run_id, iter) in new_iterations {
@@ -471,6 +497,25 @@ mod tests {
             assert_eq!(events.len(), 1);
             assert!(matches!(events[0], ChangeEvent::QuestionsChanged));
         }
+
+        #[test]
+        fn lock_file_create_and_delete_emit_lock_changed() {
+            let state = test_state();
+            let mut rx = state.event_tx.subscribe();
+            let mut known = HashSet::new();
+
+            let path = state.lock_path();
+            process_events(&state, &[create_event(path.clone())], &mut known);
+            process_events(&state, &[delete_event(path)], &mut known);
+
+            let events = collect_events(&mut rx);
+            assert_eq!(events.len(), 2);
+            assert!(
+                events
+                    .iter()
+                    .all(|ev| matches!(ev, ChangeEvent::LockChanged))
+            );
+        }
     }
 
     // =========================================================================
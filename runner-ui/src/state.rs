@@ -26,6 +26,8 @@ pub enum ChangeEvent {
     ConfigChanged,
     AssumptionsChanged,
     QuestionsChanged,
+    /// `.runner/runner.lock` was taken or released.
+    LockChanged,
 }
 
 /// Shared state accessible from all request handlers.
@@ -46,9 +48,14 @@ impl AppState {
         }
     }
 
+    /// Path to .runner/ directory.
+    pub fn runner_dir(&self) -> PathBuf {
+        self.project_dir.join(".runner")
+    }
+
     /// Path to .runner/state/ directory.
     pub fn state_dir(&self) -> PathBuf {
-        self.project_dir.join(".runner").join("state")
+        self.runner_dir().join("state")
     }
 
     /// Path to .runner/iterations/ directory.
@@ -76,6 +83,11 @@ impl AppState {
         self.state_dir().join("assumptions.md")
     }
 
+    /// Path to runner.lock.
+    pub fn lock_path(&self) -> PathBuf {
+        self.runner_dir().join(runner::io::lock::LOCK_FILE_NAME)
+    }
+
     /// Path to questions.md.
     pub fn questions_path(&self) -> PathBuf {
         self.state_dir().join("questions.md")
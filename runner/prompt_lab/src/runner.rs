@@ -9,6 +9,7 @@ use std::time::{Duration, Instant};
 use anyhow::{Context, Result};
 use runner::core::types::DecompositionOutput;
 use runner::io::executor::{CodexExecutor, ExecRequest, execute_and_load_json};
+use runner::io::interrupt::Interrupt;
 use runner::tree::NodeNext;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
@@ -256,6 +257,7 @@ fn run_combination(
         timeout: Duration::from_secs(120),
         output_limit_bytes: 100_000,
         stream_path: None,
+        interrupt: Interrupt::new(),
     };
 
     // Execute
@@ -7,18 +7,23 @@ use anyhow::Result;
 
 use crate::core::budget::remaining_budget;
 use crate::core::types::DecompositionOutput;
+use crate::io::config::PromptConfig;
 use crate::io::executor::{ExecRequest, Executor, execute_and_load_json};
-use crate::io::prompt::{PromptBuilder, PromptInputs};
+use crate::io::interrupt::Interrupt;
+use crate::io::prompt::{PromptBudget, PromptBuilder, PromptInputs, PromptTemplates};
 
-use super::write_output_schema;
+use super::{AgentRun, write_output_schema, write_prompt, write_prompt_budget};
 
 const DECOMPOSER_OUTPUT_SCHEMA: &str = include_str!("../../schemas/decomposer_output.schema.json");
 
 /// Configuration for a decomposer-agent invocation.
 #[derive(Debug, Clone)]
 pub struct DecomposerAgentConfig {
-    pub prompt_budget_bytes: usize,
+    pub prompt_budget: PromptBudget,
     pub output_limit_bytes: usize,
+    pub interrupt: Interrupt,
+    pub templates: PromptTemplates,
+    pub prompt_rules: PromptConfig,
 }
 
 /// Decomposer agent wrapper that owns schema and prompt settings.
@@ -33,12 +38,39 @@ impl DecomposerAgent {
         Self {
             schema_path: state_dir.join("decomposer_output.schema.json"),
             config: DecomposerAgentConfig {
-                prompt_budget_bytes,
+                prompt_budget: PromptBudget::bytes(prompt_budget_bytes),
                 output_limit_bytes,
+                interrupt: Interrupt::new(),
+                templates: PromptTemplates::embedded(),
+                prompt_rules: PromptConfig::default(),
             },
         }
     }
 
+    /// Share an interrupt handle so a forced interrupt terminates the agent process.
+    pub fn with_interrupt(mut self, interrupt: Interrupt) -> Self {
+        self.config.interrupt = interrupt;
+        self
+    }
+
+    /// Budget prompts with `budget` instead of the byte budget given to `new`.
+    pub fn with_prompt_budget(mut self, budget: PromptBudget) -> Self {
+        self.config.prompt_budget = budget;
+        self
+    }
+
+    /// Render prompts with `templates` (e.g. `.runner/prompts/` overrides).
+    pub fn with_templates(mut self, templates: PromptTemplates) -> Self {
+        self.config.templates = templates;
+        self
+    }
+
+    /// Apply `[prompt]` section caps, drop order and truncation strategies.
+    pub fn with_prompt_config(mut self, rules: PromptConfig) -> Self {
+        self.config.prompt_rules = rules;
+        self
+    }
+
     pub fn allows_side_effects(&self) -> bool {
         false
     }
@@ -50,25 +82,38 @@ impl DecomposerAgent {
         iter_dir: &Path,
         inputs: &PromptInputs,
         deadline: Instant,
-    ) -> Result<DecompositionOutput> {
+    ) -> Result<AgentRun<DecompositionOutput>> {
         write_output_schema(&self.schema_path, DECOMPOSER_OUTPUT_SCHEMA)?;
 
-        let prompt = PromptBuilder::new(self.config.prompt_budget_bytes)
-            .build_decomposer(inputs)
-            .render();
+        let render_start = Instant::now();
+        let pack = PromptBuilder::with_budget(self.config.prompt_budget)
+            .with_templates(self.config.templates.clone())
+            .with_rules(self.config.prompt_rules.clone())
+            .build_decomposer(inputs)?;
+        write_prompt_budget(iter_dir, "prompt_budget.json", pack.budget_report())?;
+        let prompt = pack.render();
+        write_prompt(iter_dir, "prompt.md", &prompt)?;
+        let prompt_render = render_start.elapsed();
 
         let request = ExecRequest {
             workdir: root.to_path_buf(),
             prompt,
             output_schema_path: self.schema_path.clone(),
-            output_path: iter_dir.join("planner_output.json"),
-            executor_log_path: iter_dir.join("planner_executor.log"),
+            output_path: iter_dir.join("decomposer_output.json"),
+            executor_log_path: iter_dir.join("decomposer_executor.log"),
             timeout: remaining_budget(deadline)?,
             output_limit_bytes: self.config.output_limit_bytes,
-            stream_path: Some(iter_dir.join("planner_stream.jsonl")),
+            stream_path: Some(iter_dir.join("decomposer_stream.jsonl")),
+            interrupt: self.config.interrupt.clone(),
         };
 
-        execute_and_load_json(executor, &request)
+        let exec_start = Instant::now();
+        let output = execute_and_load_json(executor, &request)?;
+        Ok(AgentRun {
+            output,
+            prompt_render,
+            exec: exec_start.elapsed(),
+        })
     }
 }
 
@@ -116,6 +161,9 @@ mod tests {
             context_goal: "goal".to_string(),
             context_history: None,
             context_failure: None,
+            context_diff: None,
+            notes: None,
+            repo_map: None,
             assumptions: String::new(),
             questions: String::new(),
         }
@@ -136,6 +184,7 @@ mod tests {
                 goal: "Child goal".to_string(),
                 acceptance: Vec::new(),
                 next: crate::tree::NodeNext::Execute,
+                depends_on: Vec::new(),
             }],
         };
         let executor = CapturingExecutor::new(output.clone());
@@ -151,11 +200,11 @@ mod tests {
             )
             .expect("run");
 
-        assert_eq!(got, output);
+        assert_eq!(got.output, output);
         assert!(agent.schema_path.exists());
         let request = executor.last_request.borrow().clone().expect("request");
         assert!(request.prompt.contains("Decomposer Contract"));
-        assert!(request.output_path.ends_with("planner_output.json"));
+        assert!(request.output_path.ends_with("decomposer_output.json"));
         assert!(!agent.allows_side_effects());
     }
 }
@@ -7,18 +7,23 @@ use anyhow::Result;
 
 use crate::core::budget::remaining_budget;
 use crate::core::types::AgentOutput;
+use crate::io::config::PromptConfig;
 use crate::io::executor::{ExecRequest, Executor, execute_and_load};
-use crate::io::prompt::{PromptBuilder, PromptInputs};
+use crate::io::interrupt::Interrupt;
+use crate::io::prompt::{PromptBudget, PromptBuilder, PromptInputs, PromptTemplates};
 
-use super::write_output_schema;
+use super::{AgentRun, write_output_schema, write_prompt, write_prompt_budget};
 
 const EXECUTOR_OUTPUT_SCHEMA: &str = include_str!("../../schemas/executor_output.schema.json");
 
 /// Configuration for an executor-agent invocation.
 #[derive(Debug, Clone)]
 pub struct ExecutorAgentConfig {
-    pub prompt_budget_bytes: usize,
+    pub prompt_budget: PromptBudget,
     pub output_limit_bytes: usize,
+    pub interrupt: Interrupt,
+    pub templates: PromptTemplates,
+    pub prompt_rules: PromptConfig,
 }
 
 /// Executor agent wrapper that owns schema and prompt settings.
@@ -33,12 +38,39 @@ impl ExecutorAgent {
         Self {
             schema_path: state_dir.join("executor_output.schema.json"),
             config: ExecutorAgentConfig {
-                prompt_budget_bytes,
+                prompt_budget: PromptBudget::bytes(prompt_budget_bytes),
                 output_limit_bytes,
+                interrupt: Interrupt::new(),
+                templates: PromptTemplates::embedded(),
+                prompt_rules: PromptConfig::default(),
             },
         }
     }
 
+    /// Share an interrupt handle so a forced interrupt terminates the agent process.
+    pub fn with_interrupt(mut self, interrupt: Interrupt) -> Self {
+        self.config.interrupt = interrupt;
+        self
+    }
+
+    /// Budget prompts with `budget` instead of the byte budget given to `new`.
+    pub fn with_prompt_budget(mut self, budget: PromptBudget) -> Self {
+        self.config.prompt_budget = budget;
+        self
+    }
+
+    /// Render prompts with `templates` (e.g. `.runner/prompts/` overrides).
+    pub fn with_templates(mut self, templates: PromptTemplates) -> Self {
+        self.config.templates = templates;
+        self
+    }
+
+    /// Apply `[prompt]` section caps, drop order and truncation strategies.
+    pub fn with_prompt_config(mut self, rules: PromptConfig) -> Self {
+        self.config.prompt_rules = rules;
+        self
+    }
+
     pub fn allows_side_effects(&self) -> bool {
         true
     }
@@ -51,12 +83,18 @@ impl ExecutorAgent {
         inputs: &PromptInputs,
         planner_notes: Option<&str>,
         deadline: Instant,
-    ) -> Result<AgentOutput> {
+    ) -> Result<AgentRun<AgentOutput>> {
         write_output_schema(&self.schema_path, EXECUTOR_OUTPUT_SCHEMA)?;
 
-        let prompt = PromptBuilder::new(self.config.prompt_budget_bytes)
-            .build_executor(inputs, planner_notes)
-            .render();
+        let render_start = Instant::now();
+        let pack = PromptBuilder::with_budget(self.config.prompt_budget)
+            .with_templates(self.config.templates.clone())
+            .with_rules(self.config.prompt_rules.clone())
+            .build_executor(inputs, planner_notes)?;
+        write_prompt_budget(iter_dir, "prompt_budget.json", pack.budget_report())?;
+        let prompt = pack.render();
+        write_prompt(iter_dir, "prompt.md", &prompt)?;
+        let prompt_render = render_start.elapsed();
 
         let request = ExecRequest {
             workdir: root.to_path_buf(),
@@ -67,9 +105,16 @@ impl ExecutorAgent {
             timeout: remaining_budget(deadline)?,
             output_limit_bytes: self.config.output_limit_bytes,
             stream_path: Some(iter_dir.join("stream.jsonl")),
+            interrupt: self.config.interrupt.clone(),
         };
 
-        execute_and_load(executor, &request)
+        let exec_start = Instant::now();
+        let output = execute_and_load(executor, &request)?;
+        Ok(AgentRun {
+            output,
+            prompt_render,
+            exec: exec_start.elapsed(),
+        })
     }
 }
 
@@ -118,6 +163,9 @@ mod tests {
             context_goal: "goal".to_string(),
             context_history: None,
             context_failure: None,
+            context_diff: None,
+            notes: None,
+            repo_map: None,
             assumptions: String::new(),
             questions: String::new(),
         }
@@ -134,6 +182,7 @@ mod tests {
         let output = AgentOutput {
             status: AgentStatus::Done,
             summary: "ok".to_string(),
+            follow_ups: Vec::new(),
         };
         let executor = CapturingExecutor::new(output.clone());
         let agent = ExecutorAgent::new(&state_dir, 1024, 2048);
@@ -149,10 +198,12 @@ mod tests {
             )
             .expect("run");
 
-        assert_eq!(got, output);
+        assert_eq!(got.output, output);
         assert!(agent.schema_path.exists());
         let request = executor.last_request.borrow().clone().expect("request");
         assert!(request.prompt.contains("Executor Contract"));
+        let recorded = fs::read_to_string(iter_dir.join("prompt.md")).expect("prompt.md");
+        assert_eq!(recorded, request.prompt);
         assert!(request.output_path.ends_with("output.json"));
         assert!(agent.allows_side_effects());
     }
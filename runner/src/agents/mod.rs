@@ -2,11 +2,25 @@
 
 use std::fs;
 use std::path::Path;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 
+use crate::io::prompt::PromptBudgetReport;
+
 pub mod decomposer;
 pub mod executor;
+pub mod planner;
+pub mod reviewer;
+pub mod verifier;
+
+/// Agent output plus where the invocation spent its time.
+#[derive(Debug, Clone)]
+pub struct AgentRun<T> {
+    pub output: T,
+    pub prompt_render: Duration,
+    pub exec: Duration,
+}
 
 pub(crate) fn write_output_schema(path: &Path, contents: &str) -> Result<()> {
     if let Some(parent) = path.parent() {
@@ -15,3 +29,23 @@ pub(crate) fn write_output_schema(path: &Path, contents: &str) -> Result<()> {
     }
     fs::write(path, contents).with_context(|| format!("write schema {}", path.display()))
 }
+
+/// Record how the prompt was budgeted in `<iter_dir>/<file_name>` (e.g. `prompt_budget.json`).
+pub(crate) fn write_prompt_budget(
+    iter_dir: &Path,
+    file_name: &str,
+    report: &PromptBudgetReport,
+) -> Result<()> {
+    fs::create_dir_all(iter_dir).with_context(|| format!("create {}", iter_dir.display()))?;
+    let path = iter_dir.join(file_name);
+    let mut contents = serde_json::to_string_pretty(report)?;
+    contents.push('\n');
+    fs::write(&path, contents).with_context(|| format!("write {}", path.display()))
+}
+
+/// Record the exact prompt given to the agent in `<iter_dir>/<file_name>` (e.g. `prompt.md`).
+pub(crate) fn write_prompt(iter_dir: &Path, file_name: &str, prompt: &str) -> Result<()> {
+    fs::create_dir_all(iter_dir).with_context(|| format!("create {}", iter_dir.display()))?;
+    let path = iter_dir.join(file_name);
+    fs::write(&path, prompt).with_context(|| format!("write {}", path.display()))
+}
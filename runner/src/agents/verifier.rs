@@ -0,0 +1,197 @@
+//! Goal verifier agent that checks a completed tree against the root goal.
+
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::Result;
+
+use crate::core::budget::remaining_budget;
+use crate::core::types::VerificationOutput;
+use crate::io::config::PromptConfig;
+use crate::io::executor::{ExecRequest, Executor, execute_and_load_json};
+use crate::io::interrupt::Interrupt;
+use crate::io::prompt::{PromptBudget, PromptBuilder, PromptInputs, PromptTemplates};
+
+use super::{AgentRun, write_output_schema, write_prompt, write_prompt_budget};
+
+const VERIFIER_OUTPUT_SCHEMA: &str = include_str!("../../schemas/verifier_output.schema.json");
+
+/// Configuration for a verifier-agent invocation.
+#[derive(Debug, Clone)]
+pub struct VerifierAgentConfig {
+    pub prompt_budget: PromptBudget,
+    pub output_limit_bytes: usize,
+    pub interrupt: Interrupt,
+    pub templates: PromptTemplates,
+    pub prompt_rules: PromptConfig,
+}
+
+/// Verifier agent wrapper that owns schema and prompt settings.
+///
+/// Files are prefixed with `verifier_`, like the other read-only agents.
+#[derive(Debug, Clone)]
+pub struct VerifierAgent {
+    schema_path: PathBuf,
+    config: VerifierAgentConfig,
+}
+
+impl VerifierAgent {
+    pub fn new(state_dir: &Path, prompt_budget_bytes: usize, output_limit_bytes: usize) -> Self {
+        Self {
+            schema_path: state_dir.join("verifier_output.schema.json"),
+            config: VerifierAgentConfig {
+                prompt_budget: PromptBudget::bytes(prompt_budget_bytes),
+                output_limit_bytes,
+                interrupt: Interrupt::new(),
+                templates: PromptTemplates::embedded(),
+                prompt_rules: PromptConfig::default(),
+            },
+        }
+    }
+
+    /// Share an interrupt handle so a forced interrupt terminates the agent process.
+    pub fn with_interrupt(mut self, interrupt: Interrupt) -> Self {
+        self.config.interrupt = interrupt;
+        self
+    }
+
+    /// Budget prompts with `budget` instead of the byte budget given to `new`.
+    pub fn with_prompt_budget(mut self, budget: PromptBudget) -> Self {
+        self.config.prompt_budget = budget;
+        self
+    }
+
+    /// Render prompts with `templates` (e.g. `.runner/prompts/` overrides).
+    pub fn with_templates(mut self, templates: PromptTemplates) -> Self {
+        self.config.templates = templates;
+        self
+    }
+
+    /// Apply `[prompt]` section caps, drop order and truncation strategies.
+    pub fn with_prompt_config(mut self, rules: PromptConfig) -> Self {
+        self.config.prompt_rules = rules;
+        self
+    }
+
+    pub fn allows_side_effects(&self) -> bool {
+        false
+    }
+
+    /// Output schema the runner writes to `.runner/state/` before each run, with its contents.
+    pub fn output_schema(&self) -> (&Path, &'static str) {
+        (&self.schema_path, VERIFIER_OUTPUT_SCHEMA)
+    }
+
+    pub fn run<E: Executor>(
+        &self,
+        executor: &E,
+        root: &Path,
+        iter_dir: &Path,
+        inputs: &PromptInputs,
+        deadline: Instant,
+    ) -> Result<AgentRun<VerificationOutput>> {
+        write_output_schema(&self.schema_path, VERIFIER_OUTPUT_SCHEMA)?;
+
+        let render_start = Instant::now();
+        let pack = PromptBuilder::with_budget(self.config.prompt_budget)
+            .with_templates(self.config.templates.clone())
+            .with_rules(self.config.prompt_rules.clone())
+            .build_verifier(inputs)?;
+        write_prompt_budget(
+            iter_dir,
+            "verifier_prompt_budget.json",
+            pack.budget_report(),
+        )?;
+        let prompt = pack.render();
+        write_prompt(iter_dir, "verifier_prompt.md", &prompt)?;
+        let prompt_render = render_start.elapsed();
+
+        let request = ExecRequest {
+            workdir: root.to_path_buf(),
+            prompt,
+            output_schema_path: self.schema_path.clone(),
+            output_path: iter_dir.join("verifier_output.json"),
+            executor_log_path: iter_dir.join("verifier_executor.log"),
+            timeout: remaining_budget(deadline)?,
+            output_limit_bytes: self.config.output_limit_bytes,
+            stream_path: Some(iter_dir.join("verifier_stream.jsonl")),
+            interrupt: self.config.interrupt.clone(),
+        };
+
+        let exec_start = Instant::now();
+        let output = execute_and_load_json(executor, &request)?;
+        Ok(AgentRun {
+            output,
+            prompt_render,
+            exec: exec_start.elapsed(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::TreeChildSpec;
+    use crate::test_support::{ScriptedExec, ScriptedExecutor, ScriptedOutput, node};
+    use crate::tree::NodeNext;
+    use std::fs;
+    use std::time::Duration;
+
+    #[test]
+    fn verifier_agent_runs_with_own_schema_and_files() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let state_dir = temp.path().join(".runner/state");
+        let iter_dir = temp.path().join(".runner/iterations/run-1/4");
+        fs::create_dir_all(&iter_dir).expect("iter dir");
+
+        let output = VerificationOutput {
+            approved: false,
+            summary: "README lacks usage".to_string(),
+            children: vec![TreeChildSpec {
+                title: "Document usage".to_string(),
+                goal: "Add a usage section".to_string(),
+                acceptance: vec!["README has a Usage heading".to_string()],
+                next: NodeNext::Execute,
+                depends_on: Vec::new(),
+            }],
+        };
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::VerificationOutput(output.clone()),
+            tree_update: None,
+        }]);
+        let agent = VerifierAgent::new(&state_dir, 4096, 2048);
+        let inputs = PromptInputs {
+            selected_path: "root".to_string(),
+            selected_node: node("root", 0),
+            tree_summary: "- root".to_string(),
+            context_goal: "goal".to_string(),
+            context_history: None,
+            context_failure: None,
+            context_diff: None,
+            notes: None,
+            repo_map: None,
+            assumptions: String::new(),
+            questions: String::new(),
+        };
+
+        let got = agent
+            .run(
+                &executor,
+                temp.path(),
+                &iter_dir,
+                &inputs,
+                Instant::now() + Duration::from_secs(5),
+            )
+            .expect("run");
+
+        assert_eq!(got.output, output);
+        assert!(state_dir.join("verifier_output.schema.json").exists());
+        let request = executor.last_request().expect("request");
+        assert!(request.prompt.contains("Verifier Contract"));
+        assert!(request.output_path.ends_with("verifier_output.json"));
+        let recorded = fs::read_to_string(iter_dir.join("verifier_prompt.md")).expect("prompt");
+        assert_eq!(recorded, request.prompt);
+        assert!(iter_dir.join("verifier_prompt_budget.json").exists());
+        assert!(!agent.allows_side_effects());
+    }
+}
@@ -3,6 +3,7 @@
 use std::time::{Duration, Instant};
 
 use anyhow::{Result, anyhow};
+use chrono::{DateTime, NaiveTime, TimeZone, Utc};
 
 /// Return the remaining time budget until the provided deadline.
 pub fn remaining_budget(deadline: Instant) -> Result<Duration> {
@@ -14,3 +15,80 @@ pub fn remaining_budget(deadline: Instant) -> Result<Duration> {
     }
     Ok(remaining)
 }
+
+/// Parse a `runner loop --deadline` value relative to `now`.
+///
+/// Accepts an RFC 3339 timestamp or a wall-clock `HH:MM` in `now`'s time zone; a clock time
+/// that has already passed today refers to tomorrow.
+pub fn parse_deadline<Tz: TimeZone>(value: &str, now: &DateTime<Tz>) -> Result<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(deadline) = DateTime::parse_from_rfc3339(value) {
+        return Ok(deadline.with_timezone(&Utc));
+    }
+    let time = NaiveTime::parse_from_str(value, "%H:%M").map_err(|_| {
+        anyhow!("invalid deadline {value:?} (expected RFC 3339 timestamp or HH:MM)")
+    })?;
+    let tz = now.timezone();
+    let mut date = now.date_naive();
+    // Two candidate days cover a clock time that already passed; a third covers the rare
+    // case where the time does not exist on the candidate day (DST gap).
+    for _ in 0..3 {
+        if let Some(candidate) = tz.from_local_datetime(&date.and_time(time)).earliest()
+            && candidate > *now
+        {
+            return Ok(candidate.with_timezone(&Utc));
+        }
+        date = date
+            .succ_opt()
+            .ok_or_else(|| anyhow!("deadline {value:?} is out of range"))?;
+    }
+    Err(anyhow!(
+        "deadline {value:?} does not exist in the local time zone"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::FixedOffset;
+
+    fn at(rfc3339: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(rfc3339).expect("timestamp")
+    }
+
+    #[test]
+    fn parse_deadline_accepts_rfc3339() {
+        let now = at("2026-03-01T10:00:00+01:00");
+        let deadline = parse_deadline("2026-03-01T18:30:00+01:00", &now).expect("parse");
+        assert_eq!(deadline, at("2026-03-01T17:30:00Z"));
+    }
+
+    #[test]
+    fn parse_deadline_clock_time_is_today_or_tomorrow() {
+        let now = at("2026-03-01T10:00:00+01:00");
+        assert_eq!(
+            parse_deadline("18:30", &now).expect("later today"),
+            at("2026-03-01T17:30:00Z")
+        );
+        assert_eq!(
+            parse_deadline("09:15", &now).expect("tomorrow"),
+            at("2026-03-02T08:15:00Z")
+        );
+        assert_eq!(
+            parse_deadline("10:00", &now).expect("now means tomorrow"),
+            at("2026-03-02T09:00:00Z")
+        );
+    }
+
+    #[test]
+    fn parse_deadline_rejects_garbage() {
+        let now = at("2026-03-01T10:00:00Z");
+        for value in ["", "tomorrow", "25:00", "2026-03-01"] {
+            let err = parse_deadline(value, &now).unwrap_err();
+            assert!(
+                err.to_string().contains("invalid deadline"),
+                "{value}: {err}"
+            );
+        }
+    }
+}
@@ -0,0 +1,326 @@
+//! Safety limits for decompositions.
+//!
+//! A decomposer can grow the tree without bound: deep chains of `decompose` nodes, huge
+//! fan-outs, or a "split" into a single child that restates its parent. These checks run on
+//! the proposed children before they are added, so violations can be returned to the agent as
+//! an explanation instead of growing the tree. They also reject `depends_on` indexes that do
+//! not name another proposed child, or that form a cycle.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::path::node_lineage;
+use crate::core::types::TreeChildSpec;
+use crate::tree::{Node, NodeNext};
+
+/// Limits on how far decomposition may grow the tree (`[decomposition]` in `config.toml`).
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct DecompositionLimits {
+    /// Deepest level new children may be created at; the root is depth 0.
+    pub max_depth: usize,
+    /// Children a single decomposition may add.
+    pub max_children: usize,
+    /// Nodes the whole tree may hold after a decomposition.
+    pub max_nodes: usize,
+    /// Consecutive levels whose children are all `next=decompose`.
+    pub max_decompose_levels: usize,
+}
+
+impl Default for DecompositionLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 6,
+            max_children: 10,
+            max_nodes: 200,
+            max_decompose_levels: 2,
+        }
+    }
+}
+
+/// Check `children` proposed for `selected_id` against `limits` and for degenerate splits.
+///
+/// Returns stable, agent-facing explanations; empty when the decomposition is acceptable.
+pub fn check_decomposition(
+    tree: &Node,
+    selected_id: &str,
+    children: &[TreeChildSpec],
+    limits: &DecompositionLimits,
+) -> Vec<String> {
+    let Some(lineage) = node_lineage(tree, selected_id) else {
+        return vec![format!("selected node '{selected_id}' not found")];
+    };
+    let selected = lineage[lineage.len() - 1];
+    let mut errors = Vec::new();
+
+    let depth = lineage.len();
+    if depth > limits.max_depth {
+        errors.push(format!(
+            "children of '{selected_id}' would be at depth {depth} (max_depth = {}); \
+             use next=execute and do the work directly",
+            limits.max_depth
+        ));
+    }
+    if children.len() > limits.max_children {
+        errors.push(format!(
+            "decomposition of '{selected_id}' has {} children (max_children = {}); \
+             group related work into fewer children",
+            children.len(),
+            limits.max_children
+        ));
+    }
+    let total = count_nodes(tree) + children.len();
+    if total > limits.max_nodes {
+        errors.push(format!(
+            "tree would hold {total} nodes (max_nodes = {}); use fewer, larger children",
+            limits.max_nodes
+        ));
+    }
+    if let [only] = children
+        && normalize(&only.goal) == normalize(&selected.goal)
+    {
+        errors.push(format!(
+            "single child restates the goal of '{selected_id}'; split it into distinct \
+             parts or use next=execute"
+        ));
+    }
+    let levels = decompose_levels(&lineage, children);
+    if levels > limits.max_decompose_levels {
+        errors.push(format!(
+            "{levels} consecutive levels of only next=decompose children above and including \
+             '{selected_id}' (max_decompose_levels = {}); give at least one child next=execute",
+            limits.max_decompose_levels
+        ));
+    }
+
+    errors.extend(check_sibling_dependencies(children));
+
+    errors
+}
+
+/// `depends_on` of proposed children must point at other children in the same list, without
+/// cycles.
+fn check_sibling_dependencies(children: &[TreeChildSpec]) -> Vec<String> {
+    let mut errors = Vec::new();
+    for (idx, child) in children.iter().enumerate() {
+        for &dep in &child.depends_on {
+            if dep == idx || dep >= children.len() {
+                errors.push(format!(
+                    "child {idx}: depends_on {dep} is not the index of another child \
+                     (0-based, {} children)",
+                    children.len()
+                ));
+            }
+        }
+    }
+    if !errors.is_empty() {
+        return errors;
+    }
+
+    // Settle children whose dependencies are settled until nothing changes; the rest wait on
+    // each other.
+    let mut settled = vec![false; children.len()];
+    loop {
+        let mut progressed = false;
+        for (idx, child) in children.iter().enumerate() {
+            if !settled[idx] && child.depends_on.iter().all(|&dep| settled[dep]) {
+                settled[idx] = true;
+                progressed = true;
+            }
+        }
+        if !progressed {
+            break;
+        }
+    }
+    let cyclic: Vec<String> = (0..children.len())
+        .filter(|&idx| !settled[idx])
+        .map(|idx| idx.to_string())
+        .collect();
+    if !cyclic.is_empty() {
+        errors.push(format!(
+            "children {} depend on each other in a cycle; order them with depends_on in \
+             one direction only",
+            cyclic.join(", ")
+        ));
+    }
+    errors
+}
+
+/// Consecutive all-`decompose` levels ending with the proposed children, counting up through
+/// the selected node's ancestors.
+fn decompose_levels(lineage: &[&Node], children: &[TreeChildSpec]) -> usize {
+    if !children
+        .iter()
+        .all(|child| child.next == NodeNext::Decompose)
+    {
+        return 0;
+    }
+    let mut levels = 1;
+    for parent in lineage[..lineage.len() - 1].iter().rev() {
+        if !parent
+            .children
+            .iter()
+            .all(|child| child.next == NodeNext::Decompose)
+        {
+            break;
+        }
+        levels += 1;
+    }
+    levels
+}
+
+fn count_nodes(node: &Node) -> usize {
+    1 + node.children.iter().map(count_nodes).sum::<usize>()
+}
+
+/// Case- and whitespace-insensitive form of a goal, for comparing restatements.
+fn normalize(goal: &str) -> String {
+    goal.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{node, node_with_children};
+
+    fn spec(goal: &str, next: NodeNext) -> TreeChildSpec {
+        TreeChildSpec {
+            title: goal.to_string(),
+            goal: goal.to_string(),
+            acceptance: Vec::new(),
+            next,
+            depends_on: Vec::new(),
+        }
+    }
+
+    fn decompose(mut node: Node) -> Node {
+        node.next = NodeNext::Decompose;
+        node
+    }
+
+    #[test]
+    fn accepts_a_normal_split() {
+        let tree = decompose(node("root", 0));
+        let children = [
+            spec("Parse input", NodeNext::Execute),
+            spec("Print output", NodeNext::Execute),
+        ];
+        let errors = check_decomposition(&tree, "root", &children, &Default::default());
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+
+    #[test]
+    fn rejects_depth_fan_out_and_size_violations() {
+        let tree = node_with_children(
+            "root",
+            0,
+            vec![node_with_children("a", 0, vec![node("a1", 0)])],
+        );
+        let limits = DecompositionLimits {
+            max_depth: 2,
+            max_children: 2,
+            max_nodes: 5,
+            max_decompose_levels: 2,
+        };
+        let children = [
+            spec("one", NodeNext::Execute),
+            spec("two", NodeNext::Execute),
+            spec("three", NodeNext::Execute),
+        ];
+        let errors = check_decomposition(&tree, "a1", &children, &limits);
+        assert_eq!(
+            errors,
+            [
+                "children of 'a1' would be at depth 3 (max_depth = 2); use next=execute and do \
+                 the work directly",
+                "decomposition of 'a1' has 3 children (max_children = 2); group related work \
+                 into fewer children",
+                "tree would hold 6 nodes (max_nodes = 5); use fewer, larger children",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_a_single_child_restating_the_parent() {
+        let mut tree = decompose(node("root", 0));
+        tree.goal = "Build the  CLI".to_string();
+        let errors = check_decomposition(
+            &tree,
+            "root",
+            &[spec("build the cli", NodeNext::Execute)],
+            &Default::default(),
+        );
+        assert_eq!(
+            errors,
+            [
+                "single child restates the goal of 'root'; split it into distinct parts or use \
+                 next=execute"
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_repeated_decompose_only_levels() {
+        let tree = decompose(node_with_children(
+            "root",
+            0,
+            vec![decompose(node_with_children(
+                "a",
+                0,
+                vec![decompose(node("a1", 0)), decompose(node("a2", 1))],
+            ))],
+        ));
+        let children = [
+            spec("x", NodeNext::Decompose),
+            spec("y", NodeNext::Decompose),
+        ];
+
+        let errors = check_decomposition(&tree, "a1", &children, &Default::default());
+        assert_eq!(
+            errors,
+            [
+                "3 consecutive levels of only next=decompose children above and including 'a1' \
+                 (max_decompose_levels = 2); give at least one child next=execute"
+            ]
+        );
+
+        let mixed = [spec("x", NodeNext::Decompose), spec("y", NodeNext::Execute)];
+        assert!(check_decomposition(&tree, "a1", &mixed, &Default::default()).is_empty());
+    }
+
+    #[test]
+    fn rejects_bad_and_cyclic_sibling_dependencies() {
+        let tree = decompose(node("root", 0));
+        let with_deps = |goal: &str, depends_on: Vec<usize>| TreeChildSpec {
+            depends_on,
+            ..spec(goal, NodeNext::Execute)
+        };
+
+        let ordered = [with_deps("api", vec![1]), with_deps("schema", Vec::new())];
+        assert!(check_decomposition(&tree, "root", &ordered, &Default::default()).is_empty());
+
+        let bad = [with_deps("api", vec![0]), with_deps("schema", vec![2])];
+        assert_eq!(
+            check_decomposition(&tree, "root", &bad, &Default::default()),
+            [
+                "child 0: depends_on 0 is not the index of another child (0-based, 2 children)",
+                "child 1: depends_on 2 is not the index of another child (0-based, 2 children)",
+            ]
+        );
+
+        let cyclic = [
+            with_deps("api", vec![1]),
+            with_deps("schema", vec![0]),
+            with_deps("docs", Vec::new()),
+        ];
+        assert_eq!(
+            check_decomposition(&tree, "root", &cyclic, &Default::default()),
+            [
+                "children 0, 1 depend on each other in a cycle; order them with depends_on in \
+                 one direction only"
+            ]
+        );
+    }
+}
@@ -1,17 +1,21 @@
 //! Semantic invariants not expressible via JSON Schema.
 
 use crate::tree::Node;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 /// Check semantic invariants not expressible in JSON Schema:
 /// - No duplicate ids
 /// - `max_attempts > 0`
 /// - `attempts <= max_attempts`
 /// - Children sorted by `(order, id)`
+/// - `depends_on` names existing nodes outside the node's own lineage, without cycles
 pub fn validate_invariants(root: &Node) -> Vec<String> {
     let mut errors = Vec::new();
     let mut seen = HashSet::new();
     validate_node(root, &mut seen, &mut errors, root.id.as_str());
+    if errors.is_empty() {
+        validate_dependencies(root, &mut errors);
+    }
     errors
 }
 
@@ -49,3 +53,165 @@ fn children_sorted(children: &[Node]) -> bool {
         (left.order, &left.id) <= (right.order, &right.id)
     })
 }
+
+/// A node waits for its dependencies (and everything below them) before its subtree is
+/// selected, and a parent passes only once its children do. A cycle through either kind of
+/// edge means the nodes on it can never be selected.
+fn validate_dependencies(root: &Node, errors: &mut Vec<String>) {
+    let mut nodes = HashMap::new();
+    let mut order = Vec::new();
+    index_nodes(root, root.id.clone(), Vec::new(), &mut nodes, &mut order);
+
+    let mut waits: HashMap<&str, Vec<&str>> = HashMap::new();
+    for id in &order {
+        let entry = &nodes[id];
+        let edges = waits.entry(id).or_default();
+        edges.extend(entry.node.children.iter().map(|child| child.id.as_str()));
+        edges.extend(entry.node.depends_on.iter().map(String::as_str));
+        for dep in &entry.node.depends_on {
+            let Some(target) = nodes.get(dep.as_str()) else {
+                errors.push(format!("{}: depends_on unknown id '{}'", entry.path, dep));
+                continue;
+            };
+            if dep == id {
+                errors.push(format!("{}: depends_on itself", entry.path));
+            } else if entry.lineage.contains(&dep.as_str()) {
+                errors.push(format!("{}: depends_on its ancestor '{}'", entry.path, dep));
+            } else if target.lineage.contains(id) {
+                errors.push(format!(
+                    "{}: depends_on its descendant '{}'",
+                    entry.path, dep
+                ));
+            }
+        }
+    }
+    if !errors.is_empty() {
+        return;
+    }
+    // Dependencies hold back the whole subtree, so descendants inherit their ancestors' edges.
+    for id in &order {
+        for ancestor in &nodes[id].lineage {
+            let deps = nodes[ancestor].node.depends_on.iter().map(String::as_str);
+            waits.entry(id).or_default().extend(deps);
+        }
+    }
+
+    let mut state = HashMap::new();
+    for id in order {
+        let mut stack = Vec::new();
+        if let Some(cycle) = find_cycle(id, &waits, &mut state, &mut stack) {
+            errors.push(format!("depends_on cycle: {}", cycle.join(" -> ")));
+            return;
+        }
+    }
+}
+
+struct IndexedNode<'a> {
+    node: &'a Node,
+    path: String,
+    /// Ancestor ids, root first.
+    lineage: Vec<&'a str>,
+}
+
+fn index_nodes<'a>(
+    node: &'a Node,
+    path: String,
+    lineage: Vec<&'a str>,
+    out: &mut HashMap<&'a str, IndexedNode<'a>>,
+    order: &mut Vec<&'a str>,
+) {
+    order.push(node.id.as_str());
+    let mut child_lineage = lineage.clone();
+    child_lineage.push(node.id.as_str());
+    for child in &node.children {
+        let child_path = format!("{}/{}", path, child.id);
+        index_nodes(child, child_path, child_lineage.clone(), out, order);
+    }
+    out.insert(
+        node.id.as_str(),
+        IndexedNode {
+            node,
+            path,
+            lineage,
+        },
+    );
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Visit {
+    InProgress,
+    Done,
+}
+
+/// Depth-first search from `id`; returns the ids along the first cycle found, closed by
+/// repeating its first id.
+fn find_cycle<'a>(
+    id: &'a str,
+    waits: &HashMap<&'a str, Vec<&'a str>>,
+    state: &mut HashMap<&'a str, Visit>,
+    stack: &mut Vec<&'a str>,
+) -> Option<Vec<String>> {
+    match state.get(id) {
+        Some(Visit::Done) => return None,
+        Some(Visit::InProgress) => {
+            let start = stack.iter().position(|entry| *entry == id).unwrap_or(0);
+            let mut cycle: Vec<String> = stack[start..].iter().map(|s| s.to_string()).collect();
+            cycle.push(id.to_string());
+            return Some(cycle);
+        }
+        None => {}
+    }
+    state.insert(id, Visit::InProgress);
+    stack.push(id);
+    let mut next: Vec<&str> = waits.get(id).cloned().unwrap_or_default();
+    next.dedup();
+    for target in next {
+        if let Some(cycle) = find_cycle(target, waits, state, stack) {
+            return Some(cycle);
+        }
+    }
+    stack.pop();
+    state.insert(id, Visit::Done);
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{leaf, node_with_children};
+
+    #[test]
+    fn dependencies_must_name_other_nodes_outside_the_lineage() {
+        let mut api = node_with_children("api", 0, vec![leaf("api.1", 0, false)]);
+        api.children[0].depends_on = vec!["api".to_string()];
+        let mut docs = leaf("docs", 1, false);
+        docs.depends_on = vec!["missing".to_string(), "docs".to_string()];
+        let tree = node_with_children("root", 0, vec![api, docs]);
+
+        assert_eq!(
+            validate_invariants(&tree),
+            [
+                "root/api/api.1: depends_on its ancestor 'api'",
+                "root/docs: depends_on unknown id 'missing'",
+                "root/docs: depends_on itself",
+            ]
+        );
+    }
+
+    #[test]
+    fn dependency_cycles_are_rejected() {
+        let mut api = node_with_children("api", 0, vec![leaf("api.1", 0, false)]);
+        api.children[0].depends_on = vec!["docs".to_string()];
+        let mut docs = leaf("docs", 1, false);
+        docs.depends_on = vec!["api".to_string()];
+        let tree = node_with_children("root", 0, vec![api, docs.clone()]);
+
+        assert_eq!(
+            validate_invariants(&tree),
+            ["depends_on cycle: api -> api.1 -> docs -> api"]
+        );
+
+        let acyclic = node_with_children("root", 0, vec![leaf("api", 0, false), docs]);
+        assert!(validate_invariants(&acyclic).is_empty());
+    }
+}
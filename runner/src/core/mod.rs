@@ -5,10 +5,13 @@
 
 pub mod budget;
 pub mod child_additions;
+pub mod decomposition;
 pub mod immutability;
 pub mod invariants;
 pub mod path;
 pub mod selector;
 pub mod state_update;
 pub mod status_validator;
+pub mod tokens;
+pub mod tree_summary;
 pub mod types;
@@ -1,4 +1,4 @@
-//! Helpers for rendering deterministic node paths.
+//! Helpers for locating nodes and rendering deterministic node paths.
 
 use crate::tree::Node;
 
@@ -11,6 +11,28 @@ pub fn node_path(root: &Node, target_id: &str) -> Option<String> {
     None
 }
 
+/// Find the node with `target_id` (depth-first, including `root`).
+pub fn find_node<'a>(root: &'a Node, target_id: &str) -> Option<&'a Node> {
+    if root.id == target_id {
+        return Some(root);
+    }
+    root.children
+        .iter()
+        .find_map(|child| find_node(child, target_id))
+}
+
+/// Nodes from `root` down to `target_id` (inclusive), or `None` if it is not in the tree.
+pub fn node_lineage<'a>(root: &'a Node, target_id: &str) -> Option<Vec<&'a Node>> {
+    if root.id == target_id {
+        return Some(vec![root]);
+    }
+    root.children.iter().find_map(|child| {
+        let mut lineage = node_lineage(child, target_id)?;
+        lineage.insert(0, root);
+        Some(lineage)
+    })
+}
+
 fn node_path_inner(node: &Node, target_id: &str, path: &mut Vec<String>) -> bool {
     path.push(node.id.clone());
     if node.id == target_id {
@@ -42,9 +64,37 @@ mod tests {
             passes: false,
             attempts: 0,
             max_attempts: 3,
+            blocked: None,
+            skipped: false,
+            depends_on: Vec::new(),
             children: Vec::new(),
         };
 
         assert_eq!(node_path(&root, "root"), Some("root".to_string()));
     }
+
+    #[test]
+    fn node_lineage_lists_ancestors_first() {
+        use crate::test_support::{leaf, node_with_children};
+        let tree = node_with_children(
+            "root",
+            0,
+            vec![node_with_children(
+                "root.1",
+                0,
+                vec![leaf("root.1.1", 0, false)],
+            )],
+        );
+        let ids = |target: &str| {
+            node_lineage(&tree, target).map(|nodes| {
+                nodes
+                    .iter()
+                    .map(|node| node.id.as_str())
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(ids("root.1.1"), Some(vec!["root", "root.1", "root.1.1"]));
+        assert_eq!(ids("root"), Some(vec!["root"]));
+        assert_eq!(ids("missing"), None);
+    }
 }
@@ -1,17 +1,34 @@
 //! Deterministic selection logic for the task tree.
 
+use std::collections::HashSet;
+
 use crate::tree::Node;
 
 /// Find the first leaf with `passes=false` via depth-first traversal.
 ///
-/// Returns `None` if all leaves pass (tree is complete).
-pub fn leftmost_open_leaf(node: &Node) -> Option<&Node> {
+/// Blocked and skipped nodes are passed over with their subtrees, and so are nodes whose
+/// `depends_on` has not settled yet (see [`settled_ids`]). Returns `None` if every remaining
+/// leaf passes (the tree is complete, or blocked; see [`blocked_nodes`]).
+pub fn leftmost_open_leaf(root: &Node) -> Option<&Node> {
+    let settled = settled_ids(root);
+    find_open_leaf(root, &settled)
+}
+
+fn find_open_leaf<'a>(node: &'a Node, settled: &HashSet<&str>) -> Option<&'a Node> {
+    if node.is_held()
+        || !node
+            .depends_on
+            .iter()
+            .all(|dep| settled.contains(dep.as_str()))
+    {
+        return None;
+    }
     if node.children.is_empty() {
         return if node.passes { None } else { Some(node) };
     }
 
     for child in &node.children {
-        if let Some(found) = leftmost_open_leaf(child) {
+        if let Some(found) = find_open_leaf(child, settled) {
             return Some(found);
         }
     }
@@ -19,6 +36,51 @@ pub fn leftmost_open_leaf(node: &Node) -> Option<&Node> {
     None
 }
 
+/// Ids that satisfy a dependency: nodes that pass, and nodes in skipped subtrees.
+pub fn settled_ids(root: &Node) -> HashSet<&str> {
+    let mut out = HashSet::new();
+    collect_settled(root, false, &mut out);
+    out
+}
+
+fn collect_settled<'a>(node: &'a Node, skipped: bool, out: &mut HashSet<&'a str>) {
+    let skipped = skipped || node.skipped;
+    if skipped || node.passes {
+        out.insert(node.id.as_str());
+    }
+    for child in &node.children {
+        collect_settled(child, skipped, out);
+    }
+}
+
+/// Blocked nodes outside skipped subtrees, in traversal order.
+pub fn blocked_nodes(node: &Node) -> Vec<&Node> {
+    let mut out = Vec::new();
+    collect_held(node, &mut out, |node| {
+        node.blocked.is_some() && !node.skipped
+    });
+    out
+}
+
+/// Skipped nodes, in traversal order (the subtrees below them are not listed).
+pub fn skipped_nodes(node: &Node) -> Vec<&Node> {
+    let mut out = Vec::new();
+    collect_held(node, &mut out, |node| node.skipped);
+    out
+}
+
+fn collect_held<'a>(node: &'a Node, out: &mut Vec<&'a Node>, matches: fn(&Node) -> bool) {
+    if matches(node) {
+        out.push(node);
+    }
+    if node.skipped {
+        return;
+    }
+    for child in &node.children {
+        collect_held(child, out, matches);
+    }
+}
+
 /// Returns true if a leaf is considered stuck (attempts exhausted, not passed).
 pub fn is_stuck(node: &Node) -> bool {
     !node.passes && node.attempts >= node.max_attempts
@@ -106,6 +168,57 @@ mod tests {
         );
     }
 
+    /// Verifies blocked and skipped subtrees are passed over and reported.
+    #[test]
+    fn held_nodes_are_not_selected() {
+        let mut blocked = leaf("blocked", 0, false);
+        blocked.blocked = Some("needs a decision".to_string());
+        let mut skipped = node_with_children("skipped", 1, vec![leaf("inner", 0, false)]);
+        skipped.skipped = true;
+        let tree = node_with_children("root", 0, vec![blocked, skipped, leaf("open", 2, false)]);
+
+        assert_eq!(
+            leftmost_open_leaf(&tree).map(|n| &n.id),
+            Some(&"open".into())
+        );
+        let ids = |nodes: Vec<&Node>| nodes.into_iter().map(|n| n.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(blocked_nodes(&tree)), ["blocked"]);
+        assert_eq!(ids(skipped_nodes(&tree)), ["skipped"]);
+
+        let mut done = tree.clone();
+        done.children[2].passes = true;
+        assert!(leftmost_open_leaf(&done).is_none());
+    }
+
+    /// Nodes wait for their `depends_on` (and so do their subtrees); skipped work counts as done.
+    #[test]
+    fn dependencies_gate_selection() {
+        let mut api = node_with_children("api", 0, vec![leaf("api.1", 0, false)]);
+        api.depends_on = vec!["schema".to_string()];
+        let mut docs = leaf("docs", 1, false);
+        docs.depends_on = vec!["api".to_string()];
+        let tree = node_with_children("root", 0, vec![api, docs, leaf("schema", 2, false)]);
+
+        assert_eq!(
+            leftmost_open_leaf(&tree).map(|n| &n.id),
+            Some(&"schema".into())
+        );
+
+        let mut schema_done = tree.clone();
+        schema_done.children[2].passes = true;
+        assert_eq!(
+            leftmost_open_leaf(&schema_done).map(|n| &n.id),
+            Some(&"api.1".into())
+        );
+
+        let mut api_skipped = schema_done.clone();
+        api_skipped.children[0].skipped = true;
+        assert_eq!(
+            leftmost_open_leaf(&api_skipped).map(|n| &n.id),
+            Some(&"docs".into())
+        );
+    }
+
     /// Verifies `is_stuck` edge cases beyond the basic "attempts maxed + not passed".
     ///
     /// Cases covered:
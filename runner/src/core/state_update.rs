@@ -1,13 +1,14 @@
 //! Runner-owned state transitions for task trees.
 //!
-//! The runner exclusively controls `passes`, `attempts`, and `next` fields—agents
-//! cannot set these directly. After each iteration:
+//! The runner exclusively controls `passes`, `attempts`, `next`, `depends_on`, `blocked` and
+//! `skipped` fields—agents cannot set these directly. After each iteration:
 //! 1. Runner resets these fields from the previous tree snapshot
 //! 2. Applies transition rules based on agent status and guard outcome
-//! 3. Derives parent `passes` from children (bottom-up propagation)
+//! 3. Derives parent `passes` from children (bottom-up propagation); skipped children count
+//!    as done, blocked children do not
 //!
-//! Note: New nodes (from decomposition) keep their `next` value since they weren't
-//! in the previous snapshot. Only existing nodes have their `next` restored.
+//! Note: New nodes (from decomposition) keep their `next` and `depends_on` values since they
+//! weren't in the previous snapshot. Only existing nodes have them restored.
 
 #![allow(dead_code)]
 
@@ -69,7 +70,8 @@ pub fn apply_state_updates(
                 debug!(node_id = %selected.id, attempts = selected.attempts, "incremented attempts on retry");
             }
         }
-        AgentStatus::Decomposed => {}
+        // The reason is recorded by `mark_blocked`; no attempt is consumed.
+        AgentStatus::Decomposed | AgentStatus::Blocked => {}
     }
 
     derive_internal_passes(next, &mut summary);
@@ -89,31 +91,80 @@ pub fn apply_state_updates(
     Ok(summary)
 }
 
-/// Indexed runner-owned fields: (passes, attempts, next).
-fn index_runner_owned(node: &Node) -> HashMap<String, (bool, u32, NodeNext)> {
+/// Mark `selected_id` blocked with the agent's `reason`, after `apply_state_updates`.
+pub fn mark_blocked(next: &mut Node, selected_id: &str, reason: &str) -> Result<(), String> {
+    let selected = find_node_mut(next, selected_id)
+        .ok_or_else(|| format!("selected node '{}' not found", selected_id))?;
+    selected.blocked = Some(reason.to_string());
+    debug!(node_id = %selected_id, "marked blocked");
+    Ok(())
+}
+
+/// Recompute parent `passes` from children, e.g. after `runner tree skip`.
+///
+/// Returns the ids of parents that newly pass.
+pub fn derive_passes(tree: &mut Node) -> Vec<String> {
+    let mut summary = StateUpdateSummary {
+        status: AgentStatus::Done,
+        guard_outcome: GuardOutcome::Skipped,
+        passes_set: Vec::new(),
+        attempts_incremented: Vec::new(),
+        derived_passes_set: Vec::new(),
+    };
+    derive_internal_passes(tree, &mut summary);
+    summary.derived_passes_set.sort();
+    summary.derived_passes_set
+}
+
+/// Runner-owned fields of one node.
+struct RunnerOwned {
+    passes: bool,
+    attempts: u32,
+    next: NodeNext,
+    depends_on: Vec<String>,
+    blocked: Option<String>,
+    skipped: bool,
+}
+
+fn index_runner_owned(node: &Node) -> HashMap<String, RunnerOwned> {
     let mut map = HashMap::new();
     index_runner_owned_inner(node, &mut map);
     map
 }
 
-fn index_runner_owned_inner(node: &Node, map: &mut HashMap<String, (bool, u32, NodeNext)>) {
-    map.insert(node.id.clone(), (node.passes, node.attempts, node.next));
+fn index_runner_owned_inner(node: &Node, map: &mut HashMap<String, RunnerOwned>) {
+    map.insert(
+        node.id.clone(),
+        RunnerOwned {
+            passes: node.passes,
+            attempts: node.attempts,
+            next: node.next,
+            depends_on: node.depends_on.clone(),
+            blocked: node.blocked.clone(),
+            skipped: node.skipped,
+        },
+    );
     for child in &node.children {
         index_runner_owned_inner(child, map);
     }
 }
 
-fn reset_runner_owned_fields(node: &mut Node, prev_state: &HashMap<String, (bool, u32, NodeNext)>) {
-    if let Some((passes, attempts, next)) = prev_state.get(&node.id) {
+fn reset_runner_owned_fields(node: &mut Node, prev_state: &HashMap<String, RunnerOwned>) {
+    if let Some(prev) = prev_state.get(&node.id) {
         // Existing node: restore all runner-owned fields from previous snapshot
-        node.passes = *passes;
-        node.attempts = *attempts;
-        node.next = *next;
+        node.passes = prev.passes;
+        node.attempts = prev.attempts;
+        node.next = prev.next;
+        node.depends_on = prev.depends_on.clone();
+        node.blocked = prev.blocked.clone();
+        node.skipped = prev.skipped;
     } else {
-        // New node (from decomposition): reset passes/attempts but keep `next`
-        // since it was set by the decomposer via TreeChildSpec
+        // New node (from decomposition): reset passes/attempts but keep `next` and
+        // `depends_on` since they were set by the decomposer via TreeChildSpec
         node.passes = false;
         node.attempts = 0;
+        node.blocked = None;
+        node.skipped = false;
     }
 
     for child in &mut node.children {
@@ -128,7 +179,7 @@ fn derive_internal_passes(node: &mut Node, summary: &mut StateUpdateSummary) ->
 
     let mut all_children_passed = true;
     for child in &mut node.children {
-        if !derive_internal_passes(child, summary) {
+        if !derive_internal_passes(child, summary) && !child.skipped {
             all_children_passed = false;
         }
     }
@@ -360,7 +411,7 @@ mod tests {
         assert!(summary.attempts_incremented.is_empty());
     }
 
-    /// Executor changes to `next` field are silently reverted for existing nodes.
+    /// Executor changes to `next` and `depends_on` are silently reverted for existing nodes.
     #[test]
     fn apply_state_updates_resets_next_field() {
         // Create prev with node "a" having next=execute
@@ -370,6 +421,7 @@ mod tests {
         // Simulate executor trying to change next to decompose
         let mut next = node_with_children("root", 0, vec![leaf("a", 0, false)]);
         next.children[0].next = NodeNext::Decompose;
+        next.children[0].depends_on = vec!["root".to_string()];
 
         let _ = apply_state_updates(
             &prev,
@@ -382,6 +434,7 @@ mod tests {
 
         // next field should be restored to original value (execute)
         assert_eq!(next.children[0].next, NodeNext::Execute);
+        assert!(next.children[0].depends_on.is_empty());
     }
 
     /// New nodes from decomposition keep their `next` value (set by decomposer).
@@ -406,4 +459,48 @@ mod tests {
         // New node's next field should be preserved (decompose)
         assert_eq!(next.children[1].next, NodeNext::Decompose);
     }
+
+    /// Skipped children count as done for their parent; blocked children do not.
+    #[test]
+    fn derive_passes_counts_skipped_but_not_blocked_children() {
+        let mut skipped = leaf("b", 1, false);
+        skipped.skipped = true;
+        let mut tree = node_with_children("root", 0, vec![leaf("a", 0, true), skipped]);
+
+        assert_eq!(derive_passes(&mut tree), ["root"]);
+        assert!(tree.passes);
+
+        tree.children[1].skipped = false;
+        tree.children[1].blocked = Some("needs credentials".to_string());
+        assert!(derive_passes(&mut tree).is_empty());
+        assert!(!tree.passes);
+    }
+
+    /// Blocked status consumes no attempt, and agents cannot set `blocked`/`skipped`.
+    #[test]
+    fn apply_state_updates_blocked_keeps_attempts_and_restores_holds() {
+        let prev = node_with_children("root", 0, vec![leaf("a", 0, false), leaf("b", 1, false)]);
+        let mut next = prev.clone();
+        next.children[1].skipped = true;
+        next.children[1].blocked = Some("agent edit".to_string());
+
+        let summary = apply_state_updates(
+            &prev,
+            &mut next,
+            "a",
+            AgentStatus::Blocked,
+            GuardOutcome::Skipped,
+        )
+        .expect("state update");
+        mark_blocked(&mut next, "a", "API key is not available").expect("mark blocked");
+
+        assert_eq!(next.children[0].attempts, 0);
+        assert_eq!(
+            next.children[0].blocked.as_deref(),
+            Some("API key is not available")
+        );
+        assert!(!next.children[1].skipped);
+        assert_eq!(next.children[1].blocked, None);
+        assert!(summary.attempts_incremented.is_empty());
+    }
 }
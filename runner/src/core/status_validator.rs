@@ -2,10 +2,11 @@
 //!
 //! Validates consistency between the agent's declared status and task-tree edits:
 //! - `decomposed` requires selected node gained children.
-//! - `done`/`retry` require selected node did not gain children.
+//! - `done`/`retry`/`blocked` require selected node did not gain children.
 
 #![allow(dead_code)]
 
+use crate::core::path::find_node;
 use crate::core::types::AgentStatus;
 use crate::tree::Node;
 
@@ -51,38 +52,26 @@ pub fn validate_status_invariants(
             "status=decomposed but selected node '{}' did not gain children (prev={}, next={})",
             selected_id, prev_children, next_children
         )),
-        AgentStatus::Done | AgentStatus::Retry if gained_children => errors.push(format!(
-            "status={} but selected node '{}' gained children (prev={}, next={})",
-            status_label(status),
-            selected_id,
-            prev_children,
-            next_children
-        )),
+        AgentStatus::Done | AgentStatus::Retry | AgentStatus::Blocked if gained_children => errors
+            .push(format!(
+                "status={} but selected node '{}' gained children (prev={}, next={})",
+                status_label(status),
+                selected_id,
+                prev_children,
+                next_children
+            )),
         _ => {}
     }
 
     errors
 }
 
-fn find_node<'a>(node: &'a Node, target_id: &str) -> Option<&'a Node> {
-    if node.id == target_id {
-        return Some(node);
-    }
-
-    for child in &node.children {
-        if let Some(found) = find_node(child, target_id) {
-            return Some(found);
-        }
-    }
-
-    None
-}
-
 fn status_label(status: AgentStatus) -> &'static str {
     match status {
         AgentStatus::Done => "done",
         AgentStatus::Retry => "retry",
         AgentStatus::Decomposed => "decomposed",
+        AgentStatus::Blocked => "blocked",
     }
 }
 
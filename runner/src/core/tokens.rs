@@ -0,0 +1,283 @@
+//! Token estimation strategies for prompt budgeting.
+//!
+//! Budgets are expressed in the units of a [`Tokenizer`]: raw bytes, whitespace-separated
+//! words, or BPE tokens (`o200k_base`, behind the `bpe` cargo feature). Only the BPE count
+//! matches what a model sees; words are a cheap, encoding-independent approximation.
+//! Truncation helpers cut text to a budget on char boundaries, keeping its head, tail or both.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+/// Counts how much of a budget a piece of text uses.
+pub trait TokenEstimator {
+    /// Number of budget units in `text`.
+    fn count(&self, text: &str) -> usize;
+}
+
+/// Selectable estimation strategy (`prompt_tokenizer` in `config.toml`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tokenizer {
+    Bytes,
+    #[default]
+    Words,
+    Bpe,
+}
+
+impl Tokenizer {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Tokenizer::Bytes => "bytes",
+            Tokenizer::Words => "words",
+            Tokenizer::Bpe => "bpe",
+        }
+    }
+
+    /// Whether this build can use the strategy.
+    pub fn is_available(self) -> bool {
+        match self {
+            Tokenizer::Bytes | Tokenizer::Words => true,
+            Tokenizer::Bpe => cfg!(feature = "bpe"),
+        }
+    }
+
+    /// The estimator for this strategy; errors for `bpe` without the `bpe` feature.
+    pub fn estimator(self) -> Result<Box<dyn TokenEstimator>> {
+        match self {
+            Tokenizer::Bytes => Ok(Box::new(ByteCount)),
+            Tokenizer::Words => Ok(Box::new(WordCount)),
+            #[cfg(feature = "bpe")]
+            Tokenizer::Bpe => Ok(Box::new(BpeCount)),
+            #[cfg(not(feature = "bpe"))]
+            Tokenizer::Bpe => Err(anyhow::anyhow!(
+                "prompt_tokenizer = \"bpe\" requires a runner built with the `bpe` feature"
+            )),
+        }
+    }
+}
+
+/// UTF-8 length in bytes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ByteCount;
+
+impl TokenEstimator for ByteCount {
+    fn count(&self, text: &str) -> usize {
+        text.len()
+    }
+}
+
+/// Number of whitespace-separated words.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WordCount;
+
+impl TokenEstimator for WordCount {
+    fn count(&self, text: &str) -> usize {
+        text.split_whitespace().count()
+    }
+}
+
+/// `o200k_base` BPE tokens.
+#[cfg(feature = "bpe")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BpeCount;
+
+#[cfg(feature = "bpe")]
+impl TokenEstimator for BpeCount {
+    fn count(&self, text: &str) -> usize {
+        tiktoken_rs::o200k_base_singleton()
+            .encode_with_special_tokens(text)
+            .len()
+    }
+}
+
+/// Which part of an over-long text to keep.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TruncateStrategy {
+    /// Keep the beginning (e.g. tree summaries).
+    #[default]
+    Head,
+    /// Keep the end (e.g. failure logs, where the error usually is).
+    Tail,
+    /// Keep both ends and elide the middle.
+    HeadTail,
+}
+
+const HEAD_MARKER: &str = "\n[truncated]";
+const TAIL_MARKER: &str = "[truncated]\n";
+const ELISION_MARKER: &str = "\n[... truncated ...]\n";
+
+/// Shorten `text` to at most `allowed` units, marking where text was removed.
+///
+/// Returns `text` unchanged when it already fits. The marker is omitted when it alone would
+/// not fit.
+pub fn truncate_to(
+    text: &str,
+    allowed: usize,
+    strategy: TruncateStrategy,
+    estimator: &dyn TokenEstimator,
+) -> String {
+    if estimator.count(text) <= allowed {
+        return text.to_string();
+    }
+    let marker = match strategy {
+        TruncateStrategy::Head => HEAD_MARKER,
+        TruncateStrategy::Tail => TAIL_MARKER,
+        TruncateStrategy::HeadTail => ELISION_MARKER,
+    };
+    let marker_len = estimator.count(marker);
+    if marker_len >= allowed {
+        return match strategy {
+            TruncateStrategy::Tail => fitting_suffix(text, allowed, estimator).to_string(),
+            _ => fitting_prefix(text, allowed, estimator).to_string(),
+        };
+    }
+    let room = allowed - marker_len;
+    match strategy {
+        TruncateStrategy::Head => format!("{}{marker}", fitting_prefix(text, room, estimator)),
+        TruncateStrategy::Tail => format!("{marker}{}", fitting_suffix(text, room, estimator)),
+        TruncateStrategy::HeadTail => {
+            let head = fitting_prefix(text, room / 2, estimator);
+            let tail = fitting_suffix(&text[head.len()..], room - estimator.count(head), estimator);
+            format!("{head}{marker}{tail}")
+        }
+    }
+}
+
+/// Longest prefix of `text`, ending on a char boundary, that fits in `allowed` units.
+///
+/// Assumes counts grow with the prefix length, which holds for bytes and words and is close
+/// enough for BPE.
+pub fn fitting_prefix<'a>(
+    text: &'a str,
+    allowed: usize,
+    estimator: &dyn TokenEstimator,
+) -> &'a str {
+    if estimator.count(text) <= allowed {
+        return text;
+    }
+    let boundaries: Vec<usize> = text
+        .char_indices()
+        .map(|(idx, _)| idx)
+        .chain(std::iter::once(text.len()))
+        .collect();
+    // boundaries[lo] always fits (the empty prefix counts 0); boundaries[hi] never does.
+    let (mut lo, mut hi) = (0, boundaries.len() - 1);
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if estimator.count(&text[..boundaries[mid]]) <= allowed {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    &text[..boundaries[lo]]
+}
+
+/// Longest suffix of `text`, starting on a char boundary, that fits in `allowed` units.
+///
+/// Same monotonicity assumption as [`fitting_prefix`].
+pub fn fitting_suffix<'a>(
+    text: &'a str,
+    allowed: usize,
+    estimator: &dyn TokenEstimator,
+) -> &'a str {
+    if estimator.count(text) <= allowed {
+        return text;
+    }
+    let boundaries: Vec<usize> = text.char_indices().map(|(idx, _)| idx).collect();
+    // Candidate starts, longest suffix first; the empty suffix (text.len()) always fits.
+    let (mut lo, mut hi) = (0, boundaries.len());
+    let start = |idx: usize| boundaries.get(idx).copied().unwrap_or(text.len());
+    // Invariant: suffix at start(lo) does not fit; suffix at start(hi) fits.
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if estimator.count(&text[start(mid)..]) <= allowed {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    &text[start(hi)..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_and_word_counts() {
+        let text = "grüße aus  Köln\n";
+        assert_eq!(ByteCount.count(text), 19);
+        assert_eq!(WordCount.count(text), 3);
+        assert_eq!(WordCount.count("   "), 0);
+    }
+
+    #[test]
+    fn fitting_prefix_respects_char_boundaries() {
+        assert_eq!(fitting_prefix("grüße", 3, &ByteCount), "gr");
+        assert_eq!(fitting_prefix("grüße", 4, &ByteCount), "grü");
+        assert_eq!(fitting_prefix("grüße", 100, &ByteCount), "grüße");
+        assert_eq!(fitting_prefix("one two three", 2, &WordCount), "one two ");
+        assert_eq!(fitting_prefix("one", 0, &ByteCount), "");
+    }
+
+    #[test]
+    fn fitting_suffix_respects_char_boundaries() {
+        assert_eq!(fitting_suffix("grüße", 3, &ByteCount), "ße");
+        assert_eq!(fitting_suffix("grüße", 2, &ByteCount), "e");
+        assert_eq!(fitting_suffix("grüße", 100, &ByteCount), "grüße");
+        assert_eq!(fitting_suffix("one two three", 2, &WordCount), " two three");
+        assert_eq!(fitting_suffix("one", 0, &ByteCount), "");
+    }
+
+    #[test]
+    fn truncate_strategies_keep_the_requested_end() {
+        let text = (1..=40)
+            .map(|n| format!("w{n}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let head = truncate_to(&text, 10, TruncateStrategy::Head, &WordCount);
+        assert!(head.starts_with("w1 w2 "), "{head}");
+        assert!(head.ends_with("[truncated]"), "{head}");
+        assert!(WordCount.count(&head) <= 10);
+
+        let tail = truncate_to(&text, 10, TruncateStrategy::Tail, &WordCount);
+        assert!(tail.starts_with("[truncated]"), "{tail}");
+        assert!(tail.ends_with("w39 w40"), "{tail}");
+        assert!(WordCount.count(&tail) <= 10);
+
+        let both = truncate_to(&text, 10, TruncateStrategy::HeadTail, &WordCount);
+        assert!(both.starts_with("w1 "), "{both}");
+        assert!(both.contains("[... truncated ...]"), "{both}");
+        assert!(both.ends_with("w40"), "{both}");
+        assert!(WordCount.count(&both) <= 10, "{both}");
+
+        assert_eq!(
+            truncate_to("short", 10, TruncateStrategy::Tail, &ByteCount),
+            "short"
+        );
+        assert_eq!(
+            truncate_to("abcdefghijklmnop", 5, TruncateStrategy::Tail, &ByteCount),
+            "lmnop"
+        );
+    }
+
+    #[test]
+    fn tokenizer_availability_matches_features() {
+        assert!(Tokenizer::Bytes.estimator().is_ok());
+        assert!(Tokenizer::Words.estimator().is_ok());
+        assert_eq!(
+            Tokenizer::Bpe.estimator().is_ok(),
+            Tokenizer::Bpe.is_available()
+        );
+    }
+
+    #[cfg(feature = "bpe")]
+    #[test]
+    fn bpe_counts_tokens() {
+        let count = BpeCount.count("hello world");
+        assert!((1..=3).contains(&count), "{count}");
+    }
+}
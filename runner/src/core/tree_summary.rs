@@ -0,0 +1,412 @@
+//! Focused tree summary for agent prompts.
+//!
+//! Instead of listing the first N nodes depth-first, the summary is built around the selected
+//! node: its ancestors with goals, the siblings along that path with titles and pass state
+//! (passed and unrelated subtrees collapsed to one line), and the open leaves that come next.
+//! Lines are kept by priority until the byte budget is used up, then printed in tree order.
+
+use std::collections::HashSet;
+
+use crate::core::selector::settled_ids;
+use crate::tree::{Node, NodeNext};
+
+/// Open leaves listed after the selected node.
+const UPCOMING_LIMIT: usize = 5;
+/// Goals longer than this are shortened.
+const GOAL_MAX_CHARS: usize = 200;
+
+/// Kept in this order when the budget is tight; lower values first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Priority {
+    Lineage,
+    Sibling,
+    AncestorSibling,
+    Upcoming,
+}
+
+struct Line {
+    priority: Priority,
+    text: String,
+}
+
+/// Summarize `tree` around `selected_id` in at most `max_bytes` bytes.
+pub fn summarize_tree(tree: &Node, selected_id: &str, max_bytes: usize) -> String {
+    let mut lines = vec![Line {
+        priority: Priority::Lineage,
+        text: "Legend: [x] passed, [ ] open, [>] selected, [!] blocked, [-] skipped".to_string(),
+    }];
+    outline(tree, selected_id, 0, &mut lines);
+    let upcoming = upcoming_open_leaves(tree, selected_id, UPCOMING_LIMIT);
+    if !upcoming.is_empty() {
+        lines.push(Line {
+            priority: Priority::Upcoming,
+            text: "\nUp next:".to_string(),
+        });
+        for node in upcoming {
+            lines.push(Line {
+                priority: Priority::Upcoming,
+                text: format!("- {} {}", node.id, node.title),
+            });
+        }
+    }
+    fit_lines(lines, max_bytes)
+}
+
+/// Push `node` and, if it is on the path to `selected_id`, its children. Returns whether
+/// `node` is on that path.
+fn outline(node: &Node, selected_id: &str, depth: usize, lines: &mut Vec<Line>) -> bool {
+    if !contains(node, selected_id) {
+        return false;
+    }
+    let indent = "  ".repeat(depth);
+    let mark = if node.id == selected_id {
+        "[>]"
+    } else {
+        marker(node)
+    };
+    lines.push(Line {
+        priority: Priority::Lineage,
+        text: format!("{indent}{mark} {} {}{}", node.id, node.title, state(node)),
+    });
+    lines.push(Line {
+        priority: Priority::Lineage,
+        text: format!("{indent}    goal: {}", shorten(&node.goal)),
+    });
+    let parent_of_selected = node.children.iter().any(|child| child.id == selected_id);
+    for child in &node.children {
+        if outline(child, selected_id, depth + 1, lines) {
+            continue;
+        }
+        lines.push(Line {
+            priority: if parent_of_selected {
+                Priority::Sibling
+            } else {
+                Priority::AncestorSibling
+            },
+            text: format!(
+                "{}  {} {} {}{}",
+                indent,
+                marker(child),
+                child.id,
+                child.title,
+                state(child)
+            ),
+        });
+    }
+    true
+}
+
+fn contains(node: &Node, id: &str) -> bool {
+    node.id == id || node.children.iter().any(|child| contains(child, id))
+}
+
+fn marker(node: &Node) -> &'static str {
+    if node.skipped {
+        "[-]"
+    } else if node.passes {
+        "[x]"
+    } else if node.blocked.is_some() {
+        "[!]"
+    } else {
+        "[ ]"
+    }
+}
+
+/// Parenthesized details worth showing for `node`, or an empty string.
+fn state(node: &Node) -> String {
+    let mut details = Vec::new();
+    if let Some(reason) = &node.blocked
+        && !node.passes
+        && !node.skipped
+    {
+        details.push(format!("blocked: {}", shorten(reason)));
+    }
+    if !node.depends_on.is_empty() {
+        details.push(format!("depends on: {}", node.depends_on.join(", ")));
+    }
+    if node.children.is_empty() {
+        let open = !node.passes && !node.is_held();
+        if open && node.next == NodeNext::Decompose {
+            details.push("to decompose".to_string());
+        }
+        if open && node.attempts > 0 {
+            details.push(format!("attempts {}/{}", node.attempts, node.max_attempts));
+        }
+    } else {
+        let counts = leaf_counts(node, false);
+        let done = if counts.skipped == 0 {
+            "passed"
+        } else {
+            "passed or skipped"
+        };
+        if counts.settled == counts.total {
+            details.push(format!("{} leaves, all {done}", counts.total));
+        } else {
+            details.push(format!("{}/{} leaves {done}", counts.settled, counts.total));
+        }
+    }
+    if details.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", details.join(", "))
+    }
+}
+
+/// Leaf tallies below a node; skipped leaves (and leaves in skipped subtrees) are settled like
+/// passed ones.
+#[derive(Default)]
+struct LeafCounts {
+    settled: usize,
+    skipped: usize,
+    total: usize,
+}
+
+fn leaf_counts(node: &Node, in_skipped: bool) -> LeafCounts {
+    let skipped = in_skipped || node.skipped;
+    if node.children.is_empty() {
+        return LeafCounts {
+            settled: usize::from(skipped || node.passes),
+            skipped: usize::from(skipped && !node.passes),
+            total: 1,
+        };
+    }
+    node.children
+        .iter()
+        .map(|child| leaf_counts(child, skipped))
+        .fold(LeafCounts::default(), |acc, child| LeafCounts {
+            settled: acc.settled + child.settled,
+            skipped: acc.skipped + child.skipped,
+            total: acc.total + child.total,
+        })
+}
+
+/// Open leaves after `selected_id` in selection (depth-first) order. Like the selector, this
+/// leaves out blocked and skipped subtrees and nodes whose `depends_on` has not settled.
+fn upcoming_open_leaves<'a>(tree: &'a Node, selected_id: &str, limit: usize) -> Vec<&'a Node> {
+    fn walk<'a>(
+        node: &'a Node,
+        selected_id: &str,
+        settled: &HashSet<&str>,
+        seen: &mut bool,
+        out: &mut Vec<&'a Node>,
+    ) {
+        if node.id == selected_id {
+            *seen = true;
+            return;
+        }
+        if node.is_held()
+            || !node
+                .depends_on
+                .iter()
+                .all(|dep| settled.contains(dep.as_str()))
+        {
+            return;
+        }
+        if node.children.is_empty() {
+            if *seen && !node.passes {
+                out.push(node);
+            }
+            return;
+        }
+        for child in &node.children {
+            walk(child, selected_id, settled, seen, out);
+        }
+    }
+    let settled = settled_ids(tree);
+    let mut seen = false;
+    let mut out = Vec::new();
+    walk(tree, selected_id, &settled, &mut seen, &mut out);
+    out.truncate(limit);
+    out
+}
+
+fn shorten(goal: &str) -> String {
+    let first_line = goal.lines().next().unwrap_or_default().trim();
+    let mut short: String = first_line.chars().take(GOAL_MAX_CHARS).collect();
+    if short.len() < first_line.len() || goal.trim().lines().count() > 1 {
+        short.push_str(" …");
+    }
+    short
+}
+
+/// Keep lines by priority while they fit in `max_bytes`, then join them in tree order.
+fn fit_lines(lines: Vec<Line>, max_bytes: usize) -> String {
+    let mut order: Vec<usize> = (0..lines.len()).collect();
+    order.sort_by_key(|&idx| lines[idx].priority);
+    let mut keep = vec![false; lines.len()];
+    let mut used = 0;
+    let mut omitted = 0;
+    for idx in order {
+        let cost = lines[idx].text.len() + 1;
+        if omitted == 0 && used + cost <= max_bytes {
+            keep[idx] = true;
+            used += cost;
+        } else {
+            omitted += 1;
+        }
+    }
+    let mut out: Vec<&str> = lines
+        .iter()
+        .zip(&keep)
+        .filter(|(_, keep)| **keep)
+        .map(|(line, _)| line.text.as_str())
+        .collect();
+    let note = format!("(summary cut to fit: {omitted} lines omitted)");
+    if omitted > 0 {
+        out.push(&note);
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{leaf, node_with_attempts, node_with_children};
+
+    fn sample_tree() -> Node {
+        let mut done = node_with_children(
+            "root.1",
+            0,
+            vec![leaf("root.1.1", 0, true), leaf("root.1.2", 1, true)],
+        );
+        done.passes = true;
+        let current = node_with_children(
+            "root.2",
+            1,
+            vec![
+                leaf("root.2.1", 0, true),
+                node_with_attempts("root.2.2", 1, 1, 3),
+                leaf("root.2.3", 2, false),
+            ],
+        );
+        let later = node_with_children("root.3", 2, vec![leaf("root.3.1", 0, false)]);
+        node_with_children("root", 0, vec![done, current, later])
+    }
+
+    #[test]
+    fn summary_focuses_on_the_selected_path() {
+        let summary = summarize_tree(&sample_tree(), "root.2.2", 10_000);
+        let expected = "\
+Legend: [x] passed, [ ] open, [>] selected, [!] blocked, [-] skipped
+[ ] root root title (3/6 leaves passed)
+    goal: root goal
+  [x] root.1 root.1 title (2 leaves, all passed)
+  [ ] root.2 root.2 title (1/3 leaves passed)
+      goal: root.2 goal
+    [x] root.2.1 root.2.1 title
+    [>] root.2.2 root.2.2 title (attempts 1/3)
+        goal: root.2.2 goal
+    [ ] root.2.3 root.2.3 title
+  [ ] root.3 root.3 title (0/1 leaves passed)
+
+Up next:
+- root.2.3 root.2.3 title
+- root.3.1 root.3.1 title";
+        assert_eq!(summary, expected);
+        assert!(
+            !summary.contains("root.1.1"),
+            "passed subtrees are collapsed"
+        );
+    }
+
+    /// Blocked and skipped nodes get their own markers, stay out of "Up next", and skipped
+    /// leaves count as settled.
+    #[test]
+    fn held_nodes_are_marked_and_not_upcoming() {
+        let mut blocked = leaf("root.2", 1, false);
+        blocked.blocked = Some("needs production access".to_string());
+        let mut skipped = leaf("root.3", 2, false);
+        skipped.skipped = true;
+        let mut skipped_leaf = leaf("root.4.2", 1, false);
+        skipped_leaf.skipped = true;
+        let mut parent =
+            node_with_children("root.4", 3, vec![leaf("root.4.1", 0, true), skipped_leaf]);
+        parent.passes = true;
+        let tree = node_with_children(
+            "root",
+            0,
+            vec![
+                leaf("root.1", 0, false),
+                blocked,
+                skipped,
+                parent,
+                leaf("root.5", 4, false),
+            ],
+        );
+
+        let summary = summarize_tree(&tree, "root.1", 10_000);
+        assert!(
+            summary.contains("  [!] root.2 root.2 title (blocked: needs production access)"),
+            "{summary}"
+        );
+        assert!(summary.contains("  [-] root.3 root.3 title\n"), "{summary}");
+        assert!(
+            summary.contains("  [x] root.4 root.4 title (2 leaves, all passed or skipped)"),
+            "{summary}"
+        );
+        assert!(
+            summary.contains("[ ] root root title (3/6 leaves passed or skipped)"),
+            "{summary}"
+        );
+        assert!(
+            summary.ends_with("Up next:\n- root.5 root.5 title"),
+            "{summary}"
+        );
+    }
+
+    /// Leaves waiting on unsettled dependencies stay out of "Up next", and every node line
+    /// shows its dependencies.
+    #[test]
+    fn dependencies_are_shown_and_gate_up_next() {
+        let mut waiting = leaf("root.2", 1, false);
+        waiting.depends_on = vec!["root.1".to_string()];
+        let mut ready = leaf("root.4", 3, false);
+        ready.depends_on = vec!["root.3".to_string()];
+        let tree = node_with_children(
+            "root",
+            0,
+            vec![
+                leaf("root.1", 0, false),
+                waiting,
+                leaf("root.3", 2, true),
+                ready,
+            ],
+        );
+
+        let summary = summarize_tree(&tree, "root.1", 10_000);
+        assert!(
+            summary.contains("  [ ] root.2 root.2 title (depends on: root.1)"),
+            "{summary}"
+        );
+        assert!(
+            summary.contains("  [ ] root.4 root.4 title (depends on: root.3)"),
+            "{summary}"
+        );
+        assert!(
+            summary.ends_with("Up next:\n- root.4 root.4 title"),
+            "{summary}"
+        );
+    }
+
+    #[test]
+    fn tight_budget_keeps_the_lineage_first() {
+        let summary = summarize_tree(&sample_tree(), "root.2.2", 260);
+        assert!(summary.contains("[>] root.2.2"), "{summary}");
+        assert!(summary.contains("goal: root.2 goal"));
+        assert!(!summary.contains("Up next"));
+        assert!(summary.ends_with("lines omitted)"), "{summary}");
+        // Everything but the omission note fits the budget.
+        let body = summary.rsplit_once('\n').unwrap().0;
+        assert!(body.len() <= 260);
+    }
+
+    #[test]
+    fn long_goals_are_shortened() {
+        assert_eq!(shorten("short"), "short");
+        assert_eq!(shorten("first\nsecond"), "first …");
+        assert_eq!(
+            shorten(&"x".repeat(300)).chars().count(),
+            GOAL_MAX_CHARS + 2
+        );
+    }
+}
@@ -16,6 +16,19 @@ pub enum AgentStatus {
     Done,
     Retry,
     Decomposed,
+    /// The node cannot be completed as specified; `summary` is the reason.
+    Blocked,
+}
+
+impl AgentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AgentStatus::Done => "done",
+            AgentStatus::Retry => "retry",
+            AgentStatus::Decomposed => "decomposed",
+            AgentStatus::Blocked => "blocked",
+        }
+    }
 }
 
 /// Minimal child specification produced by the decomposer agent.
@@ -29,6 +42,9 @@ pub struct TreeChildSpec {
     #[serde(default)]
     pub acceptance: Vec<String>,
     pub next: NodeNext,
+    /// Indexes of sibling specs in the same list that must pass before this one is selected.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<usize>,
 }
 
 /// Structured output produced by the decomposer agent.
@@ -38,11 +54,59 @@ pub struct DecompositionOutput {
     pub children: Vec<TreeChildSpec>,
 }
 
+/// Structured output produced by the planner agent before an `execute` leaf runs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanOutput {
+    /// Implementation plan handed to the executor.
+    pub plan: String,
+    /// Files the executor is expected to touch.
+    pub files: Vec<String>,
+}
+
+/// Verdict of the reviewer agent on a node's changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewVerdict {
+    Approve,
+    Reject,
+}
+
+impl ReviewVerdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewVerdict::Approve => "approve",
+            ReviewVerdict::Reject => "reject",
+        }
+    }
+}
+
+/// Structured output produced by the reviewer agent after guards pass.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewOutput {
+    pub verdict: ReviewVerdict,
+    /// Why the changes meet or miss the node's goal and acceptance criteria.
+    pub reasons: Vec<String>,
+}
+
+/// Structured output produced by the goal verifier once every leaf passes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationOutput {
+    /// The repo meets the root goal and acceptance criteria.
+    pub approved: bool,
+    pub summary: String,
+    /// Follow-up work for the gaps found; empty when approved.
+    pub children: Vec<TreeChildSpec>,
+}
+
 /// Structured output produced by an agent session.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AgentOutput {
     pub status: AgentStatus,
     pub summary: String,
+    /// Extra required work the executor discovered; added as open siblings after the selected
+    /// node once it passes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub follow_ups: Vec<TreeChildSpec>,
 }
 
 /// Result of running guards after an iteration.
@@ -61,6 +125,16 @@ pub enum GuardOutcome {
     Skipped,
 }
 
+impl GuardOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GuardOutcome::Pass => "pass",
+            GuardOutcome::Fail => "fail",
+            GuardOutcome::Skipped => "skipped",
+        }
+    }
+}
+
 /// Summary of runner-owned state updates applied after a step.
 ///
 /// Lists must be recorded in deterministic order (lexicographic node id) to
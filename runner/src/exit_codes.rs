@@ -4,7 +4,26 @@
 pub const OK: i32 = 0;
 /// Command failed due to invalid layout/config/tree/run identity or other errors.
 pub const INVALID: i32 = 1;
+/// `runner step` or `runner loop` stopped because the run reached `max_iterations`. Shares the
+/// value of [`INVALID`], which the limit exited with before it had a code of its own.
+pub const MAX_ITERATIONS: i32 = 1;
 /// `runner select` found no open leaf (tree complete).
 pub const COMPLETE: i32 = 2;
 /// `runner select` or `runner step` encountered a stuck leaf.
 pub const STUCK: i32 = 3;
+/// `runner loop` executed `--max-steps` steps.
+pub const MAX_STEPS: i32 = 4;
+/// `runner loop` stopped because the `--until-node` node passes.
+pub const NODE_PASSED: i32 = 5;
+/// `runner loop --stop-on-guard-fail` stopped after an iteration's guards failed.
+pub const GUARD_FAILED: i32 = 6;
+/// `runner loop` reached its `--deadline`.
+pub const DEADLINE: i32 = 7;
+/// `runner loop` stopped because the run exceeded `max_run_duration_secs`.
+pub const RUN_DURATION: i32 = 8;
+/// `runner loop` stopped because `runner stop` was requested.
+pub const STOP_REQUESTED: i32 = 9;
+/// `runner select`, `runner step` or `runner loop` found only blocked nodes left.
+pub const BLOCKED: i32 = 10;
+/// `runner step` or `runner loop` stopped because of SIGINT/SIGTERM (128 + SIGINT).
+pub const INTERRUPTED: i32 = 130;
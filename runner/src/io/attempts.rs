@@ -0,0 +1,261 @@
+//! Per-node attempt history at `.runner/state/attempts.jsonl`.
+//!
+//! `run_state.json` only remembers the last iteration. This sidecar keeps one record per
+//! attempt so a retried node can be shown what every earlier attempt tried and how it ended.
+//! Records are appended before the iteration commit, so a crash during the commit does not
+//! lose them; the file is gitignored like the iteration logs. Attempts that ended in a
+//! runner-internal error are recorded with [`AttemptRecord::error`] and left out of the
+//! prompt history. The iteration commit is not known yet when a record is appended;
+//! [`keep_landed_attempts`] looks it up from the commit trailers when records are read and
+//! drops records whose commit never landed (a crash in the commit, an interrupted iteration,
+//! `runner recover`), so a retried iteration number is not shown twice.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::core::tokens::{ByteCount, fitting_suffix};
+use crate::core::types::{AgentStatus, GuardOutcome, ReviewVerdict};
+use crate::io::git::Git;
+
+/// Lines kept from a guard log or agent error in [`AttemptRecord::failure`].
+const FAILURE_DIGEST_LINES: usize = 20;
+/// Byte cap for [`AttemptRecord::failure`].
+const FAILURE_DIGEST_BYTES: usize = 2_000;
+
+/// One line of `attempts.jsonl`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttemptRecord {
+    pub run_id: String,
+    pub node_id: String,
+    pub iter: u32,
+    pub status: AgentStatus,
+    pub guard: GuardOutcome,
+    /// Reviewer verdict, when the reviewer ran.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review: Option<ReviewVerdict>,
+    pub summary: String,
+    /// Tail of the guard log (guard failures), the review (rejections) or the agent error
+    /// (rejected output).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure: Option<String>,
+    /// Runner-internal error that ended the iteration (no node attempt consumed).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Iteration commit sha, filled by [`keep_landed_attempts`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+/// Path of the attempts sidecar for the repo at `root`.
+pub fn attempts_path(root: &Path) -> PathBuf {
+    root.join(".runner").join("state").join("attempts.jsonl")
+}
+
+/// Append `record` to `path`, creating the file if needed.
+pub fn append_attempt(path: &Path, record: &AttemptRecord) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("create {}", parent.display()))?;
+    }
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("open {}", path.display()))?;
+    file.write_all(line.as_bytes())
+        .with_context(|| format!("append {}", path.display()))
+}
+
+/// Records for `node_id` in run `run_id`, oldest first; empty when the file does not exist.
+pub fn read_node_attempts(path: &Path, run_id: &str, node_id: &str) -> Result<Vec<AttemptRecord>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err).with_context(|| format!("read {}", path.display())),
+    };
+    let mut records = Vec::new();
+    for (idx, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: AttemptRecord = serde_json::from_str(line)
+            .with_context(|| format!("parse {} line {}", path.display(), idx + 1))?;
+        if record.run_id == run_id && record.node_id == node_id {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+/// Keep the `records` whose iteration commit landed, with [`AttemptRecord::commit`] filled.
+///
+/// The commit is the one with the record's `Runner-Run`, `Runner-Node`, `Runner-Iter` and
+/// `Runner-Status` trailers. When an iteration number was retried after its commit was lost,
+/// only the last record for it is kept.
+pub fn keep_landed_attempts(git: &Git, records: Vec<AttemptRecord>) -> Result<Vec<AttemptRecord>> {
+    let mut landed: Vec<AttemptRecord> = Vec::new();
+    for mut record in records.into_iter().rev() {
+        if landed.iter().any(|kept| kept.iter == record.iter) {
+            continue;
+        }
+        let iter = record.iter.to_string();
+        let Some(commit) = git.last_commit_with_trailers(&[
+            ("Runner-Run", &record.run_id),
+            ("Runner-Node", &record.node_id),
+            ("Runner-Iter", &iter),
+            ("Runner-Status", record.status.as_str()),
+        ])?
+        else {
+            continue;
+        };
+        record.commit = Some(commit.sha);
+        landed.push(record);
+    }
+    landed.reverse();
+    Ok(landed)
+}
+
+/// Last lines of `text`, bounded in size, for [`AttemptRecord::failure`].
+pub fn failure_digest(text: &str) -> Option<String> {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    if lines.is_empty() {
+        return None;
+    }
+    let tail = lines[lines.len().saturating_sub(FAILURE_DIGEST_LINES)..].join("\n");
+    Some(fitting_suffix(&tail, FAILURE_DIGEST_BYTES, &ByteCount).to_string())
+}
+
+/// Render the last `limit` of `records` (oldest first) for the prompt's history section.
+///
+/// Records with a runner [`error`](AttemptRecord::error) are skipped: those failures are not
+/// the node agent's to fix.
+pub fn render_attempt_history(records: &[AttemptRecord], limit: usize) -> Option<String> {
+    let records: Vec<&AttemptRecord> = records
+        .iter()
+        .filter(|record| record.error.is_none())
+        .collect();
+    let shown = &records[records.len().saturating_sub(limit)..];
+    if shown.is_empty() {
+        return None;
+    }
+    let first_number = records.len() - shown.len() + 1;
+    let mut buf = String::new();
+    for (offset, record) in shown.iter().enumerate() {
+        if !buf.is_empty() {
+            buf.push('\n');
+        }
+        buf.push_str(&format!(
+            "Attempt {} (iter {}, status={}, guard={}",
+            first_number + offset,
+            record.iter,
+            record.status.as_str(),
+            record.guard.as_str()
+        ));
+        if let Some(review) = record.review {
+            buf.push_str(&format!(", review={}", review.as_str()));
+        }
+        if let Some(commit) = &record.commit {
+            buf.push_str(&format!(", commit {}", commit.get(..12).unwrap_or(commit)));
+        }
+        buf.push_str("):\n");
+        let summary = record.summary.trim();
+        buf.push_str(if summary.is_empty() {
+            "(no summary)"
+        } else {
+            summary
+        });
+        buf.push('\n');
+        if let Some(failure) = &record.failure {
+            buf.push_str(&format!("Failure:\n{}\n", failure.trim_end()));
+        }
+    }
+    Some(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(node_id: &str, iter: u32, summary: &str) -> AttemptRecord {
+        AttemptRecord {
+            run_id: "run-1".to_string(),
+            node_id: node_id.to_string(),
+            iter,
+            status: AgentStatus::Retry,
+            guard: GuardOutcome::Skipped,
+            review: None,
+            summary: summary.to_string(),
+            failure: None,
+            error: None,
+            commit: None,
+        }
+    }
+
+    #[test]
+    fn appends_and_filters_by_run_and_node() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let path = attempts_path(temp.path());
+        assert!(
+            read_node_attempts(&path, "run-1", "a")
+                .expect("read")
+                .is_empty()
+        );
+
+        append_attempt(&path, &record("a", 1, "first")).expect("append");
+        append_attempt(&path, &record("b", 2, "other")).expect("append");
+        let mut other_run = record("a", 1, "old run");
+        other_run.run_id = "run-0".to_string();
+        append_attempt(&path, &other_run).expect("append");
+        append_attempt(&path, &record("a", 3, "second")).expect("append");
+
+        let got = read_node_attempts(&path, "run-1", "a").expect("read");
+        assert_eq!(got, vec![record("a", 1, "first"), record("a", 3, "second")]);
+    }
+
+    #[test]
+    fn failure_digest_keeps_the_tail() {
+        let log = (1..=50)
+            .map(|n| format!("line {n}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let digest = failure_digest(&log).expect("digest");
+        assert!(digest.starts_with("line 31\n"), "{digest}");
+        assert!(digest.ends_with("line 50"));
+        assert_eq!(failure_digest("\n  \n"), None);
+    }
+
+    #[test]
+    fn history_renders_the_last_attempts() {
+        let mut failed = record("a", 4, "tests fail");
+        failed.status = AgentStatus::Done;
+        failed.guard = GuardOutcome::Fail;
+        failed.failure = Some("assertion failed".to_string());
+        failed.commit = Some("0123456789abcdef".to_string());
+        let mut errored = record("a", 5, "(runner error)");
+        errored.error = Some("write tree.json: disk full".to_string());
+        let records = vec![
+            record("a", 1, "one"),
+            record("a", 2, "two"),
+            failed,
+            errored,
+        ];
+
+        let text = render_attempt_history(&records, 2).expect("history");
+        assert!(!text.contains("Attempt 1"));
+        assert!(text.starts_with("Attempt 2 (iter 2, status=retry, guard=skipped):\ntwo\n"));
+        assert!(text.contains(
+            "Attempt 3 (iter 4, status=done, guard=fail, commit 0123456789ab):\ntests fail\n\
+             Failure:\nassertion failed\n"
+        ));
+        assert!(!text.contains("disk full"));
+        assert_eq!(render_attempt_history(&records, 0), None);
+    }
+}
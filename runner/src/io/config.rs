@@ -1,11 +1,15 @@
 //! Runner configuration stored under `.runner/state/config.toml`.
 
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 
 use anyhow::{Context, Result, anyhow};
 use serde::{Deserialize, Serialize};
 
+use crate::core::decomposition::DecompositionLimits;
+use crate::core::tokens::{Tokenizer, TruncateStrategy};
+
 /// Runner configuration (TOML).
 ///
 /// This file is intended to be edited by humans and must remain stable and
@@ -26,13 +30,50 @@ pub struct RunnerConfig {
     /// Total per-iteration wall-clock budget in seconds (agent + guards).
     pub iteration_timeout_secs: u64,
 
+    /// Wall-clock budget for the whole run, measured from `runner start`.
+    ///
+    /// `runner loop` does not start another iteration once it is used up. Unset means no limit.
+    pub max_run_duration_secs: Option<u64>,
+
     /// Truncate executor stdout/stderr logs beyond this many bytes.
     pub executor_output_limit_bytes: usize,
 
     /// Truncate guard stdout/stderr logs beyond this many bytes.
     pub guard_output_limit_bytes: usize,
 
+    /// Prompt size limit in `prompt_tokenizer` units.
+    ///
+    /// When set, this replaces the `--prompt-budget` byte budget.
+    pub prompt_budget_tokens: Option<usize>,
+
+    /// How `prompt_budget_tokens` is counted: `bytes`, `words` or `bpe` (needs the `bpe` feature).
+    pub prompt_tokenizer: Tokenizer,
+
+    pub prompt: PromptConfig,
+
+    /// Earlier attempts on the selected node shown in the prompt's history section.
+    pub history_attempts: usize,
+
+    /// Size limit in bytes for the tree summary around the selected node.
+    pub tree_summary_bytes: usize,
+
+    pub retry_diff: RetryDiffConfig,
+
+    pub repo_map: RepoMapConfig,
+
+    pub planner: PlannerConfig,
+
+    pub reviewer: ReviewerConfig,
+
+    pub verifier: VerifierConfig,
+
+    pub decomposition: DecompositionLimits,
+
     pub guard: GuardConfig,
+
+    pub hooks: HooksConfig,
+
+    pub notify: NotifyConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -42,6 +83,245 @@ pub struct GuardConfig {
     pub command: Vec<String>,
 }
 
+/// How prompt sections are fitted into the prompt budget.
+///
+/// Section names are the `section:<name>` markers of the prompt templates (`contract`, `goal`,
+/// `notes`, `history`, `diff`, `failure`, `selected`, `tree`, `repo_map`, `assumptions`, `questions`,
+/// `planner`, `changes`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct PromptConfig {
+    /// Droppable sections removed, in this order, while the prompt is over budget.
+    pub drop_order: Vec<String>,
+    /// Per-section size caps and truncation strategies.
+    pub sections: BTreeMap<String, SectionLimits>,
+}
+
+/// Limits for one prompt section (`[prompt.sections.<name>]`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct SectionLimits {
+    /// Cap in prompt budget units, applied before the overall budget.
+    pub max: Option<usize>,
+    /// What to keep when the section is cut: `head`, `tail` or `head_tail`.
+    ///
+    /// Defaults to `tail` for `failure` (the error is usually at the end) and `head` otherwise.
+    pub truncate: Option<TruncateStrategy>,
+}
+
+impl Default for PromptConfig {
+    fn default() -> Self {
+        Self {
+            drop_order: [
+                "repo_map",
+                "tree",
+                "assumptions",
+                "questions",
+                "diff",
+                "history",
+                "notes",
+                "failure",
+                "planner",
+            ]
+            .map(String::from)
+            .to_vec(),
+            sections: BTreeMap::new(),
+        }
+    }
+}
+
+impl PromptConfig {
+    /// Configured cap for `section`, if any.
+    pub fn max(&self, section: &str) -> Option<usize> {
+        self.sections.get(section).and_then(|limits| limits.max)
+    }
+
+    /// Truncation strategy for `section`, falling back to the per-section default.
+    pub fn truncate(&self, section: &str) -> TruncateStrategy {
+        self.sections
+            .get(section)
+            .and_then(|limits| limits.truncate)
+            .unwrap_or(match section {
+                "failure" => TruncateStrategy::Tail,
+                _ => TruncateStrategy::Head,
+            })
+    }
+}
+
+/// Diff of the previous attempt on a node, shown to the agent when the node is retried.
+///
+/// Paths are git glob pathspecs relative to the repo root; `.runner/` is always excluded.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RetryDiffConfig {
+    pub enabled: bool,
+    /// Cut the diff to this many bytes before it reaches the prompt budget.
+    pub max_bytes: usize,
+    /// Only show changes under these paths; empty means the whole repo.
+    pub include: Vec<String>,
+    /// Hide changes under these paths (e.g. lockfiles).
+    pub exclude: Vec<String>,
+}
+
+impl Default for RetryDiffConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_bytes: 20_000,
+            include: Vec::new(),
+            exclude: vec!["**/*.lock".to_string()],
+        }
+    }
+}
+
+/// Runner-computed map of the repository (tracked files, layout, recent changes) for prompts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RepoMapConfig {
+    pub enabled: bool,
+    /// Cut the map to this many bytes before it reaches the prompt budget.
+    pub max_bytes: usize,
+    /// Tracked files listed with their sizes; the layout outline always covers every file.
+    pub max_files: usize,
+    /// Latest commits touching paths outside `.runner/` whose changed files are listed.
+    pub recent_commits: usize,
+}
+
+impl Default for RepoMapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_bytes: 8_000,
+            max_files: 300,
+            recent_commits: 10,
+        }
+    }
+}
+
+/// Read-only planning phase that runs before the executor on `execute` leaves.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct PlannerConfig {
+    /// Plan every `execute` leaf.
+    pub enabled: bool,
+    /// Plan the leaves at or under these node ids, even when `enabled` is off.
+    pub nodes: Vec<String>,
+}
+
+impl PlannerConfig {
+    /// Whether the leaf whose path from the root is `lineage_ids` gets a planning phase.
+    pub fn applies_to<'a>(&self, mut lineage_ids: impl Iterator<Item = &'a str>) -> bool {
+        self.enabled || lineage_ids.any(|id| self.nodes.iter().any(|node| node == id))
+    }
+}
+
+/// Reviewer agent that approves or rejects a node's changes after its guards pass.
+///
+/// Paths are git glob pathspecs relative to the repo root; `.runner/` is always excluded.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ReviewerConfig {
+    pub enabled: bool,
+    /// Cut the reviewed diff to this many bytes before it reaches the prompt budget.
+    pub max_diff_bytes: usize,
+    /// Hide changes under these paths (e.g. lockfiles).
+    pub exclude: Vec<String>,
+}
+
+impl Default for ReviewerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_diff_bytes: 40_000,
+            exclude: vec!["**/*.lock".to_string()],
+        }
+    }
+}
+
+/// Goal verification once every leaf passes: the tree is complete only after the verifier
+/// approves it against the root goal.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct VerifierConfig {
+    pub enabled: bool,
+    /// Verification rounds before the root counts as stuck; each round that finds gaps adds
+    /// follow-up children under the root.
+    pub max_rounds: u32,
+}
+
+impl Default for VerifierConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_rounds: 2,
+        }
+    }
+}
+
+/// Lifecycle hook commands. An empty command disables that hook.
+///
+/// Each hook receives a JSON payload on stdin; see `io::hooks`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// Per-hook wall-clock timeout in seconds.
+    pub timeout_secs: u64,
+    /// Runs after the leaf is selected, before the agent starts.
+    pub pre_step: Vec<String>,
+    /// Runs after the iteration is committed.
+    pub post_step: Vec<String>,
+    /// Runs after an iteration marks its node as passed.
+    pub on_node_passed: Vec<String>,
+    /// Runs after an iteration leaves the next selected leaf stuck.
+    pub on_stuck: Vec<String>,
+    /// Runs after an iteration completes the tree.
+    pub on_complete: Vec<String>,
+    /// Runs after an iteration uses up `max_iterations` with work remaining.
+    pub on_limit: Vec<String>,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 60,
+            pre_step: Vec::new(),
+            post_step: Vec::new(),
+            on_node_passed: Vec::new(),
+            on_stuck: Vec::new(),
+            on_complete: Vec::new(),
+            on_limit: Vec::new(),
+        }
+    }
+}
+
+/// Webhook notifications for run events. Disabled unless `url` is set.
+///
+/// The HMAC secret is read from the environment variable named by `secret_env` so it never
+/// lands in the committed config; see `io::notify`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct NotifyConfig {
+    /// Endpoint receiving `POST` requests with a JSON event body.
+    pub url: Option<String>,
+    /// Environment variable holding the HMAC-SHA256 signing secret.
+    pub secret_env: String,
+    /// Per-request timeout in seconds.
+    pub timeout_secs: u64,
+    /// Extra delivery attempts after a failed request.
+    pub retries: u32,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            secret_env: "RUNNER_NOTIFY_SECRET".to_string(),
+            timeout_secs: 10,
+            retries: 3,
+        }
+    }
+}
+
 impl Default for GuardConfig {
     fn default() -> Self {
         Self {
@@ -56,9 +336,23 @@ impl Default for RunnerConfig {
             max_attempts_default: 3,
             max_iterations: 2,
             iteration_timeout_secs: 30 * 60,
+            max_run_duration_secs: None,
             executor_output_limit_bytes: 100_000,
             guard_output_limit_bytes: 100_000,
+            prompt_budget_tokens: None,
+            prompt_tokenizer: Tokenizer::default(),
+            prompt: PromptConfig::default(),
+            history_attempts: 3,
+            tree_summary_bytes: 6_000,
+            retry_diff: RetryDiffConfig::default(),
+            repo_map: RepoMapConfig::default(),
+            planner: PlannerConfig::default(),
+            reviewer: ReviewerConfig::default(),
+            verifier: VerifierConfig::default(),
+            decomposition: DecompositionLimits::default(),
             guard: GuardConfig::default(),
+            hooks: HooksConfig::default(),
+            notify: NotifyConfig::default(),
         }
     }
 }
@@ -71,19 +365,129 @@ impl RunnerConfig {
         if self.max_iterations == 0 {
             return Err(anyhow!("max_iterations must be > 0"));
         }
+        if self.max_run_duration_secs == Some(0) {
+            return Err(anyhow!("max_run_duration_secs must be > 0 when set"));
+        }
         if self.executor_output_limit_bytes == 0 {
             return Err(anyhow!("executor_output_limit_bytes must be > 0"));
         }
         if self.guard_output_limit_bytes == 0 {
             return Err(anyhow!("guard_output_limit_bytes must be > 0"));
         }
+        if self.prompt_budget_tokens == Some(0) {
+            return Err(anyhow!("prompt_budget_tokens must be > 0 when set"));
+        }
+        if !self.prompt_tokenizer.is_available() {
+            return Err(anyhow!(
+                "prompt_tokenizer = \"{}\" is not available in this build (enable the `{}` feature)",
+                self.prompt_tokenizer.as_str(),
+                self.prompt_tokenizer.as_str()
+            ));
+        }
+        for (idx, name) in self.prompt.drop_order.iter().enumerate() {
+            if name.trim().is_empty() {
+                return Err(anyhow!("prompt.drop_order must not contain empty names"));
+            }
+            if self.prompt.drop_order[..idx].contains(name) {
+                return Err(anyhow!("prompt.drop_order lists '{name}' more than once"));
+            }
+        }
+        for (name, limits) in &self.prompt.sections {
+            if limits.max == Some(0) {
+                return Err(anyhow!("prompt.sections.{name}.max must be > 0 when set"));
+            }
+        }
+        if self.tree_summary_bytes == 0 {
+            return Err(anyhow!("tree_summary_bytes must be > 0"));
+        }
+        if self.history_attempts == 0 {
+            return Err(anyhow!("history_attempts must be > 0"));
+        }
+        if self.retry_diff.max_bytes == 0 {
+            return Err(anyhow!("retry_diff.max_bytes must be > 0"));
+        }
+        if let Some(path) = self
+            .retry_diff
+            .include
+            .iter()
+            .chain(&self.retry_diff.exclude)
+            .find(|path| path.trim().is_empty())
+        {
+            return Err(anyhow!("retry_diff paths must not be empty (got '{path}')"));
+        }
+        if self.repo_map.max_bytes == 0 {
+            return Err(anyhow!("repo_map.max_bytes must be > 0"));
+        }
+        if self.planner.nodes.iter().any(|id| id.trim().is_empty()) {
+            return Err(anyhow!("planner.nodes must not contain empty ids"));
+        }
+        if self.reviewer.max_diff_bytes == 0 {
+            return Err(anyhow!("reviewer.max_diff_bytes must be > 0"));
+        }
+        if let Some(path) = self
+            .reviewer
+            .exclude
+            .iter()
+            .find(|path| path.trim().is_empty())
+        {
+            return Err(anyhow!(
+                "reviewer.exclude paths must not be empty (got '{path}')"
+            ));
+        }
+        if self.verifier.max_rounds == 0 {
+            return Err(anyhow!("verifier.max_rounds must be > 0"));
+        }
+        let limits = &self.decomposition;
+        for (name, value) in [
+            ("max_depth", limits.max_depth),
+            ("max_children", limits.max_children),
+            ("max_nodes", limits.max_nodes),
+            ("max_decompose_levels", limits.max_decompose_levels),
+        ] {
+            if value == 0 {
+                return Err(anyhow!("decomposition.{name} must be > 0"));
+            }
+        }
         if self.guard.command.is_empty() || self.guard.command[0].trim().is_empty() {
             return Err(anyhow!("guard.command must be a non-empty array"));
         }
+        if self.hooks.timeout_secs == 0 {
+            return Err(anyhow!("hooks.timeout_secs must be > 0"));
+        }
+        for (name, command) in self.hooks.commands() {
+            if command
+                .first()
+                .is_some_and(|program| program.trim().is_empty())
+            {
+                return Err(anyhow!("hooks.{name} must not start with an empty program"));
+            }
+        }
+        if let Some(url) = &self.notify.url
+            && !(url.starts_with("http://") || url.starts_with("https://"))
+        {
+            return Err(anyhow!("notify.url must be an http:// or https:// URL"));
+        }
+        if self.notify.timeout_secs == 0 {
+            return Err(anyhow!("notify.timeout_secs must be > 0"));
+        }
         Ok(())
     }
 }
 
+impl HooksConfig {
+    /// All hook commands keyed by their config name.
+    pub fn commands(&self) -> [(&'static str, &[String]); 6] {
+        [
+            ("pre_step", &self.pre_step),
+            ("post_step", &self.post_step),
+            ("on_node_passed", &self.on_node_passed),
+            ("on_stuck", &self.on_stuck),
+            ("on_complete", &self.on_complete),
+            ("on_limit", &self.on_limit),
+        ]
+    }
+}
+
 /// Load config from a TOML file.
 ///
 /// If the file is missing, returns `RunnerConfig::default()`.
@@ -147,11 +551,111 @@ mod tests {
         assert_eq!(cfg.max_attempts_default, 2);
         assert_eq!(cfg.max_iterations, 10);
         assert_eq!(cfg.iteration_timeout_secs, 120);
+        assert_eq!(cfg.max_run_duration_secs, Some(28800));
+        assert_eq!(cfg.prompt_budget_tokens, Some(12000));
+        assert_eq!(cfg.prompt_tokenizer, Tokenizer::Bytes);
+        assert_eq!(cfg.prompt.drop_order, ["tree", "history"]);
+        assert_eq!(cfg.prompt.max("tree"), Some(2000));
+        assert_eq!(cfg.prompt.truncate("tree"), TruncateStrategy::Head);
+        assert_eq!(cfg.prompt.max("failure"), None);
+        assert_eq!(cfg.prompt.truncate("failure"), TruncateStrategy::HeadTail);
+        assert_eq!(cfg.prompt.max("goal"), None);
+        assert_eq!(cfg.history_attempts, 5);
+        assert_eq!(cfg.tree_summary_bytes, 3000);
+        assert_eq!(cfg.retry_diff.max_bytes, 4000);
+        assert_eq!(cfg.retry_diff.include, ["src"]);
+        assert_eq!(cfg.retry_diff.exclude, ["**/*.lock"]);
+        assert_eq!(cfg.repo_map.max_bytes, 2000);
+        assert_eq!(cfg.repo_map.recent_commits, 5);
+        assert_eq!(cfg.repo_map.max_files, 300);
+        assert!(!cfg.planner.enabled);
+        assert_eq!(cfg.planner.nodes, ["root.2"]);
+        assert!(cfg.reviewer.enabled);
+        assert_eq!(cfg.reviewer.max_diff_bytes, 10_000);
+        assert_eq!(cfg.reviewer.exclude, ["**/*.lock"]);
+        assert!(cfg.verifier.enabled);
+        assert_eq!(cfg.verifier.max_rounds, 3);
+        assert_eq!(cfg.decomposition.max_depth, 4);
+        assert_eq!(cfg.decomposition.max_children, 6);
+        assert_eq!(cfg.decomposition.max_nodes, 200);
+        assert_eq!(cfg.decomposition.max_decompose_levels, 2);
         assert_eq!(cfg.executor_output_limit_bytes, 5000);
         assert_eq!(cfg.guard_output_limit_bytes, 4000);
         assert_eq!(
             cfg.guard.command,
             vec!["just".to_string(), "ci".to_string()]
         );
+        assert_eq!(cfg.hooks.timeout_secs, 5);
+        assert_eq!(
+            cfg.hooks.post_step,
+            vec!["notify-send".to_string(), "runner".to_string()]
+        );
+        assert!(cfg.hooks.pre_step.is_empty());
+        assert_eq!(
+            cfg.notify.url.as_deref(),
+            Some("https://hooks.example.com/runner")
+        );
+        assert_eq!(cfg.notify.retries, 1);
+        assert_eq!(cfg.notify.secret_env, "RUNNER_NOTIFY_SECRET");
+    }
+
+    #[test]
+    fn prompt_section_defaults_survive_partial_overrides() {
+        let cfg: RunnerConfig =
+            toml::from_str("[prompt.sections.failure]\nmax = 500\n").expect("parse");
+        assert_eq!(cfg.prompt.max("failure"), Some(500));
+        assert_eq!(cfg.prompt.truncate("failure"), TruncateStrategy::Tail);
+        assert_eq!(cfg.prompt.drop_order, PromptConfig::default().drop_order);
+
+        let cfg = RunnerConfig {
+            prompt: PromptConfig {
+                drop_order: vec!["tree".to_string(), "tree".to_string()],
+                ..PromptConfig::default()
+            },
+            ..RunnerConfig::default()
+        };
+        let err = cfg.validate().unwrap_err();
+        assert!(err.to_string().contains("more than once"), "{err}");
+    }
+
+    #[test]
+    fn validate_rejects_blank_hook_program() {
+        let cfg = RunnerConfig {
+            hooks: HooksConfig {
+                on_stuck: vec![" ".to_string()],
+                ..HooksConfig::default()
+            },
+            ..RunnerConfig::default()
+        };
+        let err = cfg.validate().unwrap_err();
+        assert!(err.to_string().contains("hooks.on_stuck"));
+    }
+
+    #[test]
+    fn validate_rejects_non_http_notify_url() {
+        let cfg = RunnerConfig {
+            notify: NotifyConfig {
+                url: Some("ftp://example.com".to_string()),
+                ..NotifyConfig::default()
+            },
+            ..RunnerConfig::default()
+        };
+        let err = cfg.validate().unwrap_err();
+        assert!(err.to_string().contains("notify.url"));
+    }
+
+    #[test]
+    fn planner_applies_to_listed_subtrees() {
+        let cfg = PlannerConfig {
+            enabled: false,
+            nodes: vec!["root.2".to_string()],
+        };
+        assert!(cfg.applies_to(["root", "root.2", "root.2.1"].into_iter()));
+        assert!(!cfg.applies_to(["root", "root.1"].into_iter()));
+        let all = PlannerConfig {
+            enabled: true,
+            nodes: Vec::new(),
+        };
+        assert!(all.applies_to(["root"].into_iter()));
     }
 }
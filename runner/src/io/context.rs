@@ -15,6 +15,8 @@ pub struct ContextPayload {
     pub history: Option<String>,
     /// Guard output from the previous attempt (set when guards failed).
     pub failure: Option<String>,
+    /// Changes committed by the previous attempt on the same node (set on retry).
+    pub diff: Option<String>,
 }
 
 /// Resolved paths for context files.
@@ -24,6 +26,7 @@ pub struct ContextPaths {
     pub goal_path: PathBuf,
     pub history_path: PathBuf,
     pub failure_path: PathBuf,
+    pub diff_path: PathBuf,
 }
 
 impl ContextPaths {
@@ -34,6 +37,7 @@ impl ContextPaths {
             goal_path: dir.join("goal.md"),
             history_path: dir.join("history.md"),
             failure_path: dir.join("failure.md"),
+            diff_path: dir.join("diff.md"),
         }
     }
 }
@@ -53,10 +57,18 @@ pub fn write_context(root: &Path, payload: &ContextPayload) -> Result<ContextPat
         &paths.failure_path,
         &render_optional("Failure (guard output)", payload.failure.as_deref()),
     )?;
+    // Unlike history/failure there is no placeholder: the file only exists on retry.
+    if let Some(diff) = payload.diff.as_deref() {
+        write_file(
+            &paths.diff_path,
+            &render_optional("Diff (previous attempt)", Some(diff)),
+        )?;
+    }
 
     debug!(
         has_history = payload.history.is_some(),
         has_failure = payload.failure.is_some(),
+        has_diff = payload.diff.is_some(),
         "context written"
     );
     Ok(paths)
@@ -106,6 +118,7 @@ mod tests {
             goal: "Do the thing".to_string(),
             history: None,
             failure: None,
+            diff: None,
         };
 
         let paths = write_context(root, &payload).expect("write context");
@@ -113,6 +126,7 @@ mod tests {
         assert!(paths.goal_path.is_file());
         assert!(paths.history_path.is_file());
         assert!(paths.failure_path.is_file());
+        assert!(!paths.diff_path.exists());
         assert!(!dir.join("stale.txt").exists());
     }
 
@@ -128,6 +142,7 @@ mod tests {
             goal: "Goal body".to_string(),
             history: Some("Did work".to_string()),
             failure: None,
+            diff: Some("+fn added() {}".to_string()),
         };
 
         let paths = write_context(root, &payload).expect("write context");
@@ -136,5 +151,7 @@ mod tests {
 
         assert!(history.contains("Did work"));
         assert!(failure.contains("None."));
+        let diff = fs::read_to_string(&paths.diff_path).expect("read diff");
+        assert!(diff.contains("+fn added() {}"));
     }
 }
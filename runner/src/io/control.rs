@@ -0,0 +1,97 @@
+//! Operator control files under `.runner/`.
+//!
+//! `runner pause` and `runner stop` create `.runner/PAUSE` and `.runner/STOP`; `runner loop`
+//! checks for them between steps, so a human (or another tool) can intervene without killing
+//! the process mid-iteration. The files hold the RFC 3339 time of the request and are
+//! gitignored.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+
+/// A control file a running loop reacts to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    /// Block before the next step until the file is removed (`runner resume`).
+    Pause,
+    /// Stop before the next step; the loop removes the file when it honours it.
+    Stop,
+}
+
+impl Control {
+    pub fn file_name(self) -> &'static str {
+        match self {
+            Control::Pause => "PAUSE",
+            Control::Stop => "STOP",
+        }
+    }
+
+    /// Path of the control file in `runner_dir`.
+    pub fn path(self, runner_dir: &Path) -> PathBuf {
+        runner_dir.join(self.file_name())
+    }
+
+    /// Whether the control file exists.
+    pub fn is_requested(self, runner_dir: &Path) -> bool {
+        self.path(runner_dir).is_file()
+    }
+
+    /// Create the control file; returns `false` when it already existed.
+    pub fn request(self, runner_dir: &Path) -> Result<bool> {
+        if !runner_dir.is_dir() {
+            return Err(anyhow!(
+                "missing {} (run `runner init`)",
+                runner_dir.display()
+            ));
+        }
+        let path = self.path(runner_dir);
+        if path.is_file() {
+            return Ok(false);
+        }
+        let requested_at = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        fs::write(&path, format!("{requested_at}\n"))
+            .with_context(|| format!("write {}", path.display()))?;
+        Ok(true)
+    }
+
+    /// Remove the control file; returns `false` when it did not exist.
+    pub fn clear(self, runner_dir: &Path) -> Result<bool> {
+        let path = self.path(runner_dir);
+        match fs::remove_file(&path) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err).with_context(|| format!("remove {}", path.display())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_and_clear_are_idempotent() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let runner_dir = temp.path();
+
+        assert!(!Control::Pause.is_requested(runner_dir));
+        assert!(Control::Pause.request(runner_dir).expect("request"));
+        assert!(!Control::Pause.request(runner_dir).expect("request again"));
+        assert!(Control::Pause.is_requested(runner_dir));
+        assert!(!Control::Stop.is_requested(runner_dir));
+
+        assert!(Control::Pause.clear(runner_dir).expect("clear"));
+        assert!(!Control::Pause.clear(runner_dir).expect("clear again"));
+        assert!(!Control::Pause.is_requested(runner_dir));
+    }
+
+    #[test]
+    fn request_requires_runner_dir() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let err = Control::Stop
+            .request(&temp.path().join(".runner"))
+            .unwrap_err();
+        assert!(err.to_string().contains("runner init"), "{err:#}");
+    }
+}
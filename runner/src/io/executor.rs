@@ -13,6 +13,7 @@ use std::process::Command;
 use tracing::{debug, info, instrument, warn};
 
 use crate::core::types::AgentOutput;
+use crate::io::interrupt::{Interrupt, InterruptedError};
 use crate::io::process::{CommandOutput, run_command_with_stream};
 use serde::de::DeserializeOwned;
 
@@ -36,6 +37,8 @@ pub struct ExecRequest {
     /// Path to write JSONL event stream. When `Some`, enables `--json` flag
     /// and writes stdout lines incrementally for real-time observability.
     pub stream_path: Option<PathBuf>,
+    /// Shared interrupt handle; a forced interrupt terminates the agent process.
+    pub interrupt: Interrupt,
 }
 
 /// Abstraction over agent execution backends.
@@ -91,6 +94,7 @@ impl Executor for CodexExecutor {
             request.timeout,
             request.output_limit_bytes,
             request.stream_path.as_deref(),
+            &request.interrupt,
         )
         .context("run codex exec")?;
 
@@ -100,6 +104,10 @@ impl Executor for CodexExecutor {
             request.output_limit_bytes,
         )?;
 
+        if output.interrupted {
+            warn!("codex exec interrupted");
+            return Err(InterruptedError { phase: "executor" }.into());
+        }
         if output.timed_out {
             warn!(
                 timeout_secs = request.timeout.as_secs(),
@@ -169,6 +177,9 @@ fn write_executor_log(path: &Path, output: &CommandOutput, output_limit: usize)
     if output.timed_out {
         buf.push_str("\n[executor timed out]\n");
     }
+    if output.interrupted {
+        buf.push_str("\n[executor interrupted]\n");
+    }
 
     if buf.len() > output_limit {
         let truncated = format!(
@@ -220,11 +231,13 @@ mod tests {
             timeout: Duration::from_secs(1),
             output_limit_bytes: 1000,
             stream_path: None,
+            interrupt: Interrupt::new(),
         };
         let fake = FakeExecutor {
             output: Some(AgentOutput {
                 status: AgentStatus::Done,
                 summary: "ok".to_string(),
+                follow_ups: Vec::new(),
             }),
         };
 
@@ -247,6 +260,7 @@ mod tests {
             timeout: Duration::from_secs(1),
             output_limit_bytes: 1000,
             stream_path: None,
+            interrupt: Interrupt::new(),
         };
         let fake = FakeExecutor { output: None };
 
@@ -7,6 +7,7 @@ use std::path::{Path, PathBuf};
 use std::process::{Command, Output};
 
 use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
 use tracing::{debug, instrument, warn};
 
 /// Parsed `git status --porcelain` entry.
@@ -18,6 +19,23 @@ pub struct StatusEntry {
     pub path: String,
 }
 
+/// Aggregate line counts for a set of changes (`git diff --numstat`).
+///
+/// Binary files count towards `files_changed` but contribute no line counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffStat {
+    pub files_changed: u32,
+    pub insertions: u64,
+    pub deletions: u64,
+}
+
+/// A commit found by [`Git::last_commit_with_trailers`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitRef {
+    pub sha: String,
+    pub subject: String,
+}
+
 /// Wrapper for executing git commands in a working directory.
 #[derive(Debug, Clone)]
 pub struct Git {
@@ -55,6 +73,12 @@ impl Git {
         Ok(out.trim().to_string())
     }
 
+    /// Full sha of HEAD.
+    pub fn head_sha(&self) -> Result<String> {
+        let out = self.run_capture(&["rev-parse", "HEAD"])?;
+        Ok(out.trim().to_string())
+    }
+
     /// Get status entries (including untracked) in porcelain format.
     pub fn status_porcelain(&self) -> Result<Vec<StatusEntry>> {
         let out = self.run_capture(&["status", "--porcelain=v1", "-uall"])?;
@@ -155,6 +179,190 @@ impl Git {
         Ok(true)
     }
 
+    /// Discard all tracked and untracked changes (ignored files such as `.runner/` logs are
+    /// kept).
+    #[instrument(skip_all)]
+    pub fn discard_worktree_changes(&self) -> Result<()> {
+        debug!("discarding worktree changes");
+        self.run_checked(&["reset", "--hard", "HEAD"])?;
+        self.run_checked(&["clean", "-fd"])?;
+        Ok(())
+    }
+
+    /// Discard unstaged and untracked changes, keeping what is staged (and ignored files).
+    pub fn discard_unstaged_changes(&self) -> Result<()> {
+        debug!("discarding unstaged changes");
+        self.run_checked(&["checkout", "--", "."])?;
+        self.run_checked(&["clean", "-fd"])?;
+        Ok(())
+    }
+
+    /// Restore the given paths to their committed contents at HEAD.
+    pub fn restore_paths_from_head(&self, paths: &[&str]) -> Result<()> {
+        let mut args = vec!["checkout", "HEAD", "--"];
+        args.extend_from_slice(paths);
+        self.run_checked(&args)?;
+        Ok(())
+    }
+
+    /// Commit subjects reachable from HEAD, newest first.
+    pub fn log_subjects(&self) -> Result<Vec<String>> {
+        let out = self.run_capture(&["log", "--format=%s"])?;
+        Ok(out.lines().map(str::to_string).collect())
+    }
+
+    /// Newest commit reachable from HEAD whose message has every `Key: value` trailer line.
+    pub fn last_commit_with_trailers(
+        &self,
+        trailers: &[(&str, &str)],
+    ) -> Result<Option<CommitRef>> {
+        Ok(self.commits_with_trailers(trailers)?.into_iter().next())
+    }
+
+    /// Commits reachable from HEAD whose message has every `Key: value` trailer line, newest
+    /// first.
+    pub fn commits_with_trailers(&self, trailers: &[(&str, &str)]) -> Result<Vec<CommitRef>> {
+        let mut args = vec![
+            "log".to_string(),
+            "--fixed-strings".to_string(),
+            "--all-match".to_string(),
+            "--format=%H%x1f%s%x1f%B%x1e".to_string(),
+        ];
+        args.extend(
+            trailers
+                .iter()
+                .map(|(key, value)| format!("--grep={key}: {value}")),
+        );
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let out = self.run_capture(&args)?;
+        // `--grep` matches substrings (`root.1` also matches `root.10`); check exact lines.
+        let mut commits = Vec::new();
+        for record in out.split('\x1e') {
+            let mut fields = record.trim_start_matches('\n').splitn(3, '\x1f');
+            let (Some(sha), Some(subject), Some(body)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            if trailers
+                .iter()
+                .all(|(key, value)| has_trailer(body, key, value))
+            {
+                commits.push(CommitRef {
+                    sha: sha.to_string(),
+                    subject: subject.to_string(),
+                });
+            }
+        }
+        Ok(commits)
+    }
+
+    /// Patch introduced by commit `sha`, limited to `include` and without `exclude`.
+    ///
+    /// Both lists are glob pathspecs; `.runner/` is always excluded.
+    pub fn commit_diff(&self, sha: &str, include: &[String], exclude: &[String]) -> Result<String> {
+        let pathspecs = diff_pathspecs(include, exclude);
+        let mut args = vec![
+            "show",
+            "--format=",
+            "--no-color",
+            "--no-ext-diff",
+            sha,
+            "--",
+        ];
+        args.extend(pathspecs.iter().map(String::as_str));
+        self.run_capture(&args)
+    }
+
+    /// Patch of the staged changes, with the same pathspec rules as [`Git::commit_diff`].
+    pub fn staged_diff(&self, include: &[String], exclude: &[String]) -> Result<String> {
+        let pathspecs = diff_pathspecs(include, exclude);
+        let mut args = vec!["diff", "--cached", "--no-color", "--no-ext-diff", "--"];
+        args.extend(pathspecs.iter().map(String::as_str));
+        self.run_capture(&args)
+    }
+
+    /// Files tracked at `rev` with their blob sizes in bytes, in path order.
+    ///
+    /// Submodules are skipped (they have no size).
+    pub fn tracked_file_sizes(&self, rev: &str) -> Result<Vec<(String, u64)>> {
+        let out = self.run_capture(&["ls-tree", "-r", "-l", "-z", "--full-tree", rev])?;
+        let mut files = Vec::new();
+        for entry in out.split('\0').filter(|entry| !entry.is_empty()) {
+            let (meta, path) = entry
+                .split_once('\t')
+                .ok_or_else(|| anyhow!("unexpected ls-tree entry: {entry}"))?;
+            let Some(Ok(size)) = meta.split_whitespace().nth(3).map(str::parse::<u64>) else {
+                continue;
+            };
+            files.push((path.to_string(), size));
+        }
+        Ok(files)
+    }
+
+    /// Last commit reachable from HEAD that changed a path outside `.runner/`; HEAD when no
+    /// commit did.
+    ///
+    /// Iteration commits that only update runner state leave it unchanged, so it identifies
+    /// the project files independently of runner bookkeeping.
+    pub fn last_project_commit(&self) -> Result<String> {
+        let mut args = vec!["log", "-1", "--format=%H", "--"];
+        let pathspecs = diff_pathspecs(&[], &[]);
+        args.extend(pathspecs.iter().map(String::as_str));
+        let sha = self.run_capture(&args)?.trim().to_string();
+        if sha.is_empty() {
+            return self.head_sha();
+        }
+        Ok(sha)
+    }
+
+    /// Paths outside `.runner/` touched by the last `commits` commits reachable from `rev`
+    /// that changed such paths, most recent first, without duplicates.
+    pub fn recently_changed_files(&self, rev: &str, commits: usize) -> Result<Vec<String>> {
+        let limit = format!("--max-count={commits}");
+        let mut args = vec![
+            "log",
+            &limit,
+            "--format=",
+            "--name-only",
+            "--no-renames",
+            "-z",
+            rev,
+            "--",
+        ];
+        let pathspecs = diff_pathspecs(&[], &[]);
+        args.extend(pathspecs.iter().map(String::as_str));
+        let out = self.run_capture(&args)?;
+        let mut seen = std::collections::HashSet::new();
+        Ok(out
+            .split(['\0', '\n'])
+            .filter(|path| !path.is_empty() && seen.insert(*path))
+            .map(str::to_string)
+            .collect())
+    }
+
+    /// Summarize staged changes, ignoring paths under any of the given prefixes.
+    pub fn staged_diff_stat_excluding(&self, excluded_prefixes: &[&str]) -> Result<DiffStat> {
+        let out = self.run_capture(&["diff", "--cached", "--numstat", "--no-renames"])?;
+        let mut stat = DiffStat::default();
+        for line in out.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let (insertions, deletions, path) = parse_numstat_line(line)?;
+            if excluded_prefixes
+                .iter()
+                .any(|prefix| path.starts_with(prefix))
+            {
+                continue;
+            }
+            stat.files_changed += 1;
+            stat.insertions += insertions;
+            stat.deletions += deletions;
+        }
+        Ok(stat)
+    }
+
     fn run_capture(&self, args: &[&str]) -> Result<String> {
         let output = self.run_checked(args)?;
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
@@ -178,6 +386,28 @@ impl Git {
     }
 }
 
+fn has_trailer(body: &str, key: &str, value: &str) -> bool {
+    body.lines().any(|line| {
+        line.strip_prefix(key)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .is_some_and(|rest| rest.trim() == value)
+    })
+}
+
+fn diff_pathspecs(include: &[String], exclude: &[String]) -> Vec<String> {
+    let mut specs: Vec<String> = if include.is_empty() {
+        vec![".".to_string()]
+    } else {
+        include
+            .iter()
+            .map(|path| format!(":(glob){path}"))
+            .collect()
+    };
+    specs.push(":(exclude).runner".to_string());
+    specs.extend(exclude.iter().map(|path| format!(":(exclude,glob){path}")));
+    specs
+}
+
 fn parse_status_line(line: &str) -> Result<StatusEntry> {
     if let Some(path) = line.strip_prefix("?? ") {
         return Ok(StatusEntry {
@@ -196,6 +426,23 @@ fn parse_status_line(line: &str) -> Result<StatusEntry> {
     Ok(StatusEntry { code, path })
 }
 
+fn parse_numstat_line(line: &str) -> Result<(u64, u64, &str)> {
+    let mut parts = line.splitn(3, '\t');
+    let (Some(added), Some(removed), Some(path)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(anyhow!("unexpected numstat line: '{line}'"));
+    };
+    // Binary files report `-` for both counts.
+    let parse = |v: &str| -> Result<u64> {
+        if v == "-" {
+            return Ok(0);
+        }
+        v.parse()
+            .map_err(|_| anyhow!("unexpected numstat count '{v}' in '{line}'"))
+    };
+    Ok((parse(added)?, parse(removed)?, path))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -229,4 +476,38 @@ mod tests {
         let e = parse_status_line("R  old.txt -> new.txt").expect("parse");
         assert_eq!(e.path, "new.txt");
     }
+
+    #[test]
+    fn parses_numstat_lines_including_binary() {
+        assert_eq!(
+            parse_numstat_line("3\t1\tsrc/lib.rs").expect("parse"),
+            (3, 1, "src/lib.rs")
+        );
+        assert_eq!(
+            parse_numstat_line("-\t-\tassets/logo.png").expect("parse"),
+            (0, 0, "assets/logo.png")
+        );
+        assert!(parse_numstat_line("garbage").is_err());
+    }
+
+    #[test]
+    fn trailer_match_is_exact() {
+        let body = "summary\n\nRunner-Run: run-1\nRunner-Node: root.10\n";
+        assert!(has_trailer(body, "Runner-Node", "root.10"));
+        assert!(!has_trailer(body, "Runner-Node", "root.1"));
+        assert!(!has_trailer(body, "Runner", "Node: root.10"));
+    }
+
+    #[test]
+    fn diff_pathspecs_always_exclude_runner_dir() {
+        assert_eq!(diff_pathspecs(&[], &[]), [".", ":(exclude).runner"]);
+        assert_eq!(
+            diff_pathspecs(&["src".to_string()], &["**/*.lock".to_string()]),
+            [
+                ":(glob)src",
+                ":(exclude).runner",
+                ":(exclude,glob)**/*.lock"
+            ]
+        );
+    }
 }
@@ -13,6 +13,7 @@ use std::process::Command;
 use tracing::{debug, info, instrument, warn};
 
 use crate::core::types::{AgentStatus, GuardOutcome};
+use crate::io::interrupt::{Interrupt, InterruptedError};
 use crate::io::process::{CommandOutput, run_command_with_timeout};
 
 /// Default timeout for guard execution (30 minutes).
@@ -31,6 +32,8 @@ pub struct GuardRequest {
     pub timeout: Duration,
     /// Truncate guard output if it exceeds this size.
     pub output_limit_bytes: usize,
+    /// Shared interrupt handle; a forced interrupt terminates the guard process.
+    pub interrupt: Interrupt,
 }
 
 /// Abstraction over guard execution backends.
@@ -71,11 +74,20 @@ impl GuardRunner for CommandGuardRunner {
             .current_dir(&request.workdir)
             .stdin(std::process::Stdio::null());
 
-        let output =
-            run_command_with_timeout(cmd, None, request.timeout, request.output_limit_bytes)
-                .with_context(|| format!("run guard command: {}", self.command.join(" ")))?;
+        let output = run_command_with_timeout(
+            cmd,
+            None,
+            request.timeout,
+            request.output_limit_bytes,
+            &request.interrupt,
+        )
+        .with_context(|| format!("run guard command: {}", self.command.join(" ")))?;
         write_guard_log(&request.log_path, &output, request.output_limit_bytes)?;
 
+        if output.interrupted {
+            warn!("guard interrupted");
+            return Err(InterruptedError { phase: "guard" }.into());
+        }
         if output.timed_out {
             warn!("guard timed out");
             return Ok(GuardOutcome::Fail);
@@ -117,6 +129,9 @@ fn write_guard_log(path: &PathBuf, output: &CommandOutput, output_limit: usize)
     if output.timed_out {
         buf.push_str("\n[guard timed out]\n");
     }
+    if output.interrupted {
+        buf.push_str("\n[guard interrupted]\n");
+    }
 
     if buf.len() > output_limit {
         let truncated = format!(
@@ -159,6 +174,7 @@ mod tests {
             log_path: temp.path().join("guard.log"),
             timeout: Duration::from_secs(1),
             output_limit_bytes: 100,
+            interrupt: Interrupt::new(),
         };
         let runner = FakeGuardRunner {
             outcome: GuardOutcome::Pass,
@@ -180,6 +196,7 @@ mod tests {
             log_path: temp.path().join("guard.log"),
             timeout: Duration::from_secs(1),
             output_limit_bytes: 100,
+            interrupt: Interrupt::new(),
         };
         let runner = FakeGuardRunner {
             outcome: GuardOutcome::Fail,
@@ -189,4 +206,30 @@ mod tests {
             run_guards_if_needed(AgentStatus::Done, &runner, &request).expect("guard outcome");
         assert_eq!(outcome, GuardOutcome::Fail);
     }
+
+    /// Verifies a forced interrupt terminates a running guard command.
+    ///
+    /// The interrupt is already forced, so the first poll kills the process group and the
+    /// runner reports `InterruptedError` instead of a guard outcome.
+    #[cfg(unix)]
+    #[test]
+    fn forced_interrupt_terminates_guard() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let interrupt = Interrupt::new();
+        interrupt.request();
+        interrupt.request();
+        let request = GuardRequest {
+            workdir: temp.path().to_path_buf(),
+            log_path: temp.path().join("guard.log"),
+            timeout: Duration::from_secs(30),
+            output_limit_bytes: 100,
+            interrupt,
+        };
+        let runner = CommandGuardRunner::new(vec!["sleep".to_string(), "30".to_string()]);
+
+        let started = std::time::Instant::now();
+        let err = runner.run(&request).unwrap_err();
+        assert!(err.downcast_ref::<InterruptedError>().is_some());
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
 }
@@ -0,0 +1,293 @@
+//! Lifecycle hooks configured under `[hooks]` in `config.toml`.
+//!
+//! A hook is an arbitrary command run from the repo root with a JSON [`HookPayload`] on stdin
+//! and `RUNNER_HOOK=<event>` in its environment. Hooks are best-effort: a failing, timed-out
+//! or missing hook is logged to `<iteration dir>/hooks/<event>.log` and never fails the step.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use tracing::{debug, info, warn};
+
+use crate::core::types::{AgentStatus, GuardOutcome, ReviewVerdict};
+use crate::io::config::HooksConfig;
+use crate::io::interrupt::Interrupt;
+use crate::io::process::{CommandOutput, run_command_with_timeout};
+
+/// Hook output kept in memory and in the hook log.
+const HOOK_OUTPUT_LIMIT_BYTES: usize = 100_000;
+
+/// Lifecycle events that can trigger a hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    PreStep,
+    PostStep,
+    OnNodePassed,
+    OnStuck,
+    OnComplete,
+    OnLimit,
+}
+
+impl HookEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HookEvent::PreStep => "pre_step",
+            HookEvent::PostStep => "post_step",
+            HookEvent::OnNodePassed => "on_node_passed",
+            HookEvent::OnStuck => "on_stuck",
+            HookEvent::OnComplete => "on_complete",
+            HookEvent::OnLimit => "on_limit",
+        }
+    }
+
+    fn command(self, cfg: &HooksConfig) -> &[String] {
+        match self {
+            HookEvent::PreStep => &cfg.pre_step,
+            HookEvent::PostStep => &cfg.post_step,
+            HookEvent::OnNodePassed => &cfg.on_node_passed,
+            HookEvent::OnStuck => &cfg.on_stuck,
+            HookEvent::OnComplete => &cfg.on_complete,
+            HookEvent::OnLimit => &cfg.on_limit,
+        }
+    }
+}
+
+/// JSON document written to a hook's stdin.
+///
+/// `status` and `guard` are absent for `pre_step`. Log paths point into the iteration dir and
+/// may not exist (e.g. no guard log when guards were skipped).
+#[derive(Debug, Clone, Serialize)]
+pub struct HookPayload {
+    pub event: HookEvent,
+    pub run_id: String,
+    pub iter: u32,
+    /// Node the iteration worked on.
+    pub node_id: String,
+    pub status: Option<AgentStatus>,
+    pub guard: Option<GuardOutcome>,
+    /// Reviewer verdict, when the reviewer ran.
+    pub review: Option<ReviewVerdict>,
+    /// Stuck leaf that will be selected next (`on_stuck` only).
+    pub stuck_node_id: Option<String>,
+    pub iteration_dir: PathBuf,
+    pub meta_path: PathBuf,
+    pub output_path: PathBuf,
+    pub guard_log_path: PathBuf,
+    pub review_log_path: PathBuf,
+    pub executor_log_path: PathBuf,
+}
+
+impl HookPayload {
+    /// Payload for `event` in `iteration_dir`; outcome fields start empty.
+    pub fn new(
+        event: HookEvent,
+        run_id: &str,
+        iter: u32,
+        node_id: &str,
+        iteration_dir: &Path,
+    ) -> Self {
+        Self {
+            event,
+            run_id: run_id.to_string(),
+            iter,
+            node_id: node_id.to_string(),
+            status: None,
+            guard: None,
+            review: None,
+            stuck_node_id: None,
+            iteration_dir: iteration_dir.to_path_buf(),
+            meta_path: iteration_dir.join("meta.json"),
+            output_path: iteration_dir.join("output.json"),
+            guard_log_path: iteration_dir.join("guard.log"),
+            review_log_path: iteration_dir.join("review.log"),
+            executor_log_path: iteration_dir.join("executor.log"),
+        }
+    }
+}
+
+/// Result of running one hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookOutcome {
+    /// No command configured for the event.
+    NotConfigured,
+    Succeeded,
+    Failed,
+    TimedOut,
+}
+
+/// Run the hook configured for `payload.event`, if any, and log it to the iteration dir.
+///
+/// Errors spawning or waiting for the hook are logged and reported as `Failed`; only failures
+/// writing the hook log are returned as errors.
+pub fn run_hook(
+    cfg: &HooksConfig,
+    root: &Path,
+    payload: &HookPayload,
+    interrupt: &Interrupt,
+) -> Result<HookOutcome> {
+    let event = payload.event;
+    let command = event.command(cfg);
+    let Some(program) = command.first() else {
+        return Ok(HookOutcome::NotConfigured);
+    };
+    info!(hook = event.as_str(), command = ?command, "running hook");
+
+    let mut input = serde_json::to_string_pretty(payload)?;
+    input.push('\n');
+    let mut cmd = Command::new(program);
+    cmd.args(&command[1..])
+        .current_dir(root)
+        .env("RUNNER_HOOK", event.as_str())
+        .stdin(std::process::Stdio::piped());
+
+    let log_path = payload
+        .iteration_dir
+        .join("hooks")
+        .join(format!("{}.log", event.as_str()));
+    let result = run_command_with_timeout(
+        cmd,
+        Some(input.as_bytes()),
+        Duration::from_secs(cfg.timeout_secs),
+        HOOK_OUTPUT_LIMIT_BYTES,
+        interrupt,
+    );
+    let (outcome, log) = match result {
+        Ok(output) => {
+            let outcome = if output.timed_out {
+                HookOutcome::TimedOut
+            } else if output.status.success() {
+                HookOutcome::Succeeded
+            } else {
+                HookOutcome::Failed
+            };
+            (outcome, render_hook_log(command, &output))
+        }
+        Err(err) => (
+            HookOutcome::Failed,
+            format!(
+                "command: {}\n[hook failed to run: {err:#}]\n",
+                command.join(" ")
+            ),
+        ),
+    };
+    write_hook_log(&log_path, &log)?;
+
+    match outcome {
+        HookOutcome::Succeeded => debug!(hook = event.as_str(), "hook succeeded"),
+        _ => warn!(
+            hook = event.as_str(),
+            outcome = ?outcome,
+            log = %log_path.display(),
+            "hook did not succeed"
+        ),
+    }
+    Ok(outcome)
+}
+
+fn render_hook_log(command: &[String], output: &CommandOutput) -> String {
+    let mut buf = format!("command: {}\n", command.join(" "));
+    match output.status.code() {
+        Some(code) => buf.push_str(&format!("exit_code: {code}\n")),
+        None => buf.push_str("exit_code: none (terminated by signal)\n"),
+    }
+    buf.push_str("=== stdout ===\n");
+    buf.push_str(&String::from_utf8_lossy(&output.stdout));
+    buf.push_str(&output.stdout_truncated_notice("hook"));
+    buf.push_str("\n=== stderr ===\n");
+    buf.push_str(&String::from_utf8_lossy(&output.stderr));
+    buf.push_str(&output.stderr_truncated_notice("hook"));
+    if output.timed_out {
+        buf.push_str("\n[hook timed out]\n");
+    }
+    buf
+}
+
+fn write_hook_log(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("create hook log dir {}", parent.display()))?;
+    }
+    fs::write(path, contents).with_context(|| format!("write hook log {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sh(script: &str) -> Vec<String> {
+        vec!["sh".to_string(), "-c".to_string(), script.to_string()]
+    }
+
+    #[test]
+    fn unconfigured_hook_is_skipped() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let payload = HookPayload::new(HookEvent::PreStep, "run-1", 1, "root", temp.path());
+        let outcome = run_hook(
+            &HooksConfig::default(),
+            temp.path(),
+            &payload,
+            &Interrupt::new(),
+        )
+        .expect("run hook");
+        assert_eq!(outcome, HookOutcome::NotConfigured);
+        assert!(!temp.path().join("hooks").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn hook_receives_payload_on_stdin_and_is_logged() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let root = temp.path();
+        let iter_dir = root.join("iter");
+        let cfg = HooksConfig {
+            post_step: sh("cat > payload.json; echo \"hook=$RUNNER_HOOK\""),
+            ..HooksConfig::default()
+        };
+        let mut payload = HookPayload::new(HookEvent::PostStep, "run-1", 3, "root.1", &iter_dir);
+        payload.status = Some(AgentStatus::Done);
+        payload.guard = Some(GuardOutcome::Pass);
+
+        let outcome = run_hook(&cfg, root, &payload, &Interrupt::new()).expect("run hook");
+
+        assert_eq!(outcome, HookOutcome::Succeeded);
+        let received: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(root.join("payload.json")).expect("read"))
+                .expect("parse payload");
+        assert_eq!(received["event"], "post_step");
+        assert_eq!(received["iter"], 3);
+        assert_eq!(received["node_id"], "root.1");
+        assert_eq!(received["status"], "done");
+        assert_eq!(received["guard"], "pass");
+        let log = fs::read_to_string(iter_dir.join("hooks/post_step.log")).expect("log");
+        assert!(log.contains("exit_code: 0"));
+        assert!(log.contains("hook=post_step"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failing_and_missing_hooks_do_not_error() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let root = temp.path();
+        let cfg = HooksConfig {
+            on_stuck: sh("exit 7"),
+            on_limit: vec!["definitely-not-a-real-hook-binary".to_string()],
+            ..HooksConfig::default()
+        };
+
+        let stuck = HookPayload::new(HookEvent::OnStuck, "run-1", 1, "root", root);
+        let outcome = run_hook(&cfg, root, &stuck, &Interrupt::new()).expect("run hook");
+        assert_eq!(outcome, HookOutcome::Failed);
+        let log = fs::read_to_string(root.join("hooks/on_stuck.log")).expect("log");
+        assert!(log.contains("exit_code: 7"));
+
+        let limit = HookPayload::new(HookEvent::OnLimit, "run-1", 1, "root", root);
+        let outcome = run_hook(&cfg, root, &limit, &Interrupt::new()).expect("run hook");
+        assert_eq!(outcome, HookOutcome::Failed);
+        assert!(root.join("hooks/on_limit.log").exists());
+    }
+}
@@ -10,11 +10,29 @@ use super::config::{RunnerConfig, write_config};
 use super::run_state::{RunState, write_run_state};
 use crate::tree::default_tree_with_max_attempts;
 
+/// Lines every `.runner/.gitignore` must contain: runner-ephemeral files and directories.
+pub const RUNNER_GITIGNORE_LINES: &[&str] = &[
+    "PAUSE",
+    "STOP",
+    "cache/",
+    "context/",
+    "iterations/",
+    "runner.lock",
+    "state/attempts.jsonl",
+];
+
 const TREE_SCHEMA: &str = include_str!(concat!(
     env!("CARGO_MANIFEST_DIR"),
-    "/../schemas/task_tree/v1.schema.json"
+    "/../schemas/task_tree/v2.schema.json"
 ));
 
+/// `$id` of the task tree schema this runner deploys to `.runner/state/schema.json`.
+pub const TREE_SCHEMA_ID: &str = "https://runner.local/schemas/task_tree/v2.schema.json";
+
+/// `$id`s of earlier task tree schemas. Each version only adds optional fields, so trees
+/// valid under them stay valid under [`TREE_SCHEMA_ID`].
+const OLDER_TREE_SCHEMA_IDS: &[&str] = &["https://runner.local/schemas/task_tree/v1.schema.json"];
+
 /// All canonical paths within `.runner/` for a project root.
 #[derive(Debug, Clone)]
 pub struct RunnerPaths {
@@ -24,6 +42,7 @@ pub struct RunnerPaths {
     pub context_dir: PathBuf,
     pub iterations_dir: PathBuf,
     pub gitignore_path: PathBuf,
+    pub lock_path: PathBuf,
     pub goal_path: PathBuf,
     pub tree_path: PathBuf,
     pub schema_path: PathBuf,
@@ -50,6 +69,7 @@ impl RunnerPaths {
             context_dir: context_dir.clone(),
             iterations_dir: iterations_dir.clone(),
             gitignore_path: runner_dir.join(".gitignore"),
+            lock_path: runner_dir.join(crate::io::lock::LOCK_FILE_NAME),
             goal_path: runner_dir.join("GOAL.md"),
             tree_path: state_dir.join("tree.json"),
             schema_path: state_dir.join("schema.json"),
@@ -94,7 +114,7 @@ pub fn init_runner(root: &Path, options: &InitOptions) -> Result<RunnerPaths> {
 
     let cfg = RunnerConfig::default();
 
-    write_file(&paths.gitignore_path, RUNNER_GITIGNORE)?;
+    write_file(&paths.gitignore_path, &runner_gitignore())?;
     write_file(&paths.goal_path, GOAL_PLACEHOLDER)?;
     write_tree(&paths.tree_path, cfg.max_attempts_default)?;
     write_file(&paths.schema_path, TREE_SCHEMA)?;
@@ -109,6 +129,83 @@ pub fn init_runner(root: &Path, options: &InitOptions) -> Result<RunnerPaths> {
     Ok(paths)
 }
 
+/// [`RUNNER_GITIGNORE_LINES`] that `path` lacks (all of them when it does not exist).
+pub fn missing_gitignore_lines(path: &Path) -> Result<Vec<&'static str>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err).with_context(|| format!("read {}", path.display())),
+    };
+    Ok(RUNNER_GITIGNORE_LINES
+        .iter()
+        .copied()
+        .filter(|required| !contents.lines().any(|line| line.trim() == *required))
+        .collect())
+}
+
+/// Append the missing [`RUNNER_GITIGNORE_LINES`] to `path`, creating it if needed; existing
+/// lines are kept as they are. Returns the lines added.
+pub fn ensure_runner_gitignore(path: &Path) -> Result<Vec<&'static str>> {
+    let missing = missing_gitignore_lines(path)?;
+    if missing.is_empty() {
+        return Ok(missing);
+    }
+    let mut contents = fs::read_to_string(path).unwrap_or_default();
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    for line in &missing {
+        contents.push_str(line);
+        contents.push('\n');
+    }
+    write_file(path, &contents)?;
+    Ok(missing)
+}
+
+/// Replace the deployed tree schema at `path` with the current one when it is an older version
+/// or differs from the embedded copy. Returns the replaced schema's `$id`, `None` when it was
+/// already current.
+///
+/// Runs started by older runners keep the schema they were initialized with; without the
+/// upgrade the first tree using a newer field would fail the next `load_tree`. A schema with an
+/// unknown `$id` (e.g. from a newer runner) is an error rather than a silent downgrade.
+pub fn upgrade_tree_schema(path: &Path) -> Result<Option<String>> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("read schema {}", path.display()))?;
+    if contents == TREE_SCHEMA {
+        return Ok(None);
+    }
+    let id = deployed_tree_schema_id(path)?;
+    write_file(path, TREE_SCHEMA)?;
+    Ok(Some(id))
+}
+
+/// `$id` of the tree schema deployed at `path`: [`TREE_SCHEMA_ID`] or an older version this
+/// runner can upgrade. Any other `$id` is an error.
+pub fn deployed_tree_schema_id(path: &Path) -> Result<String> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("read schema {}", path.display()))?;
+    let schema: serde_json::Value = serde_json::from_str(&contents)
+        .with_context(|| format!("parse schema {}", path.display()))?;
+    let id = schema
+        .get("$id")
+        .and_then(serde_json::Value::as_str)
+        .ok_or_else(|| anyhow!("schema {} has no $id", path.display()))?;
+    if id != TREE_SCHEMA_ID && !OLDER_TREE_SCHEMA_IDS.contains(&id) {
+        return Err(anyhow!(
+            "unknown tree schema '{id}' in {} (expected {TREE_SCHEMA_ID} or older)",
+            path.display()
+        ));
+    }
+    Ok(id.to_string())
+}
+
+fn runner_gitignore() -> String {
+    let mut contents = RUNNER_GITIGNORE_LINES.join("\n");
+    contents.push('\n');
+    contents
+}
+
 fn create_dir(path: &Path) -> Result<()> {
     fs::create_dir_all(path).with_context(|| format!("create directory {}", path.display()))
 }
@@ -140,7 +237,6 @@ const CONTEXT_HISTORY_PLACEHOLDER: &str =
     "# History (previous attempt)\n\nGenerated by `runner step` on retry.\n";
 const CONTEXT_FAILURE_PLACEHOLDER: &str =
     "# Failure (guard output)\n\nGenerated by `runner step` when guards fail.\n";
-const RUNNER_GITIGNORE: &str = "context/\niterations/\n";
 
 #[cfg(test)]
 mod tests {
@@ -188,7 +284,52 @@ mod tests {
         assert_eq!(tree_contents, expected_tree_json);
 
         let gitignore = read_to_string(&paths.gitignore_path);
-        assert_eq!(gitignore, RUNNER_GITIGNORE);
+        assert_eq!(gitignore, runner_gitignore());
+        assert!(
+            missing_gitignore_lines(&paths.gitignore_path)
+                .expect("missing lines")
+                .is_empty()
+        );
+    }
+
+    /// A v1 schema from an older run is replaced by the current one; unknown schemas are kept.
+    #[test]
+    fn upgrade_tree_schema_replaces_older_versions_only() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let path = temp.path().join("schema.json");
+        let v1 = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../schemas/task_tree/v1.schema.json"
+        ));
+        fs::write(&path, v1).expect("write v1");
+
+        let replaced = upgrade_tree_schema(&path).expect("upgrade");
+        assert_eq!(
+            replaced.as_deref(),
+            Some("https://runner.local/schemas/task_tree/v1.schema.json")
+        );
+        assert_eq!(read_to_string(&path), TREE_SCHEMA);
+        assert_eq!(upgrade_tree_schema(&path).expect("upgrade"), None);
+
+        let future = v1.replace("v1.schema.json", "v9.schema.json");
+        fs::write(&path, &future).expect("write future");
+        let err = upgrade_tree_schema(&path).expect_err("unknown schema");
+        assert!(err.to_string().contains("unknown tree schema"), "{err}");
+        assert_eq!(read_to_string(&path), future);
+    }
+
+    #[test]
+    fn ensure_runner_gitignore_appends_missing_lines_once() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let path = temp.path().join(".gitignore");
+        fs::write(&path, "custom/\ncontext/").expect("write");
+
+        let added = ensure_runner_gitignore(&path).expect("ensure");
+        assert_eq!(added.len(), RUNNER_GITIGNORE_LINES.len() - 1);
+        let contents = read_to_string(&path);
+        assert!(contents.starts_with("custom/\ncontext/\nPAUSE\n"));
+        assert!(ensure_runner_gitignore(&path).expect("ensure").is_empty());
+        assert_eq!(read_to_string(&path), contents);
     }
 
     /// Verifies init_runner refuses to overwrite without --force.
@@ -0,0 +1,106 @@
+//! Cooperative interruption for `runner step` and `runner loop`.
+//!
+//! - First SIGINT/SIGTERM: finish the current iteration, then stop.
+//! - Second SIGINT/SIGTERM: terminate the running agent/guard process group and abort the
+//!   iteration (recorded as `interrupted.json` in the iteration dir).
+//!
+//! Agent and guard processes are spawned in their own process group, so a terminal Ctrl-C
+//! only reaches the runner; the runner decides when (and whether) to forward it.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::Result;
+
+/// Shared interrupt counter fed by signal handlers (or tests).
+#[derive(Debug, Clone, Default)]
+pub struct Interrupt {
+    count: Arc<AtomicUsize>,
+}
+
+impl Interrupt {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Route SIGINT/SIGTERM into this handle instead of terminating the process.
+    #[cfg(unix)]
+    pub fn install_signal_handlers(&self) -> Result<()> {
+        use anyhow::Context;
+        use signal_hook::consts::{SIGINT, SIGTERM};
+        use signal_hook::iterator::Signals;
+
+        let mut signals = Signals::new([SIGINT, SIGTERM]).context("register signal handlers")?;
+        let handle = self.clone();
+        std::thread::spawn(move || {
+            for signal in signals.forever() {
+                handle.request();
+                if handle.is_forced() {
+                    tracing::warn!(signal, "signal received again; aborting iteration");
+                } else {
+                    tracing::warn!(
+                        signal,
+                        "signal received; stopping after the current iteration (send again to abort)"
+                    );
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Signals are not intercepted on non-unix platforms.
+    #[cfg(not(unix))]
+    pub fn install_signal_handlers(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Record one interrupt request.
+    pub fn request(&self) {
+        self.count.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// True once any interrupt has been requested (stop after the current iteration).
+    pub fn stop_requested(&self) -> bool {
+        self.count.load(Ordering::SeqCst) >= 1
+    }
+
+    /// True once a second interrupt has been requested (abort the current iteration).
+    pub fn is_forced(&self) -> bool {
+        self.count.load(Ordering::SeqCst) >= 2
+    }
+}
+
+/// Error returned when a forced interrupt aborts a running agent or guard process.
+#[derive(Debug, Clone)]
+pub struct InterruptedError {
+    /// Which process was terminated (`executor` or `guard`).
+    pub phase: &'static str,
+}
+
+impl std::fmt::Display for InterruptedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "interrupted: {} terminated by signal", self.phase)
+    }
+}
+
+impl std::error::Error for InterruptedError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interrupt_levels_escalate() {
+        let interrupt = Interrupt::new();
+        let shared = interrupt.clone();
+        assert!(!interrupt.stop_requested());
+        assert!(!interrupt.is_forced());
+
+        shared.request();
+        assert!(interrupt.stop_requested());
+        assert!(!interrupt.is_forced());
+
+        shared.request();
+        assert!(interrupt.is_forced());
+    }
+}
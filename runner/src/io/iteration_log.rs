@@ -4,22 +4,100 @@ use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use crate::core::types::{AgentOutput, AgentStatus, GuardOutcome};
+use crate::core::types::{AgentOutput, AgentStatus, GuardOutcome, ReviewVerdict, TreeChildSpec};
+use crate::io::git::DiffStat;
+use crate::io::prompt::PromptTemplateRef;
 use crate::tree::Node;
 
 /// Metadata written to `meta.json` for each iteration.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct IterationMeta {
     pub run_id: String,
     pub iter: u32,
     pub node_id: String,
     pub status: AgentStatus,
     pub guard: GuardOutcome,
+    /// Reviewer verdict, when the reviewer ran; a rejection fails the node although `guard`
+    /// is `pass`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review: Option<ReviewVerdict>,
+    /// RFC 3339 timestamp of when the step started.
     pub started_at: Option<String>,
+    /// RFC 3339 timestamp of when the iteration commit finished.
     pub ended_at: Option<String>,
     pub duration_ms: Option<u64>,
+    /// Repository changes committed by this iteration (excluding `.runner/`).
+    pub diffstat: Option<DiffStat>,
+    /// Where the iteration spent its time.
+    #[serde(default)]
+    pub phases: PhaseTimings,
+    /// Prompt templates for the iteration's phase, so runs stay attributable to a prompt version.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prompt_templates: Vec<PromptTemplateRef>,
+    /// Phase the iteration was interrupted in, for partial work committed by `runner recover`;
+    /// `status` is then `retry` and no node attempt was consumed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interrupted: Option<String>,
+}
+
+/// Per-phase wall-clock durations of an iteration; `None` for phases that did not run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhaseTimings {
+    /// Selection context, `.runner/context/` files and prompt inputs.
+    pub context_build_ms: Option<u64>,
+    /// Planner agent, prompt included (only when the planner phase is enabled).
+    pub planning_ms: Option<u64>,
+    pub prompt_render_ms: Option<u64>,
+    pub agent_exec_ms: Option<u64>,
+    /// Loading and checking the tree written by the agent.
+    pub tree_validation_ms: Option<u64>,
+    pub guards_ms: Option<u64>,
+    /// Reviewer agent, prompt and diff included (only when guards passed and the reviewer is
+    /// enabled).
+    pub review_ms: Option<u64>,
+    /// Applying state updates and writing `tree.json`, `run_state.json` and iteration logs.
+    pub state_update_ms: Option<u64>,
+    pub commit_ms: Option<u64>,
+}
+
+impl PhaseTimings {
+    /// Phase names and durations in execution order.
+    pub fn entries(&self) -> [(&'static str, Option<u64>); 9] {
+        [
+            ("context_build", self.context_build_ms),
+            ("planning", self.planning_ms),
+            ("prompt_render", self.prompt_render_ms),
+            ("agent_exec", self.agent_exec_ms),
+            ("tree_validation", self.tree_validation_ms),
+            ("guards", self.guards_ms),
+            ("review", self.review_ms),
+            ("state_update", self.state_update_ms),
+            ("commit", self.commit_ms),
+        ]
+    }
+}
+
+/// Record written to `interrupted.json` when a forced interrupt aborts an iteration.
+#[derive(Debug, Clone, Serialize)]
+pub struct InterruptedRecord {
+    pub run_id: String,
+    pub iter: u32,
+    pub node_id: String,
+    /// Which process was terminated (`executor` or `guard`), or `crash` when the runner died
+    /// and the iteration was recovered by `runner recover`.
+    pub phase: String,
+    pub duration_ms: Option<u64>,
+}
+
+/// Executor follow-ups that were not inserted, written to `dropped_follow_ups.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DroppedFollowUps {
+    /// Why the attempt did not insert them, e.g. `status=retry`, `guard=fail`, `agent error`.
+    pub reason: String,
+    pub follow_ups: Vec<TreeChildSpec>,
 }
 
 /// Resolved paths for iteration log artifacts.
@@ -31,6 +109,7 @@ pub struct IterationPaths {
     pub guard_log_path: PathBuf,
     pub tree_before_path: PathBuf,
     pub tree_after_path: PathBuf,
+    pub interrupted_path: PathBuf,
 }
 
 impl IterationPaths {
@@ -47,6 +126,7 @@ impl IterationPaths {
             guard_log_path: dir.join("guard.log"),
             tree_before_path: dir.join("tree.before.json"),
             tree_after_path: dir.join("tree.after.json"),
+            interrupted_path: dir.join("interrupted.json"),
         }
     }
 }
@@ -81,6 +161,69 @@ pub fn write_iteration(request: &IterationWriteRequest<'_>) -> Result<IterationP
     Ok(paths)
 }
 
+/// Rewrite `meta.json` for an iteration whose logs were already written.
+pub fn write_meta(root: &Path, meta: &IterationMeta) -> Result<PathBuf> {
+    let paths = IterationPaths::new(root, &meta.run_id, meta.iter);
+    write_json(&paths.meta_path, meta)?;
+    Ok(paths.meta_path)
+}
+
+/// Read `meta.json` for iteration `iter`, if it exists.
+pub fn read_meta(root: &Path, run_id: &str, iter: u32) -> Result<Option<IterationMeta>> {
+    let path = IterationPaths::new(root, run_id, iter).meta_path;
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err).with_context(|| format!("read {}", path.display())),
+    };
+    let meta =
+        serde_json::from_str(&contents).with_context(|| format!("parse {}", path.display()))?;
+    Ok(Some(meta))
+}
+
+/// Write `interrupted.json` for an aborted iteration.
+pub fn write_interrupted(root: &Path, record: &InterruptedRecord) -> Result<PathBuf> {
+    let paths = IterationPaths::new(root, &record.run_id, record.iter);
+    fs::create_dir_all(&paths.dir)
+        .with_context(|| format!("create iteration dir {}", paths.dir.display()))?;
+    write_json(&paths.interrupted_path, record)?;
+    Ok(paths.interrupted_path)
+}
+
+/// Write `dropped_follow_ups.json` into the iteration directory `iter_dir`.
+pub fn write_dropped_follow_ups(iter_dir: &Path, dropped: &DroppedFollowUps) -> Result<PathBuf> {
+    let path = iter_dir.join("dropped_follow_ups.json");
+    write_json(&path, dropped)?;
+    Ok(path)
+}
+
+/// Move a leftover directory for `iter` (from an aborted iteration) out of the way.
+///
+/// The directory is renamed to `{iter}.aborted-{n}`; readers that only list numeric
+/// iteration directories ignore it. Returns the new location, if anything was moved.
+pub fn archive_stale_iteration_dir(
+    root: &Path,
+    run_id: &str,
+    iter: u32,
+) -> Result<Option<PathBuf>> {
+    let paths = IterationPaths::new(root, run_id, iter);
+    if !paths.dir.exists() {
+        return Ok(None);
+    }
+    let parent = paths.dir.parent().context("iteration dir has no parent")?;
+    let mut n = 1u32;
+    let archived = loop {
+        let candidate = parent.join(format!("{iter}.aborted-{n}"));
+        if !candidate.exists() {
+            break candidate;
+        }
+        n += 1;
+    };
+    fs::rename(&paths.dir, &archived)
+        .with_context(|| format!("archive {} to {}", paths.dir.display(), archived.display()))?;
+    Ok(Some(archived))
+}
+
 fn write_text(path: &Path, contents: &str) -> Result<()> {
     fs::write(path, contents).with_context(|| format!("write {}", path.display()))
 }
@@ -117,6 +260,7 @@ mod tests {
         assert!(paths.guard_log_path.ends_with("guard.log"));
         assert!(paths.tree_before_path.ends_with("tree.before.json"));
         assert!(paths.tree_after_path.ends_with("tree.after.json"));
+        assert!(paths.interrupted_path.ends_with("interrupted.json"));
     }
 
     /// Verifies write_iteration creates all expected log files.
@@ -134,13 +278,23 @@ mod tests {
             node_id: "node-1".to_string(),
             status: AgentStatus::Done,
             guard: GuardOutcome::Fail,
+            review: None,
             started_at: None,
             ended_at: None,
             duration_ms: None,
+            diffstat: None,
+            phases: PhaseTimings {
+                agent_exec_ms: Some(1200),
+                guards_ms: Some(300),
+                ..PhaseTimings::default()
+            },
+            prompt_templates: Vec::new(),
+            interrupted: None,
         };
         let output = AgentOutput {
             status: AgentStatus::Done,
             summary: "summary".to_string(),
+            follow_ups: Vec::new(),
         };
 
         let paths = write_iteration(&IterationWriteRequest {
@@ -160,5 +314,45 @@ mod tests {
         assert!(paths.guard_log_path.is_file());
         assert!(paths.tree_before_path.is_file());
         assert!(paths.tree_after_path.is_file());
+        let read = read_meta(root, "run-9", 1).expect("read").expect("meta");
+        assert_eq!(read, meta);
+        assert!(read_meta(root, "run-9", 2).expect("read").is_none());
+    }
+
+    /// Verifies `meta.json` written before phase timings existed still parses.
+    #[test]
+    fn meta_without_phases_parses() {
+        let meta: IterationMeta = serde_json::from_str(
+            r#"{"run_id":"r","iter":1,"node_id":"n","status":"retry","guard":"skipped",
+                "started_at":null,"ended_at":null,"duration_ms":5,"diffstat":null}"#,
+        )
+        .expect("parse");
+        assert_eq!(meta.phases, PhaseTimings::default());
+    }
+
+    /// Verifies leftover iteration dirs are archived under unique non-numeric names.
+    #[test]
+    fn archive_stale_iteration_dir_picks_unused_name() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let root = temp.path();
+        assert!(
+            archive_stale_iteration_dir(root, "run-1", 2)
+                .expect("archive")
+                .is_none()
+        );
+
+        let paths = IterationPaths::new(root, "run-1", 2);
+        fs::create_dir_all(&paths.dir).expect("dir");
+        let first = archive_stale_iteration_dir(root, "run-1", 2)
+            .expect("archive")
+            .expect("moved");
+        assert!(first.ends_with("2.aborted-1"));
+
+        fs::create_dir_all(&paths.dir).expect("dir");
+        let second = archive_stale_iteration_dir(root, "run-1", 2)
+            .expect("archive")
+            .expect("moved");
+        assert!(second.ends_with("2.aborted-2"));
+        assert!(!paths.dir.exists());
     }
 }
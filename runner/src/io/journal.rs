@@ -0,0 +1,219 @@
+//! Append-only run journal at `.runner/iterations/{run_id}/events.jsonl`.
+//!
+//! Iteration directories are only complete once a step ends; the journal is written as the
+//! step progresses, one timestamped JSON record per line, so it stays useful for steps that
+//! failed or were killed midway. Appends are best-effort: a failed write is logged and never
+//! fails the step.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::core::types::{AgentStatus, GuardOutcome};
+
+/// Journal file name under `.runner/iterations/{run_id}/`.
+pub const JOURNAL_FILE_NAME: &str = "events.jsonl";
+
+/// One line of `events.jsonl`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalRecord {
+    /// RFC 3339 timestamp with millisecond precision.
+    pub ts: String,
+    pub run_id: String,
+    /// Iteration the event belongs to; absent for loop-level events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iter: Option<u32>,
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+/// Typed journal events, tagged by `type`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JournalEvent {
+    LoopStarted {
+        next_iter: u32,
+    },
+    /// `reason` is `complete`, `stuck`, `limit`, `interrupted`, `max_steps`, `node_passed`,
+    /// `guard_failed`, `deadline`, `run_duration`, `requested` or `error`.
+    LoopStopped {
+        reason: String,
+        steps: u32,
+    },
+    /// The loop found `.runner/PAUSE` and is waiting for `runner resume`.
+    Paused,
+    Resumed,
+    StepStarted,
+    NodeSelected {
+        node_id: String,
+        path: String,
+        attempts: u32,
+        max_attempts: u32,
+    },
+    ContextWritten,
+    /// `phase` is `decompose` or `execute`.
+    ExecStarted {
+        node_id: String,
+        phase: String,
+    },
+    /// `status` is absent when the agent failed to produce output, and for the `plan` phase,
+    /// whose output has no status.
+    ExecFinished {
+        node_id: String,
+        phase: String,
+        status: Option<AgentStatus>,
+        duration_ms: u64,
+    },
+    GuardStarted,
+    GuardFinished {
+        outcome: Option<GuardOutcome>,
+        duration_ms: u64,
+    },
+    StateUpdated {
+        status: AgentStatus,
+        guard: GuardOutcome,
+        next_iter: u32,
+    },
+    Committed {
+        node_id: String,
+        status: AgentStatus,
+        guard: GuardOutcome,
+    },
+    /// A forced interrupt aborted the iteration during `phase`.
+    Interrupted {
+        phase: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Appends records for one run, optionally scoped to one iteration.
+#[derive(Debug, Clone)]
+pub struct Journal {
+    path: PathBuf,
+    run_id: String,
+    iter: Option<u32>,
+}
+
+impl Journal {
+    pub fn new(root: &Path, run_id: &str) -> Self {
+        Self {
+            path: journal_path(root, run_id),
+            run_id: run_id.to_string(),
+            iter: None,
+        }
+    }
+
+    /// A journal whose records carry `iter`.
+    pub fn for_iter(&self, iter: u32) -> Self {
+        Self {
+            iter: Some(iter),
+            ..self.clone()
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `event`, logging (not returning) write failures.
+    pub fn record(&self, event: JournalEvent) {
+        let record = JournalRecord {
+            ts: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            run_id: self.run_id.clone(),
+            iter: self.iter,
+            event,
+        };
+        if let Err(err) = self.append(&record) {
+            warn!(error = %format!("{err:#}"), path = %self.path.display(), "journal append failed");
+        }
+    }
+
+    fn append(&self, record: &JournalRecord) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("create {}", parent.display()))?;
+        }
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("open {}", self.path.display()))?;
+        // A single write per record keeps lines intact for concurrent readers.
+        file.write_all(line.as_bytes())
+            .with_context(|| format!("append {}", self.path.display()))
+    }
+}
+
+/// Path of the journal for `run_id`.
+pub fn journal_path(root: &Path, run_id: &str) -> PathBuf {
+    root.join(".runner")
+        .join("iterations")
+        .join(run_id)
+        .join(JOURNAL_FILE_NAME)
+}
+
+/// Read all records of a journal; a missing file reads as empty.
+pub fn read_journal(path: &Path) -> Result<Vec<JournalRecord>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err).with_context(|| format!("read {}", path.display())),
+    };
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .map(|(idx, line)| {
+            serde_json::from_str(line)
+                .with_context(|| format!("parse {} line {}", path.display(), idx + 1))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_append_in_order_and_round_trip() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let journal = Journal::new(temp.path(), "run-1");
+        journal.record(JournalEvent::LoopStarted { next_iter: 1 });
+        let step = journal.for_iter(1);
+        step.record(JournalEvent::StepStarted);
+        step.record(JournalEvent::Committed {
+            node_id: "root".to_string(),
+            status: AgentStatus::Done,
+            guard: GuardOutcome::Pass,
+        });
+
+        let records = read_journal(journal.path()).expect("read");
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].iter, None);
+        assert_eq!(records[0].event, JournalEvent::LoopStarted { next_iter: 1 });
+        assert_eq!(records[1].iter, Some(1));
+        assert_eq!(records[1].event, JournalEvent::StepStarted);
+        assert!(records.iter().all(|record| record.run_id == "run-1"));
+
+        let raw = fs::read_to_string(journal.path()).expect("raw");
+        let last: serde_json::Value =
+            serde_json::from_str(raw.lines().last().expect("line")).expect("json");
+        assert_eq!(last["type"], "committed");
+        assert_eq!(last["status"], "done");
+        assert!(last["ts"].as_str().expect("ts").ends_with('Z'));
+    }
+
+    #[test]
+    fn missing_journal_reads_empty() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let records = read_journal(&journal_path(temp.path(), "run-1")).expect("read");
+        assert!(records.is_empty());
+    }
+}
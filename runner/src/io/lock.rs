@@ -0,0 +1,272 @@
+//! Advisory lock preventing concurrent runner instances in one repo.
+//!
+//! Mutating commands (`start`, `step`, `loop`, `recover`, ...) hold `.runner/runner.lock`
+//! for their whole duration. The file records who holds it; a lock whose pid no longer
+//! exists on this host is stale and is taken over. The file is gitignored.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+/// Lock file name under `.runner/`.
+pub const LOCK_FILE_NAME: &str = "runner.lock";
+
+/// Contents of `.runner/runner.lock`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockInfo {
+    pub pid: u32,
+    pub host: String,
+    /// RFC 3339 timestamp of when the lock was taken.
+    pub started_at: String,
+    /// Runner subcommand holding the lock (e.g. `loop`).
+    pub command: String,
+}
+
+/// Liveness of a lock holder as seen from this process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HolderState {
+    /// The holder's pid exists on this host.
+    Alive,
+    /// The holder's pid does not exist on this host.
+    Dead,
+    /// The holder runs on another host; liveness cannot be checked.
+    Unknown,
+}
+
+impl HolderState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HolderState::Alive => "alive",
+            HolderState::Dead => "stale",
+            HolderState::Unknown => "unknown",
+        }
+    }
+}
+
+/// Error returned when another live runner instance holds the lock.
+#[derive(Debug, Clone)]
+pub struct LockHeldError {
+    pub holder: LockInfo,
+}
+
+impl std::fmt::Display for LockHeldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "runner lock held by pid {} on {} (`runner {}` since {}); if that process is gone, \
+             remove .runner/{LOCK_FILE_NAME}",
+            self.holder.pid, self.holder.host, self.holder.command, self.holder.started_at
+        )
+    }
+}
+
+impl std::error::Error for LockHeldError {}
+
+/// Held lock; the lock file is removed on drop.
+#[derive(Debug)]
+pub struct RunnerLock {
+    path: PathBuf,
+    info: LockInfo,
+}
+
+impl RunnerLock {
+    /// Take the lock for `command`, replacing a stale lock left by a dead process.
+    pub fn acquire(runner_dir: &Path, command: &str) -> Result<Self> {
+        fs::create_dir_all(runner_dir)
+            .with_context(|| format!("create {}", runner_dir.display()))?;
+        let path = runner_dir.join(LOCK_FILE_NAME);
+        let info = LockInfo {
+            pid: std::process::id(),
+            host: current_host(),
+            started_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+            command: command.to_string(),
+        };
+
+        // One retry: after removing a stale lock another process may win the race.
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let mut buf = serde_json::to_string_pretty(&info)?;
+                    buf.push('\n');
+                    file.write_all(buf.as_bytes())
+                        .with_context(|| format!("write {}", path.display()))?;
+                    debug!(path = %path.display(), command, "lock acquired");
+                    return Ok(Self { path, info });
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let holder = match read_lock(runner_dir)? {
+                        Some(holder) => holder,
+                        // Released between open and read; try again.
+                        None => continue,
+                    };
+                    if holder_state(&holder) != HolderState::Dead {
+                        return Err(LockHeldError { holder }.into());
+                    }
+                    warn!(pid = holder.pid, host = %holder.host, "removing stale runner lock");
+                    match fs::remove_file(&path) {
+                        Ok(()) => {}
+                        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                        Err(err) => {
+                            return Err(err).with_context(|| format!("remove {}", path.display()));
+                        }
+                    }
+                }
+                Err(err) => {
+                    return Err(err).with_context(|| format!("create {}", path.display()));
+                }
+            }
+        }
+        let holder = read_lock(runner_dir)?.context("runner lock contention")?;
+        Err(LockHeldError { holder }.into())
+    }
+
+    pub fn info(&self) -> &LockInfo {
+        &self.info
+    }
+}
+
+impl Drop for RunnerLock {
+    fn drop(&mut self) {
+        // Only remove the file if it is still ours.
+        let ours = fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<LockInfo>(&contents).ok())
+            .is_some_and(|info| info == self.info);
+        if ours && let Err(err) = fs::remove_file(&self.path) {
+            warn!(error = %err, path = %self.path.display(), "failed to release runner lock");
+        }
+    }
+}
+
+/// Read the current lock holder, if any.
+///
+/// An unreadable lock file is reported as an error rather than treated as free.
+pub fn read_lock(runner_dir: &Path) -> Result<Option<LockInfo>> {
+    let path = runner_dir.join(LOCK_FILE_NAME);
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err).with_context(|| format!("read {}", path.display())),
+    };
+    let info =
+        serde_json::from_str(&contents).with_context(|| format!("parse {}", path.display()))?;
+    Ok(Some(info))
+}
+
+/// Check whether the lock holder is still running.
+pub fn holder_state(info: &LockInfo) -> HolderState {
+    if info.host != current_host() {
+        return HolderState::Unknown;
+    }
+    if pid_alive(info.pid) {
+        HolderState::Alive
+    } else {
+        HolderState::Dead
+    }
+}
+
+#[cfg(unix)]
+fn pid_alive(pid: u32) -> bool {
+    use nix::errno::Errno;
+    use nix::sys::signal::kill;
+    use nix::unistd::Pid;
+
+    let Ok(raw) = i32::try_from(pid) else {
+        return false;
+    };
+    // Signal 0 only checks for existence; EPERM means it exists but belongs to someone else.
+    match kill(Pid::from_raw(raw), None) {
+        Ok(()) => true,
+        Err(Errno::EPERM) => true,
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn pid_alive(_pid: u32) -> bool {
+    // Without a portable liveness check, never treat a lock as stale.
+    true
+}
+
+#[cfg(unix)]
+fn current_host() -> String {
+    nix::unistd::gethostname()
+        .ok()
+        .and_then(|name| name.into_string().ok())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[cfg(not(unix))]
+fn current_host() -> String {
+    std::env::var("COMPUTERNAME").unwrap_or_else(|_| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acquire_records_holder_and_releases_on_drop() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let runner_dir = temp.path().join(".runner");
+
+        let lock = RunnerLock::acquire(&runner_dir, "loop").expect("acquire");
+        let holder = read_lock(&runner_dir).expect("read").expect("holder");
+        assert_eq!(&holder, lock.info());
+        assert_eq!(holder.pid, std::process::id());
+        assert_eq!(holder.command, "loop");
+        assert_eq!(holder_state(&holder), HolderState::Alive);
+
+        let err = RunnerLock::acquire(&runner_dir, "step").unwrap_err();
+        let held = err
+            .downcast_ref::<LockHeldError>()
+            .expect("lock held error");
+        assert_eq!(held.holder.command, "loop");
+
+        drop(lock);
+        assert!(read_lock(&runner_dir).expect("read").is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn acquire_replaces_stale_lock_from_dead_pid() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let runner_dir = temp.path().join(".runner");
+        fs::create_dir_all(&runner_dir).expect("runner dir");
+
+        // A reaped child's pid is free (until reused, which is unlikely within the test).
+        let mut child = std::process::Command::new("true").spawn().expect("spawn");
+        let dead_pid = child.id();
+        child.wait().expect("wait");
+        let stale = LockInfo {
+            pid: dead_pid,
+            host: current_host(),
+            started_at: "2020-01-01T00:00:00Z".to_string(),
+            command: "loop".to_string(),
+        };
+        fs::write(
+            runner_dir.join(LOCK_FILE_NAME),
+            serde_json::to_string(&stale).expect("json"),
+        )
+        .expect("write stale lock");
+        assert_eq!(holder_state(&stale), HolderState::Dead);
+
+        let lock = RunnerLock::acquire(&runner_dir, "step").expect("acquire");
+        assert_eq!(lock.info().pid, std::process::id());
+    }
+
+    #[test]
+    fn foreign_host_lock_is_not_stale() {
+        let info = LockInfo {
+            pid: u32::MAX,
+            host: format!("{}-elsewhere", current_host()),
+            started_at: "2020-01-01T00:00:00Z".to_string(),
+            command: "loop".to_string(),
+        };
+        assert_eq!(holder_state(&info), HolderState::Unknown);
+    }
+}
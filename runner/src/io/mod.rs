@@ -6,15 +6,24 @@
 //! - Side effects are explicit and auditable
 //! - Test doubles can replace real I/O (see `test_support`)
 
+pub mod attempts;
 pub mod config;
 pub mod context;
+pub mod control;
 pub mod executor;
 pub mod git;
 pub mod goal;
 pub mod guards;
+pub mod hooks;
 pub mod init;
+pub mod interrupt;
 pub mod iteration_log;
+pub mod journal;
+pub mod lock;
+pub mod notes;
+pub mod notify;
 pub mod process;
 pub mod prompt;
+pub mod repo_map;
 pub mod run_state;
 pub mod tree_store;
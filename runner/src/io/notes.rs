@@ -0,0 +1,374 @@
+//! Per-node notes at `.runner/state/notes/<node-id>.md`.
+//!
+//! Agents run in fresh sessions; notes let an attempt leave findings, dead ends and relevant
+//! files for later attempts on the same node and its descendants. The agent may only append
+//! to the selected node's file, so notes are snapshotted before the agent runs and compared
+//! afterwards.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(not(unix))]
+use anyhow::anyhow;
+use anyhow::{Context, Result};
+
+use crate::tree::Node;
+
+/// Notes directory for the repo at `root`.
+pub fn notes_dir(root: &Path) -> PathBuf {
+    root.join(".runner").join("state").join("notes")
+}
+
+/// File name for `node_id`'s notes; `None` when the id is not a safe file name.
+pub fn note_file_name(node_id: &str) -> Option<String> {
+    let safe = !node_id.is_empty()
+        && !node_id.starts_with('.')
+        && node_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_' || c == '-');
+    safe.then(|| format!("{node_id}.md"))
+}
+
+/// Contents of the notes directory, keyed by path relative to it (directories end in `/`; `.`
+/// is the notes directory itself when it is a symlink).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotesSnapshot {
+    entries: BTreeMap<String, NotesEntry>,
+}
+
+/// One entry of a [`NotesSnapshot`]. Symlinks are recorded, not followed, so a link out of the
+/// notes directory is a change of its own instead of a copy of whatever it points at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum NotesEntry {
+    Dir,
+    File(Vec<u8>),
+    Symlink(PathBuf),
+}
+
+impl NotesSnapshot {
+    /// Read every entry under `notes_dir(root)`; empty when the directory does not exist.
+    pub fn capture(root: &Path) -> Result<Self> {
+        let mut snapshot = Self::default();
+        let dir = notes_dir(root);
+        let Ok(meta) = fs::symlink_metadata(&dir) else {
+            return Ok(snapshot);
+        };
+        if meta.file_type().is_symlink() {
+            let target = fs::read_link(&dir).with_context(|| format!("read {}", dir.display()))?;
+            snapshot
+                .entries
+                .insert(".".to_string(), NotesEntry::Symlink(target));
+        } else if meta.is_dir() {
+            snapshot.capture_dir(&dir, "")?;
+        }
+        Ok(snapshot)
+    }
+
+    fn capture_dir(&mut self, dir: &Path, prefix: &str) -> Result<()> {
+        let entries = fs::read_dir(dir).with_context(|| format!("read {}", dir.display()))?;
+        for entry in entries {
+            let entry = entry.with_context(|| format!("read {}", dir.display()))?;
+            let name = format!("{prefix}{}", entry.file_name().to_string_lossy());
+            let path = entry.path();
+            let file_type = entry
+                .file_type()
+                .with_context(|| format!("read {}", path.display()))?;
+            if file_type.is_symlink() {
+                let target =
+                    fs::read_link(&path).with_context(|| format!("read {}", path.display()))?;
+                self.entries.insert(name, NotesEntry::Symlink(target));
+            } else if file_type.is_dir() {
+                self.entries.insert(format!("{name}/"), NotesEntry::Dir);
+                self.capture_dir(&path, &format!("{name}/"))?;
+            } else {
+                let bytes = fs::read(&path).with_context(|| format!("read {}", path.display()))?;
+                self.entries.insert(name, NotesEntry::File(bytes));
+            }
+        }
+        Ok(())
+    }
+
+    /// Notes recorded for `node_id`, if any.
+    pub fn get(&self, node_id: &str) -> Option<Cow<'_, str>> {
+        let name = note_file_name(node_id)?;
+        match self.entries.get(&name)? {
+            // Lossy so binary junk still renders instead of hiding the node's notes.
+            NotesEntry::File(bytes) => Some(String::from_utf8_lossy(bytes)),
+            NotesEntry::Dir | NotesEntry::Symlink(_) => None,
+        }
+    }
+
+    /// Replace the notes directory with this snapshot.
+    pub fn restore(&self, root: &Path) -> Result<()> {
+        let dir = notes_dir(root);
+        if let Ok(meta) = fs::symlink_metadata(&dir) {
+            if meta.is_dir() {
+                fs::remove_dir_all(&dir)
+            } else {
+                fs::remove_file(&dir)
+            }
+            .with_context(|| format!("remove {}", dir.display()))?;
+        }
+        if let Some(NotesEntry::Symlink(target)) = self.entries.get(".") {
+            return restore_symlink(target, &dir);
+        }
+        if self.entries.is_empty() {
+            return Ok(());
+        }
+        fs::create_dir_all(&dir).with_context(|| format!("create {}", dir.display()))?;
+        for (name, entry) in &self.entries {
+            let path = dir.join(name.trim_end_matches('/'));
+            match entry {
+                NotesEntry::Dir => fs::create_dir_all(&path)
+                    .with_context(|| format!("create {}", path.display()))?,
+                NotesEntry::File(bytes) => {
+                    fs::write(&path, bytes).with_context(|| format!("write {}", path.display()))?;
+                }
+                NotesEntry::Symlink(target) => restore_symlink(target, &path)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn restore_symlink(target: &Path, path: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(target, path).with_context(|| format!("link {}", path.display()))
+}
+
+#[cfg(not(unix))]
+fn restore_symlink(_target: &Path, path: &Path) -> Result<()> {
+    Err(anyhow!("cannot restore symlink {}", path.display()))
+}
+
+/// Check that between `before` and `after` only `node_id`'s notes changed, by appending.
+///
+/// Returns one message per violation; empty when the change is allowed. Symlinks are always a
+/// violation: notes are plain files inside the notes directory.
+pub fn check_notes_changes(
+    before: &NotesSnapshot,
+    after: &NotesSnapshot,
+    node_id: &str,
+) -> Vec<String> {
+    let own = note_file_name(node_id);
+    let mut errors = Vec::new();
+    for (name, old) in &before.entries {
+        match after.entries.get(name) {
+            None => errors.push(format!("notes file '{name}' was removed")),
+            Some(NotesEntry::Symlink(_)) => {}
+            Some(new) if new == old => {}
+            Some(new) if Some(name) == own.as_ref() => {
+                let appended = matches!(
+                    (old, new),
+                    (NotesEntry::File(old), NotesEntry::File(new)) if new.starts_with(old)
+                );
+                if !appended {
+                    errors.push(format!(
+                        "notes file '{name}' was rewritten (notes are append-only)"
+                    ));
+                }
+            }
+            Some(_) => errors.push(format!(
+                "notes file '{name}' belongs to another node (only '{}' may change)",
+                own.as_deref().unwrap_or("<none>")
+            )),
+        }
+    }
+    for (name, entry) in &after.entries {
+        if matches!(entry, NotesEntry::Symlink(_)) {
+            errors.push(format!(
+                "notes entry '{name}' is a symlink (notes must be regular files)"
+            ));
+        } else if !before.entries.contains_key(name) && Some(name) != own.as_ref() {
+            errors.push(format!(
+                "notes file '{name}' was created (only '{}' may change)",
+                own.as_deref().unwrap_or("<none>")
+            ));
+        }
+    }
+    errors
+}
+
+/// Render the notes of every node in `lineage` (root first) for the prompt's notes section.
+pub fn render_lineage_notes(snapshot: &NotesSnapshot, lineage: &[&Node]) -> Option<String> {
+    let mut buf = String::new();
+    for node in lineage {
+        let Some(notes) = snapshot.get(&node.id) else {
+            continue;
+        };
+        let notes = notes.trim();
+        if notes.is_empty() {
+            continue;
+        }
+        if !buf.is_empty() {
+            buf.push('\n');
+        }
+        buf.push_str(&format!("#### {} ({})\n\n{notes}\n", node.id, node.title));
+    }
+    (!buf.is_empty()).then_some(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::node;
+
+    fn snapshot(entries: &[(&str, &str)]) -> NotesSnapshot {
+        NotesSnapshot {
+            entries: entries
+                .iter()
+                .map(|(name, contents)| {
+                    let entry = if name.ends_with('/') {
+                        NotesEntry::Dir
+                    } else {
+                        NotesEntry::File(contents.as_bytes().to_vec())
+                    };
+                    (name.to_string(), entry)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn note_file_names_reject_unsafe_ids() {
+        assert_eq!(note_file_name("root.1"), Some("root.1.md".to_string()));
+        assert_eq!(note_file_name("../x"), None);
+        assert_eq!(note_file_name(".hidden"), None);
+        assert_eq!(note_file_name(""), None);
+    }
+
+    #[test]
+    fn only_appends_to_own_notes_are_allowed() {
+        let before = snapshot(&[("root.md", "found A\n"), ("root.1.md", "tried B\n")]);
+
+        let appended = snapshot(&[("root.md", "found A\n"), ("root.1.md", "tried B\nC too\n")]);
+        assert!(check_notes_changes(&before, &appended, "root.1").is_empty());
+
+        let created = snapshot(&[
+            ("root.md", "found A\n"),
+            ("root.1.md", "tried B\n"),
+            ("root.2.md", "x"),
+        ]);
+        assert!(check_notes_changes(&before, &created, "root.2").is_empty());
+
+        let rewritten = snapshot(&[("root.md", "found A\n"), ("root.1.md", "different\n")]);
+        assert_eq!(
+            check_notes_changes(&before, &rewritten, "root.1"),
+            ["notes file 'root.1.md' was rewritten (notes are append-only)"]
+        );
+
+        let other = snapshot(&[
+            ("root.md", "found A\nmore\n"),
+            ("root.1.md", "tried B\n"),
+            ("x/", ""),
+        ]);
+        let errors = check_notes_changes(&before, &other, "root.1");
+        assert_eq!(errors.len(), 2, "{errors:?}");
+        assert!(errors[0].contains("'root.md' belongs to another node"));
+        assert!(errors[1].contains("'x/' was created"));
+
+        let removed = snapshot(&[("root.1.md", "tried B\n")]);
+        assert_eq!(
+            check_notes_changes(&before, &removed, "root.1"),
+            ["notes file 'root.md' was removed"]
+        );
+    }
+
+    #[test]
+    fn capture_and_restore_round_trip() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let root = temp.path();
+        assert_eq!(
+            NotesSnapshot::capture(root).expect("capture"),
+            NotesSnapshot::default()
+        );
+
+        let dir = notes_dir(root);
+        fs::create_dir_all(&dir).expect("dir");
+        fs::write(dir.join("root.md"), "found A\n").expect("write");
+        let before = NotesSnapshot::capture(root).expect("capture");
+        assert_eq!(before.get("root").as_deref(), Some("found A\n"));
+
+        fs::write(dir.join("root.md"), "oops").expect("write");
+        fs::create_dir_all(dir.join("junk")).expect("dir");
+        before.restore(root).expect("restore");
+        assert_eq!(NotesSnapshot::capture(root).expect("capture"), before);
+    }
+
+    #[test]
+    fn restore_keeps_bytes_that_are_not_utf8() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let root = temp.path();
+        let dir = notes_dir(root);
+        fs::create_dir_all(&dir).expect("dir");
+        fs::write(dir.join("root.md"), b"found A \xff\xfe\n").expect("write");
+        let before = NotesSnapshot::capture(root).expect("capture");
+
+        fs::write(dir.join("root.md"), "oops").expect("write");
+        before.restore(root).expect("restore");
+        assert_eq!(
+            fs::read(dir.join("root.md")).expect("read"),
+            b"found A \xff\xfe\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_are_violations_and_not_followed() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let root = temp.path();
+        let dir = notes_dir(root);
+        fs::create_dir_all(&dir).expect("dir");
+        fs::write(dir.join("root.md"), "found A\n").expect("write");
+        let outside = root.join("outside.md");
+        fs::write(&outside, "found A\n").expect("write outside");
+        let before = NotesSnapshot::capture(root).expect("capture");
+
+        // The link points at identical contents, so following it would hide the change.
+        fs::remove_file(dir.join("root.md")).expect("remove");
+        std::os::unix::fs::symlink(&outside, dir.join("root.md")).expect("symlink");
+        let after = NotesSnapshot::capture(root).expect("capture");
+        assert_eq!(
+            check_notes_changes(&before, &after, "root"),
+            ["notes entry 'root.md' is a symlink (notes must be regular files)"]
+        );
+        before.restore(root).expect("restore");
+        assert!(
+            !fs::symlink_metadata(dir.join("root.md"))
+                .expect("meta")
+                .is_symlink()
+        );
+        assert_eq!(NotesSnapshot::capture(root).expect("capture"), before);
+
+        // Replacing the whole notes directory with a link is caught the same way.
+        let elsewhere = root.join("elsewhere");
+        fs::create_dir_all(&elsewhere).expect("elsewhere");
+        fs::write(elsewhere.join("root.md"), "found A\n").expect("write");
+        fs::remove_dir_all(&dir).expect("remove dir");
+        std::os::unix::fs::symlink(&elsewhere, &dir).expect("symlink dir");
+        let after = NotesSnapshot::capture(root).expect("capture");
+        let errors = check_notes_changes(&before, &after, "root");
+        assert!(
+            errors.contains(
+                &"notes entry '.' is a symlink (notes must be regular files)".to_string()
+            ),
+            "{errors:?}"
+        );
+        before.restore(root).expect("restore");
+        assert!(fs::symlink_metadata(&dir).expect("meta").is_dir());
+        assert_eq!(fs::read_to_string(outside).expect("outside"), "found A\n");
+        assert_eq!(NotesSnapshot::capture(root).expect("capture"), before);
+    }
+
+    #[test]
+    fn lineage_notes_render_root_first_and_skip_empty() {
+        let notes = snapshot(&[("root.md", "use the v2 API\n"), ("root.1.md", "  \n")]);
+        let root = node("root", 0);
+        let child = node("root.1", 0);
+        let text = render_lineage_notes(&notes, &[&root, &child]).expect("notes");
+        assert_eq!(text, "#### root (root title)\n\nuse the v2 API\n");
+        assert_eq!(render_lineage_notes(&notes, &[&child]), None);
+    }
+}
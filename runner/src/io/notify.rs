@@ -0,0 +1,342 @@
+//! Webhook notifier for run events configured under `[notify]` in `config.toml`.
+//!
+//! Each event is `POST`ed as a JSON object with an `event` tag. When a secret is available the
+//! body is signed with HMAC-SHA256 and sent as `X-Runner-Signature-256: sha256=<hex>`, the
+//! same scheme GitHub webhooks use, so bridges can verify requests with stock helpers.
+//! Delivery is best-effort: failures are retried and then logged, never failing the run.
+
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use tracing::{debug, warn};
+
+use crate::core::types::{AgentStatus, GuardOutcome, ReviewVerdict};
+use crate::io::config::NotifyConfig;
+
+/// Header carrying the event name.
+pub const EVENT_HEADER: &str = "X-Runner-Event";
+/// Header carrying the HMAC-SHA256 signature of the body.
+pub const SIGNATURE_HEADER: &str = "X-Runner-Signature-256";
+
+/// Delay before the first retry; doubled for each further retry.
+const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Run events delivered to the webhook.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum NotifyEvent {
+    /// An iteration was committed.
+    IterationFinished {
+        run_id: String,
+        iter: u32,
+        node_id: String,
+        status: AgentStatus,
+        guard: GuardOutcome,
+        /// Reviewer verdict, when the reviewer ran.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        review: Option<ReviewVerdict>,
+    },
+    /// An iteration finished its node with `status=done`, passing guards and no rejecting
+    /// review.
+    NodePassed {
+        run_id: String,
+        iter: u32,
+        node_id: String,
+    },
+    /// The next selected leaf has exhausted its attempts.
+    Stuck {
+        run_id: String,
+        node_id: String,
+        path: String,
+        attempts: u32,
+        max_attempts: u32,
+    },
+    /// Only blocked nodes are left to work on.
+    Blocked {
+        run_id: String,
+        node_ids: Vec<String>,
+    },
+    /// The tree has no open leaves left.
+    Complete { run_id: String, steps: u32 },
+    /// The run used up `max_iterations` with work remaining.
+    IterationLimit {
+        run_id: String,
+        next_iter: u32,
+        max_iterations: u32,
+    },
+    /// The runner stopped on an unexpected error.
+    RunnerError {
+        run_id: Option<String>,
+        message: String,
+    },
+}
+
+impl NotifyEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            NotifyEvent::IterationFinished { .. } => "iteration_finished",
+            NotifyEvent::NodePassed { .. } => "node_passed",
+            NotifyEvent::Stuck { .. } => "stuck",
+            NotifyEvent::Blocked { .. } => "blocked",
+            NotifyEvent::Complete { .. } => "complete",
+            NotifyEvent::IterationLimit { .. } => "iteration_limit",
+            NotifyEvent::RunnerError { .. } => "runner_error",
+        }
+    }
+}
+
+/// Request body: the event plus the time it was sent.
+#[derive(Serialize)]
+struct Envelope<'a> {
+    #[serde(flatten)]
+    event: &'a NotifyEvent,
+    sent_at: String,
+}
+
+/// Delivers [`NotifyEvent`]s to a webhook URL.
+#[derive(Debug)]
+pub struct Notifier {
+    url: String,
+    secret: Option<String>,
+    retries: u32,
+    backoff: Duration,
+    agent: ureq::Agent,
+}
+
+impl Notifier {
+    pub fn new(url: &str, secret: Option<String>, timeout: Duration, retries: u32) -> Self {
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(timeout))
+            .http_status_as_error(false)
+            .build()
+            .into();
+        Self {
+            url: url.to_string(),
+            secret,
+            retries,
+            backoff: DEFAULT_RETRY_BACKOFF,
+            agent,
+        }
+    }
+
+    /// Build a notifier from config, or `None` when no URL is configured.
+    ///
+    /// The secret is read from `cfg.secret_env`; without it requests are sent unsigned.
+    pub fn from_config(cfg: &NotifyConfig) -> Option<Self> {
+        let url = cfg.url.as_deref()?;
+        let secret = std::env::var(&cfg.secret_env)
+            .ok()
+            .filter(|secret| !secret.is_empty());
+        if secret.is_none() {
+            warn!(
+                env = %cfg.secret_env,
+                "notify secret not set; webhook requests will be unsigned"
+            );
+        }
+        Some(Self::new(
+            url,
+            secret,
+            Duration::from_secs(cfg.timeout_secs),
+            cfg.retries,
+        ))
+    }
+
+    /// Override the delay before the first retry.
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Deliver `event`, logging (not returning) a final failure.
+    pub fn notify(&self, event: &NotifyEvent) {
+        if let Err(err) = self.send(event) {
+            warn!(event = event.name(), error = %format!("{err:#}"), "webhook delivery failed");
+        }
+    }
+
+    /// Deliver `event`, retrying timeouts, connection errors, 408/429 and 5xx responses.
+    pub fn send(&self, event: &NotifyEvent) -> Result<()> {
+        let body = serde_json::to_vec(&Envelope {
+            event,
+            sent_at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        })?;
+        let signature = self.secret.as_deref().map(|secret| sign(secret, &body));
+
+        let mut last_err = anyhow!("no delivery attempted");
+        for attempt in 0..=self.retries {
+            if attempt > 0 {
+                std::thread::sleep(self.backoff * 2u32.saturating_pow(attempt - 1));
+            }
+            let mut request = self
+                .agent
+                .post(&self.url)
+                .header("Content-Type", "application/json")
+                .header(EVENT_HEADER, event.name());
+            if let Some(signature) = &signature {
+                request = request.header(SIGNATURE_HEADER, signature);
+            }
+            match request.send(&body[..]) {
+                Ok(response) => {
+                    let status = response.status().as_u16();
+                    if (200..300).contains(&status) {
+                        debug!(event = event.name(), status, attempt, "webhook delivered");
+                        return Ok(());
+                    }
+                    last_err = anyhow!("webhook responded with HTTP {status}");
+                    if !matches!(status, 408 | 429 | 500..=599) {
+                        return Err(last_err);
+                    }
+                }
+                Err(err) => last_err = anyhow!(err).context("send webhook request"),
+            }
+            debug!(event = event.name(), attempt, error = %last_err, "webhook attempt failed");
+        }
+        Err(last_err.context(format!(
+            "webhook delivery failed after {} attempt(s)",
+            self.retries + 1
+        )))
+    }
+}
+
+/// `sha256=<hex>` HMAC-SHA256 signature of `body` under `secret`.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// Request as seen by the stand-in server.
+    struct Received {
+        headers: Vec<(String, String)>,
+        body: Vec<u8>,
+    }
+
+    impl Received {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        }
+    }
+
+    /// Minimal HTTP/1.1 server answering one request per scripted status code.
+    fn serve(statuses: Vec<u16>) -> (String, mpsc::Receiver<Received>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind");
+        let url = format!("http://{}/hook", listener.local_addr().expect("addr"));
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for status in statuses {
+                let (stream, _) = listener.accept().expect("accept");
+                let mut reader = BufReader::new(stream);
+                let mut headers = Vec::new();
+                let mut line = String::new();
+                reader.read_line(&mut line).expect("request line");
+                loop {
+                    line.clear();
+                    reader.read_line(&mut line).expect("header");
+                    let trimmed = line.trim_end();
+                    if trimmed.is_empty() {
+                        break;
+                    }
+                    let (key, value) = trimmed.split_once(':').expect("header colon");
+                    headers.push((key.trim().to_string(), value.trim().to_string()));
+                }
+                let len = headers
+                    .iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case("content-length"))
+                    .map_or(0, |(_, value)| value.parse::<usize>().expect("length"));
+                let mut body = vec![0; len];
+                reader.read_exact(&mut body).expect("body");
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                )
+                .expect("respond");
+                tx.send(Received { headers, body }).expect("record");
+            }
+        });
+        (url, rx)
+    }
+
+    fn stuck_event() -> NotifyEvent {
+        NotifyEvent::Stuck {
+            run_id: "run-1".to_string(),
+            node_id: "root.2".to_string(),
+            path: "root/b".to_string(),
+            attempts: 3,
+            max_attempts: 3,
+        }
+    }
+
+    #[test]
+    fn sign_matches_known_vector() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn send_posts_signed_json_event() {
+        let (url, rx) = serve(vec![204]);
+        let notifier = Notifier::new(&url, Some("s3cret".to_string()), Duration::from_secs(5), 0);
+
+        notifier.send(&stuck_event()).expect("send");
+
+        let received = rx.recv().expect("request");
+        assert_eq!(received.header(EVENT_HEADER), Some("stuck"));
+        assert_eq!(
+            received.header(SIGNATURE_HEADER),
+            Some(sign("s3cret", &received.body).as_str())
+        );
+        let body: serde_json::Value = serde_json::from_slice(&received.body).expect("json");
+        assert_eq!(body["event"], "stuck");
+        assert_eq!(body["node_id"], "root.2");
+        assert_eq!(body["attempts"], 3);
+        assert!(body["sent_at"].is_string());
+    }
+
+    #[test]
+    fn send_retries_server_errors_until_success() {
+        let (url, rx) = serve(vec![503, 500, 200]);
+        let notifier = Notifier::new(&url, None, Duration::from_secs(5), 2)
+            .with_backoff(Duration::from_millis(1));
+
+        notifier.send(&stuck_event()).expect("send");
+
+        let received: Vec<_> = rx.iter().take(3).collect();
+        assert_eq!(received.len(), 3);
+        assert!(received[0].header(SIGNATURE_HEADER).is_none());
+    }
+
+    #[test]
+    fn send_gives_up_on_client_errors_and_exhausted_retries() {
+        let (url, rx) = serve(vec![400]);
+        let notifier = Notifier::new(&url, None, Duration::from_secs(5), 3)
+            .with_backoff(Duration::from_millis(1));
+        let err = notifier.send(&stuck_event()).unwrap_err();
+        assert!(err.to_string().contains("HTTP 400"), "{err:#}");
+        assert_eq!(rx.iter().count(), 1);
+
+        let (url, rx) = serve(vec![500, 500]);
+        let notifier = Notifier::new(&url, None, Duration::from_secs(5), 1)
+            .with_backoff(Duration::from_millis(1));
+        let err = notifier.send(&stuck_event()).unwrap_err();
+        assert!(format!("{err:#}").contains("after 2 attempt(s)"), "{err:#}");
+        assert_eq!(rx.iter().count(), 2);
+    }
+}
@@ -1,14 +1,21 @@
 //! Helpers for running child processes with timeouts and bounded output.
 
 use std::io::{Read, Write};
-use std::process::{Command, ExitStatus, Stdio};
+use std::process::{Child, Command, ExitStatus, Stdio};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result, anyhow};
 use tracing::{debug, error, instrument, warn};
 use wait_timeout::ChildExt;
 
+use crate::io::interrupt::Interrupt;
+
+/// How often a running child is polled for a forced interrupt.
+const INTERRUPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Grace period between SIGTERM and SIGKILL when terminating a process group.
+const TERMINATE_GRACE: Duration = Duration::from_secs(2);
+
 /// Captured child process output.
 #[derive(Debug)]
 pub struct CommandOutput {
@@ -18,6 +25,8 @@ pub struct CommandOutput {
     pub stdout_truncated: usize,
     pub stderr_truncated: usize,
     pub timed_out: bool,
+    /// The child was terminated because of a forced interrupt.
+    pub interrupted: bool,
 }
 
 impl CommandOutput {
@@ -54,6 +63,7 @@ pub fn run_command_with_timeout(
     stdin: Option<&[u8]>,
     timeout: Duration,
     output_limit_bytes: usize,
+    interrupt: &Interrupt,
 ) -> Result<CommandOutput> {
     if stdin.is_some() {
         cmd.stdin(Stdio::piped());
@@ -61,6 +71,10 @@ pub fn run_command_with_timeout(
         cmd.stdin(Stdio::null());
     }
     cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    // Own process group: terminal signals reach only the runner, which decides when to
+    // forward them (see `io::interrupt`).
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
 
     debug!("spawning child process");
     let mut child = match cmd.spawn() {
@@ -76,7 +90,13 @@ pub fn run_command_with_timeout(
             .stdin
             .take()
             .ok_or_else(|| anyhow!("stdin was not piped"))?;
-        child_stdin.write_all(input).context("write stdin")?;
+        // A child that exits without reading its input is not an error here.
+        match child_stdin.write_all(input) {
+            Err(err) if err.kind() != std::io::ErrorKind::BrokenPipe => {
+                return Err(err).context("write stdin");
+            }
+            _ => {}
+        }
     }
 
     let stdout = child
@@ -91,19 +111,9 @@ pub fn run_command_with_timeout(
     let stdout_handle = thread::spawn(move || read_stream_limited(stdout, output_limit_bytes));
     let stderr_handle = thread::spawn(move || read_stream_limited(stderr, output_limit_bytes));
 
-    let mut timed_out = false;
-    let status = match child.wait_timeout(timeout).context("wait for command")? {
-        Some(status) => status,
-        None => {
-            warn!(
-                timeout_secs = timeout.as_secs(),
-                "command timed out, killing"
-            );
-            timed_out = true;
-            child.kill().context("kill command")?;
-            child.wait().context("wait command after kill")?
-        }
-    };
+    let (status, end) = wait_for_child(&mut child, timeout, interrupt)?;
+    let timed_out = end == WaitEnd::TimedOut;
+    let interrupted = end == WaitEnd::Interrupted;
 
     let (stdout, stdout_truncated) = join_output(stdout_handle).context("join stdout")?;
     let (stderr, stderr_truncated) = join_output(stderr_handle).context("join stderr")?;
@@ -112,7 +122,7 @@ pub fn run_command_with_timeout(
         warn!(stdout_truncated, stderr_truncated, "output truncated");
     }
 
-    debug!(exit_code = ?status.code(), timed_out, "command finished");
+    debug!(exit_code = ?status.code(), timed_out, interrupted, "command finished");
     Ok(CommandOutput {
         status,
         stdout,
@@ -120,9 +130,75 @@ pub fn run_command_with_timeout(
         stdout_truncated,
         stderr_truncated,
         timed_out,
+        interrupted,
     })
 }
 
+/// Why `wait_for_child` returned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WaitEnd {
+    Exited,
+    TimedOut,
+    Interrupted,
+}
+
+/// Wait for `child` up to `timeout`, terminating its process group on timeout or on a forced
+/// interrupt.
+fn wait_for_child(
+    child: &mut Child,
+    timeout: Duration,
+    interrupt: &Interrupt,
+) -> Result<(ExitStatus, WaitEnd)> {
+    let started = Instant::now();
+    loop {
+        let remaining = timeout.saturating_sub(started.elapsed());
+        if remaining.is_zero() {
+            warn!(
+                timeout_secs = timeout.as_secs(),
+                "command timed out, killing"
+            );
+            let status = terminate_child(child)?;
+            return Ok((status, WaitEnd::TimedOut));
+        }
+        if let Some(status) = child
+            .wait_timeout(remaining.min(INTERRUPT_POLL_INTERVAL))
+            .context("wait for command")?
+        {
+            return Ok((status, WaitEnd::Exited));
+        }
+        if interrupt.is_forced() {
+            warn!("forced interrupt, terminating command");
+            let status = terminate_child(child)?;
+            return Ok((status, WaitEnd::Interrupted));
+        }
+    }
+}
+
+/// Terminate the child's process group (SIGTERM, then SIGKILL after a grace period).
+fn terminate_child(child: &mut Child) -> Result<ExitStatus> {
+    #[cfg(unix)]
+    {
+        use nix::sys::signal::{Signal, killpg};
+        use nix::unistd::Pid;
+
+        let pgid = Pid::from_raw(child.id() as i32);
+        if killpg(pgid, Signal::SIGTERM).is_ok()
+            && let Some(status) = child
+                .wait_timeout(TERMINATE_GRACE)
+                .context("wait command after SIGTERM")?
+        {
+            let _ = killpg(pgid, Signal::SIGKILL);
+            return Ok(status);
+        }
+        let _ = killpg(pgid, Signal::SIGKILL);
+    }
+    #[cfg(not(unix))]
+    let _ = TERMINATE_GRACE;
+    // Best effort: the child may already have exited.
+    let _ = child.kill();
+    child.wait().context("wait command after kill")
+}
+
 fn join_output(handle: thread::JoinHandle<Result<(Vec<u8>, usize)>>) -> Result<(Vec<u8>, usize)> {
     match handle.join() {
         Ok(result) => result,
@@ -142,6 +218,7 @@ pub fn run_command_with_stream(
     timeout: Duration,
     output_limit_bytes: usize,
     stream_path: Option<&std::path::Path>,
+    interrupt: &Interrupt,
 ) -> Result<CommandOutput> {
     if stdin.is_some() {
         cmd.stdin(Stdio::piped());
@@ -149,6 +226,10 @@ pub fn run_command_with_stream(
         cmd.stdin(Stdio::null());
     }
     cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+    // Own process group: terminal signals reach only the runner, which decides when to
+    // forward them (see `io::interrupt`).
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut cmd, 0);
 
     debug!("spawning child process");
     let mut child = match cmd.spawn() {
@@ -164,7 +245,13 @@ pub fn run_command_with_stream(
             .stdin
             .take()
             .ok_or_else(|| anyhow!("stdin was not piped"))?;
-        child_stdin.write_all(input).context("write stdin")?;
+        // A child that exits without reading its input is not an error here.
+        match child_stdin.write_all(input) {
+            Err(err) if err.kind() != std::io::ErrorKind::BrokenPipe => {
+                return Err(err).context("write stdin");
+            }
+            _ => {}
+        }
     }
 
     let stdout = child
@@ -198,19 +285,9 @@ pub fn run_command_with_stream(
     });
     let stderr_handle = thread::spawn(move || read_stream_limited(stderr, output_limit_bytes));
 
-    let mut timed_out = false;
-    let status = match child.wait_timeout(timeout).context("wait for command")? {
-        Some(status) => status,
-        None => {
-            warn!(
-                timeout_secs = timeout.as_secs(),
-                "command timed out, killing"
-            );
-            timed_out = true;
-            child.kill().context("kill command")?;
-            child.wait().context("wait command after kill")?
-        }
-    };
+    let (status, end) = wait_for_child(&mut child, timeout, interrupt)?;
+    let timed_out = end == WaitEnd::TimedOut;
+    let interrupted = end == WaitEnd::Interrupted;
 
     let (stdout, stdout_truncated) = join_output(stdout_handle).context("join stdout")?;
     let (stderr, stderr_truncated) = join_output(stderr_handle).context("join stderr")?;
@@ -219,7 +296,7 @@ pub fn run_command_with_stream(
         warn!(stdout_truncated, stderr_truncated, "output truncated");
     }
 
-    debug!(exit_code = ?status.code(), timed_out, "command finished");
+    debug!(exit_code = ?status.code(), timed_out, interrupted, "command finished");
     Ok(CommandOutput {
         status,
         stdout,
@@ -227,6 +304,7 @@ pub fn run_command_with_stream(
         stdout_truncated,
         stderr_truncated,
         timed_out,
+        interrupted,
     })
 }
 
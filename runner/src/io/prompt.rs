@@ -1,17 +1,212 @@
 //! Prompt pack builder for deterministic executor input.
+//!
+//! Templates are embedded in the binary and can be overridden per repo by
+//! `.runner/prompts/{decomposer,planner,executor,reviewer,verifier}.md`. Overrides are checked
+//! when loaded: they must compile and declare the required `contract`, `goal` and `selected`
+//! sections.
 
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow};
 use minijinja::{Environment, context};
-use serde::Serialize;
-use tracing::debug;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{debug, info};
 
+use crate::core::tokens::{TokenEstimator, Tokenizer, truncate_to};
+use crate::io::config::PromptConfig;
 use crate::tree::Node;
 
 const DECOMPOSER_TEMPLATE: &str = include_str!("prompts/decomposer.md");
+const PLANNER_TEMPLATE: &str = include_str!("prompts/planner.md");
 const EXECUTOR_TEMPLATE: &str = include_str!("prompts/executor.md");
+const REVIEWER_TEMPLATE: &str = include_str!("prompts/reviewer.md");
+const VERIFIER_TEMPLATE: &str = include_str!("prompts/verifier.md");
+
+/// Sections every template must mark `required`.
+const REQUIRED_SECTIONS: [&str; 3] = ["contract", "goal", "selected"];
+
+static SECTION_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r"<!--\s*section:(\w+)\s+(required|droppable)\s*-->").unwrap()
+});
+
+/// Prompt templates the runner renders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateKind {
+    Decomposer,
+    Planner,
+    Executor,
+    Reviewer,
+    Verifier,
+}
+
+impl TemplateKind {
+    pub const ALL: [TemplateKind; 5] = [
+        TemplateKind::Decomposer,
+        TemplateKind::Planner,
+        TemplateKind::Executor,
+        TemplateKind::Reviewer,
+        TemplateKind::Verifier,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TemplateKind::Decomposer => "decomposer",
+            TemplateKind::Planner => "planner",
+            TemplateKind::Executor => "executor",
+            TemplateKind::Reviewer => "reviewer",
+            TemplateKind::Verifier => "verifier",
+        }
+    }
+
+    fn embedded(self) -> &'static str {
+        match self {
+            TemplateKind::Decomposer => DECOMPOSER_TEMPLATE,
+            TemplateKind::Planner => PLANNER_TEMPLATE,
+            TemplateKind::Executor => EXECUTOR_TEMPLATE,
+            TemplateKind::Reviewer => REVIEWER_TEMPLATE,
+            TemplateKind::Verifier => VERIFIER_TEMPLATE,
+        }
+    }
+}
+
+/// Which template version rendered a prompt, as recorded in `meta.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptTemplateRef {
+    pub name: String,
+    /// `embedded`, or the override path relative to the repo root.
+    pub source: String,
+    /// Hex SHA-256 of the template source.
+    pub sha256: String,
+}
+
+/// A template source and where it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptTemplate {
+    kind: TemplateKind,
+    source: String,
+    /// Override file, relative to the repo root; `None` for the embedded template.
+    override_path: Option<PathBuf>,
+}
+
+impl PromptTemplate {
+    fn embedded(kind: TemplateKind) -> Self {
+        Self {
+            kind,
+            source: kind.embedded().to_string(),
+            override_path: None,
+        }
+    }
+
+    pub fn reference(&self) -> PromptTemplateRef {
+        PromptTemplateRef {
+            name: self.kind.name().to_string(),
+            source: self
+                .override_path
+                .as_ref()
+                .map_or_else(|| "embedded".to_string(), |path| path.display().to_string()),
+            sha256: hex::encode(Sha256::digest(self.source.as_bytes())),
+        }
+    }
+}
+
+/// The templates used for a step, one per [`TemplateKind`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PromptTemplates {
+    decomposer: PromptTemplate,
+    planner: PromptTemplate,
+    executor: PromptTemplate,
+    reviewer: PromptTemplate,
+    verifier: PromptTemplate,
+}
+
+impl Default for PromptTemplates {
+    fn default() -> Self {
+        Self::embedded()
+    }
+}
+
+impl PromptTemplates {
+    /// The templates compiled into the runner.
+    pub fn embedded() -> Self {
+        Self {
+            decomposer: PromptTemplate::embedded(TemplateKind::Decomposer),
+            planner: PromptTemplate::embedded(TemplateKind::Planner),
+            executor: PromptTemplate::embedded(TemplateKind::Executor),
+            reviewer: PromptTemplate::embedded(TemplateKind::Reviewer),
+            verifier: PromptTemplate::embedded(TemplateKind::Verifier),
+        }
+    }
+
+    /// Load `.runner/prompts/` overrides, falling back to the embedded templates.
+    pub fn load(root: &Path) -> Result<Self> {
+        Ok(Self {
+            decomposer: load_template(root, TemplateKind::Decomposer)?,
+            planner: load_template(root, TemplateKind::Planner)?,
+            executor: load_template(root, TemplateKind::Executor)?,
+            reviewer: load_template(root, TemplateKind::Reviewer)?,
+            verifier: load_template(root, TemplateKind::Verifier)?,
+        })
+    }
+
+    pub fn get(&self, kind: TemplateKind) -> &PromptTemplate {
+        match kind {
+            TemplateKind::Decomposer => &self.decomposer,
+            TemplateKind::Planner => &self.planner,
+            TemplateKind::Executor => &self.executor,
+            TemplateKind::Reviewer => &self.reviewer,
+            TemplateKind::Verifier => &self.verifier,
+        }
+    }
+}
+
+/// Path of the override for `kind` under `root`.
+pub fn template_override_path(root: &Path, kind: TemplateKind) -> PathBuf {
+    root.join(".runner")
+        .join("prompts")
+        .join(format!("{}.md", kind.name()))
+}
+
+fn load_template(root: &Path, kind: TemplateKind) -> Result<PromptTemplate> {
+    let path = template_override_path(root, kind);
+    let Some(source) = read_optional(&path)? else {
+        return Ok(PromptTemplate::embedded(kind));
+    };
+    validate_template(kind.name(), &source)
+        .with_context(|| format!("invalid prompt template {}", path.display()))?;
+    info!(template = kind.name(), path = %path.display(), "using prompt template override");
+    Ok(PromptTemplate {
+        kind,
+        source,
+        override_path: Some(path.strip_prefix(root).unwrap_or(&path).to_path_buf()),
+    })
+}
+
+/// Check that `source` compiles and marks every section in [`REQUIRED_SECTIONS`] required.
+pub fn validate_template(name: &str, source: &str) -> Result<()> {
+    let mut env = Environment::new();
+    env.add_template(name, source)
+        .map_err(|err| anyhow!("template does not compile: {err}"))?;
+    let declared: Vec<(&str, bool)> = SECTION_RE
+        .captures_iter(source)
+        .map(|caps| {
+            (
+                caps.get(1).unwrap().as_str(),
+                caps.get(2).unwrap().as_str() == "required",
+            )
+        })
+        .collect();
+    for section in REQUIRED_SECTIONS {
+        if !declared.contains(&(section, true)) {
+            return Err(anyhow!(
+                "missing `<!-- section:{section} required -->` marker"
+            ));
+        }
+    }
+    Ok(())
+}
 
 /// Selected node context for template rendering.
 #[derive(Debug, Clone, Serialize)]
@@ -22,11 +217,15 @@ struct SelectedNodeContext {
     goal: String,
     acceptance: Vec<String>,
     next: String,
+    /// The node is not the root (its path has more than one segment), so it has siblings for
+    /// executor follow-ups.
+    has_parent: bool,
 }
 
 impl SelectedNodeContext {
     fn from_node(path: &str, node: &Node) -> Self {
         Self {
+            has_parent: path.contains('/'),
             path: path.to_string(),
             id: node.id.clone(),
             title: node.title.clone(),
@@ -38,29 +237,34 @@ impl SelectedNodeContext {
 }
 
 /// Template engine wrapper around minijinja.
-struct PromptEngine {
-    env: Environment<'static>,
+struct PromptEngine<'a> {
+    env: Environment<'a>,
 }
 
-impl PromptEngine {
-    fn new() -> Self {
+impl<'a> PromptEngine<'a> {
+    fn new(templates: &'a PromptTemplates) -> Result<Self> {
         let mut env = Environment::new();
-        env.add_template("decomposer", DECOMPOSER_TEMPLATE)
-            .expect("decomposer template should be valid");
-        env.add_template("executor", EXECUTOR_TEMPLATE)
-            .expect("executor template should be valid");
-        Self { env }
+        for kind in TemplateKind::ALL {
+            env.add_template(kind.name(), &templates.get(kind).source)
+                .with_context(|| format!("compile {} template", kind.name()))?;
+        }
+        Ok(Self { env })
     }
 
-    fn render_decomposer(&self, input: &PromptInputs) -> Result<String> {
+    /// Render the decomposer, planner or verifier template, which share the same variables.
+    fn render_read_only(&self, kind: TemplateKind, input: &PromptInputs) -> Result<String> {
         let selected = SelectedNodeContext::from_node(&input.selected_path, &input.selected_node);
-        let template = self.env.get_template("decomposer")?;
+        let template = self.env.get_template(kind.name())?;
         let rendered = template.render(context! {
             goal => input.context_goal.trim(),
             history => input.context_history.as_deref().map(str::trim).filter(|s| !s.is_empty()),
             failure => input.context_failure.as_deref().map(str::trim).filter(|s| !s.is_empty()),
+            diff => input.context_diff.as_deref().map(str::trim).filter(|s| !s.is_empty()),
+            notes => input.notes.as_deref().map(str::trim).filter(|s| !s.is_empty()),
             selected => selected,
-            tree_summary => (!input.tree_summary.trim().is_empty()).then(|| input.tree_summary.trim()),
+            tree_summary => (!input.tree_summary.trim().is_empty())
+                .then(|| input.tree_summary.trim()),
+            repo_map => input.repo_map.as_deref().map(str::trim).filter(|s| !s.is_empty()),
             assumptions => (!input.assumptions.trim().is_empty()).then(|| input.assumptions.trim()),
             questions => (!input.questions.trim().is_empty()).then(|| input.questions.trim()),
         })?;
@@ -75,13 +279,29 @@ impl PromptEngine {
             goal => input.context_goal.trim(),
             history => input.context_history.as_deref().map(str::trim).filter(|s| !s.is_empty()),
             failure => input.context_failure.as_deref().map(str::trim).filter(|s| !s.is_empty()),
+            diff => input.context_diff.as_deref().map(str::trim).filter(|s| !s.is_empty()),
+            notes => input.notes.as_deref().map(str::trim).filter(|s| !s.is_empty()),
             selected => selected,
-            tree_summary => (!input.tree_summary.trim().is_empty()).then(|| input.tree_summary.trim()),
+            tree_summary => (!input.tree_summary.trim().is_empty())
+                .then(|| input.tree_summary.trim()),
+            repo_map => input.repo_map.as_deref().map(str::trim).filter(|s| !s.is_empty()),
             assumptions => (!input.assumptions.trim().is_empty()).then(|| input.assumptions.trim()),
             questions => (!input.questions.trim().is_empty()).then(|| input.questions.trim()),
         })?;
         Ok(rendered)
     }
+
+    fn render_reviewer(&self, input: &PromptInputs, changes: &str) -> Result<String> {
+        let selected = SelectedNodeContext::from_node(&input.selected_path, &input.selected_node);
+        let template = self.env.get_template("reviewer")?;
+        let rendered = template.render(context! {
+            goal => input.context_goal.trim(),
+            notes => input.notes.as_deref().map(str::trim).filter(|s| !s.is_empty()),
+            selected => selected,
+            changes => changes.trim(),
+        })?;
+        Ok(rendered)
+    }
 }
 
 /// A parsed section from rendered template output.
@@ -99,11 +319,6 @@ struct ParsedSection {
 ///
 /// Markers follow format: `<!-- section:KEY required|droppable -->`
 fn parse_sections(rendered: &str) -> Vec<ParsedSection> {
-    use std::sync::LazyLock;
-    static SECTION_RE: LazyLock<regex::Regex> = LazyLock::new(|| {
-        regex::Regex::new(r"<!--\s*section:(\w+)\s+(required|droppable)\s*-->").unwrap()
-    });
-
     let mut sections = Vec::new();
     let matches: Vec<_> = SECTION_RE.captures_iter(rendered).collect();
 
@@ -130,65 +345,198 @@ fn parse_sections(rendered: &str) -> Vec<ParsedSection> {
     sections
 }
 
-/// Apply budget to parsed sections, dropping droppable sections as needed.
+/// Prompt size limit in the units of `tokenizer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PromptBudget {
+    pub limit: usize,
+    pub tokenizer: Tokenizer,
+}
+
+impl PromptBudget {
+    pub fn bytes(limit: usize) -> Self {
+        Self {
+            limit,
+            tokenizer: Tokenizer::Bytes,
+        }
+    }
+
+    pub fn tokens(limit: usize, tokenizer: Tokenizer) -> Self {
+        Self { limit, tokenizer }
+    }
+}
+
+/// What budgeting did to a section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionAction {
+    Kept,
+    Dropped,
+    Truncated,
+}
+
+/// Size of one section before and after budgeting.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionBudget {
+    pub key: String,
+    pub required: bool,
+    pub tokens: usize,
+    /// Size in the final prompt; 0 when dropped.
+    pub tokens_after: usize,
+    pub action: SectionAction,
+    /// Configured `prompt.sections.<key>.max`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cap: Option<usize>,
+}
+
+/// Budgeting record for one rendered prompt, written to `prompt_budget.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptBudgetReport {
+    pub template: String,
+    /// Units of `budget` and all counts (`bytes`, `words` or `bpe`).
+    pub tokenizer: Tokenizer,
+    pub budget: usize,
+    pub tokens_before: usize,
+    pub tokens_after: usize,
+    /// Sections in prompt order.
+    pub sections: Vec<SectionBudget>,
+}
+
+/// Apply caps and the budget to parsed sections.
 ///
-/// Drop order: tree -> assumptions -> questions -> history -> failure -> planner
-fn apply_budget_to_sections(sections: &mut Vec<ParsedSection>, budget: usize) {
-    let total_len =
-        |secs: &[ParsedSection]| -> usize { secs.iter().map(|s| s.content.len()).sum() };
+/// Sections with a configured `max` are cut to it first. While still over budget, droppable
+/// sections are removed in `rules.drop_order`; if that is not enough, the last remaining
+/// section is cut. Cuts use the section's truncation strategy.
+fn apply_budget_to_sections(
+    sections: &mut Vec<ParsedSection>,
+    budget: usize,
+    estimator: &dyn TokenEstimator,
+    rules: &PromptConfig,
+) -> Vec<SectionBudget> {
+    let mut report: Vec<SectionBudget> = sections
+        .iter()
+        .map(|s| {
+            let tokens = estimator.count(&s.content);
+            SectionBudget {
+                key: s.key.clone(),
+                required: s.required,
+                tokens,
+                tokens_after: tokens,
+                action: SectionAction::Kept,
+                cap: rules.max(&s.key),
+            }
+        })
+        .collect();
+
+    for (section, entry) in sections.iter_mut().zip(report.iter_mut()) {
+        let Some(cap) = entry.cap else { continue };
+        if entry.tokens > cap {
+            truncate_section(section, cap, estimator, rules);
+            entry.tokens_after = estimator.count(&section.content);
+            entry.action = SectionAction::Truncated;
+            debug!(
+                section = section.key,
+                before = entry.tokens,
+                after = entry.tokens_after,
+                "capped section"
+            );
+        }
+    }
+
+    let total_len = |secs: &[ParsedSection]| -> usize {
+        secs.iter().map(|s| estimator.count(&s.content)).sum()
+    };
 
     if total_len(sections) <= budget {
-        return;
+        return report;
     }
 
-    let drop_order = [
-        "tree",
-        "assumptions",
-        "questions",
-        "history",
-        "failure",
-        "planner",
-    ];
-    for key in drop_order {
+    for key in &rules.drop_order {
         if total_len(sections) <= budget {
             break;
         }
-        if let Some(idx) = sections.iter().position(|s| s.key == key && !s.required) {
-            let dropped_len = sections[idx].content.len();
+        if let Some(idx) = sections.iter().position(|s| &s.key == key && !s.required) {
+            let entry = report
+                .iter_mut()
+                .find(|entry| &entry.key == key && entry.action != SectionAction::Dropped)
+                .expect("every section has a report entry");
             debug!(
-                section = key,
-                bytes_dropped = dropped_len,
+                section = key.as_str(),
+                tokens_dropped = entry.tokens_after,
                 "dropped section for budget"
             );
+            entry.action = SectionAction::Dropped;
+            entry.tokens_after = 0;
             sections.remove(idx);
         }
     }
 
     // If still over budget, truncate the last section
     if total_len(sections) > budget && !sections.is_empty() {
-        let other_len: usize = sections
-            .iter()
-            .take(sections.len() - 1)
-            .map(|s| s.content.len())
-            .sum();
+        let other_len = total_len(&sections[..sections.len() - 1]);
         let allowed = budget.saturating_sub(other_len);
         let last = sections.last_mut().unwrap();
-        let before_len = last.content.len();
-        if last.content.len() > allowed {
-            if allowed > 12 {
-                last.content.truncate(allowed - 12);
-                last.content.push_str("\n[truncated]");
-            } else {
-                last.content.truncate(allowed);
-            }
+        let before = estimator.count(&last.content);
+        if before > allowed {
+            truncate_section(last, allowed, estimator, rules);
+            let after = estimator.count(&last.content);
             debug!(
                 section = last.key,
-                before_len,
-                after_len = last.content.len(),
-                "truncated section for budget"
+                before, after, "truncated section for budget"
             );
+            if let Some(entry) = report
+                .iter_mut()
+                .rev()
+                .find(|entry| entry.action != SectionAction::Dropped)
+            {
+                entry.action = SectionAction::Truncated;
+                entry.tokens_after = after;
+            }
         }
     }
+
+    report
+}
+
+/// Cut `section` to `allowed` units with its truncation strategy.
+///
+/// When the section wraps its body in a `<tag>...</tag>` pair (as the embedded templates do),
+/// only the body is cut so the heading and tags survive.
+fn truncate_section(
+    section: &mut ParsedSection,
+    allowed: usize,
+    estimator: &dyn TokenEstimator,
+    rules: &PromptConfig,
+) {
+    let strategy = rules.truncate(&section.key);
+    let content = &section.content;
+    if let Some((prefix, body, suffix)) = split_tagged_body(content) {
+        let frame = estimator.count(prefix) + estimator.count(suffix);
+        if frame < allowed {
+            let body = truncate_to(body, allowed - frame, strategy, estimator);
+            section.content = format!("{prefix}{body}{suffix}");
+            return;
+        }
+    }
+    section.content = truncate_to(content, allowed, strategy, estimator);
+}
+
+/// Split `content` into (`...<tag>`, body, `</tag>`) when it ends with the closing tag of the
+/// first opening tag it contains.
+fn split_tagged_body(content: &str) -> Option<(&str, &str, &str)> {
+    static OPEN_TAG_RE: LazyLock<regex::Regex> =
+        LazyLock::new(|| regex::Regex::new(r"<(\w+)>").unwrap());
+    let open = OPEN_TAG_RE.captures(content)?;
+    let body_start = open.get(0)?.end();
+    let close = format!("</{}>", &open[1]);
+    let trimmed = content.trim_end();
+    let body_end = trimmed.strip_suffix(close.as_str())?.len();
+    (body_start <= body_end).then(|| {
+        (
+            &content[..body_start],
+            &content[body_start..body_end],
+            &content[body_end..],
+        )
+    })
 }
 
 /// Render sections back to a single string.
@@ -215,6 +563,12 @@ pub struct PromptInputs {
     pub context_history: Option<String>,
     /// Failure content from `.runner/context/failure.md`.
     pub context_failure: Option<String>,
+    /// Previous attempt's diff from `.runner/context/diff.md` (only present on retry).
+    pub context_diff: Option<String>,
+    /// Notes of the selected node and its ancestors (`.runner/state/notes/`); set by the caller.
+    pub notes: Option<String>,
+    /// Tracked files, layout and recent changes (`.runner/cache/repo_map/`); set by the caller.
+    pub repo_map: Option<String>,
     /// Accumulated assumptions from `.runner/state/assumptions.md`.
     pub assumptions: String,
     /// Open questions from `.runner/state/questions.md`.
@@ -239,6 +593,9 @@ impl PromptInputs {
             context_goal: read_optional(context_dir.join("goal.md"))?.unwrap_or_default(),
             context_history: read_optional(context_dir.join("history.md"))?,
             context_failure: read_optional(context_dir.join("failure.md"))?,
+            context_diff: read_optional(context_dir.join("diff.md"))?,
+            notes: None,
+            repo_map: None,
             assumptions: read_optional(state_dir.join("assumptions.md"))?.unwrap_or_default(),
             questions: read_optional(state_dir.join("questions.md"))?.unwrap_or_default(),
         })
@@ -248,43 +605,113 @@ impl PromptInputs {
 /// Builds a prompt pack within a byte budget, dropping less critical sections first.
 #[derive(Debug, Clone)]
 pub struct PromptBuilder {
-    budget_bytes: usize,
+    budget: PromptBudget,
+    templates: PromptTemplates,
+    rules: PromptConfig,
 }
 
 impl PromptBuilder {
-    /// Create a builder with the given byte budget.
+    /// Create a builder with the given byte budget and the embedded templates.
     pub fn new(budget_bytes: usize) -> Self {
-        Self { budget_bytes }
+        Self::with_budget(PromptBudget::bytes(budget_bytes))
     }
 
-    /// Build a prompt pack for the decomposer agent.
-    pub fn build_decomposer(&self, input: &PromptInputs) -> PromptPack {
-        let engine = PromptEngine::new();
-        let rendered = engine
-            .render_decomposer(input)
-            .expect("decomposer template rendering should not fail");
+    /// Create a builder with `budget` and the embedded templates.
+    pub fn with_budget(budget: PromptBudget) -> Self {
+        Self {
+            budget,
+            templates: PromptTemplates::embedded(),
+            rules: PromptConfig::default(),
+        }
+    }
 
-        let mut sections = parse_sections(&rendered);
-        apply_budget_to_sections(&mut sections, self.budget_bytes);
+    /// Use `rules` for section caps, drop order and truncation.
+    pub fn with_rules(mut self, rules: PromptConfig) -> Self {
+        self.rules = rules;
+        self
+    }
 
-        PromptPack {
-            content: render_sections(&sections),
-        }
+    /// Render with `templates` instead of the embedded ones.
+    pub fn with_templates(mut self, templates: PromptTemplates) -> Self {
+        self.templates = templates;
+        self
+    }
+
+    /// Build a prompt pack for the decomposer agent.
+    ///
+    /// Fails only when an override template errors while rendering.
+    pub fn build_decomposer(&self, input: &PromptInputs) -> Result<PromptPack> {
+        let rendered = PromptEngine::new(&self.templates)?
+            .render_read_only(TemplateKind::Decomposer, input)
+            .context("render decomposer template")?;
+        self.pack(TemplateKind::Decomposer, &rendered)
+    }
+
+    /// Build a prompt pack for the planner agent.
+    ///
+    /// Fails only when an override template errors while rendering.
+    pub fn build_planner(&self, input: &PromptInputs) -> Result<PromptPack> {
+        let rendered = PromptEngine::new(&self.templates)?
+            .render_read_only(TemplateKind::Planner, input)
+            .context("render planner template")?;
+        self.pack(TemplateKind::Planner, &rendered)
     }
 
     /// Build a prompt pack for the executor agent.
-    pub fn build_executor(&self, input: &PromptInputs, planner_notes: Option<&str>) -> PromptPack {
-        let engine = PromptEngine::new();
-        let rendered = engine
+    ///
+    /// Fails only when an override template errors while rendering.
+    pub fn build_executor(
+        &self,
+        input: &PromptInputs,
+        planner_notes: Option<&str>,
+    ) -> Result<PromptPack> {
+        let rendered = PromptEngine::new(&self.templates)?
             .render_executor(input, planner_notes)
-            .expect("executor template rendering should not fail");
+            .context("render executor template")?;
+        self.pack(TemplateKind::Executor, &rendered)
+    }
 
-        let mut sections = parse_sections(&rendered);
-        apply_budget_to_sections(&mut sections, self.budget_bytes);
+    /// Build a prompt pack for the goal verifier agent.
+    ///
+    /// Fails only when an override template errors while rendering.
+    pub fn build_verifier(&self, input: &PromptInputs) -> Result<PromptPack> {
+        let rendered = PromptEngine::new(&self.templates)?
+            .render_read_only(TemplateKind::Verifier, input)
+            .context("render verifier template")?;
+        self.pack(TemplateKind::Verifier, &rendered)
+    }
 
-        PromptPack {
+    /// Build a prompt pack for the reviewer agent from the diff under review.
+    ///
+    /// Fails only when an override template errors while rendering.
+    pub fn build_reviewer(&self, input: &PromptInputs, changes: &str) -> Result<PromptPack> {
+        let rendered = PromptEngine::new(&self.templates)?
+            .render_reviewer(input, changes)
+            .context("render reviewer template")?;
+        self.pack(TemplateKind::Reviewer, &rendered)
+    }
+
+    fn pack(&self, kind: TemplateKind, rendered: &str) -> Result<PromptPack> {
+        let estimator = self.budget.tokenizer.estimator()?;
+        let mut sections = parse_sections(rendered);
+        let tokens_before = sections.iter().map(|s| estimator.count(&s.content)).sum();
+        let section_report = apply_budget_to_sections(
+            &mut sections,
+            self.budget.limit,
+            estimator.as_ref(),
+            &self.rules,
+        );
+        Ok(PromptPack {
             content: render_sections(&sections),
-        }
+            budget_report: PromptBudgetReport {
+                template: kind.name().to_string(),
+                tokenizer: self.budget.tokenizer,
+                budget: self.budget.limit,
+                tokens_before,
+                tokens_after: section_report.iter().map(|s| s.tokens_after).sum(),
+                sections: section_report,
+            },
+        })
     }
 }
 
@@ -292,6 +719,7 @@ impl PromptBuilder {
 #[derive(Debug, Clone)]
 pub struct PromptPack {
     content: String,
+    budget_report: PromptBudgetReport,
 }
 
 impl PromptPack {
@@ -299,15 +727,20 @@ impl PromptPack {
     pub fn render(&self) -> String {
         self.content.clone()
     }
+
+    /// Per-section sizes and what budgeting dropped or truncated.
+    pub fn budget_report(&self) -> &PromptBudgetReport {
+        &self.budget_report
+    }
 }
 
 /// Read file contents if it exists, returning `None` for missing files.
-fn read_optional(path: impl Into<PathBuf>) -> Result<Option<String>> {
-    let path = path.into();
+fn read_optional(path: impl AsRef<Path>) -> Result<Option<String>> {
+    let path = path.as_ref();
     if !path.exists() {
         return Ok(None);
     }
-    let contents = fs::read_to_string(&path)
+    let contents = fs::read_to_string(path)
         .with_context(|| format!("read prompt input {}", path.display()))?;
     Ok(Some(contents))
 }
@@ -324,17 +757,22 @@ mod tests {
     #[test]
     fn prompt_ordering_is_stable() {
         let input = PromptInputs {
-            selected_path: "root>child".to_string(),
+            selected_path: "root/child".to_string(),
             selected_node: default_tree(),
             tree_summary: "summary".to_string(),
             context_goal: "goal".to_string(),
             context_history: Some("history".to_string()),
             context_failure: Some("failure".to_string()),
+            context_diff: None,
+            notes: None,
+            repo_map: None,
             assumptions: "assumptions".to_string(),
             questions: "questions".to_string(),
         };
 
-        let pack = PromptBuilder::new(10_000).build_executor(&input, None);
+        let pack = PromptBuilder::new(10_000)
+            .build_executor(&input, None)
+            .expect("build");
         let content = pack.render();
 
         // Verify sections appear in expected order
@@ -365,6 +803,36 @@ mod tests {
         );
     }
 
+    /// Verifies only non-root nodes are told to report follow-ups; the root has no siblings.
+    #[test]
+    fn follow_ups_instruction_depends_on_parent() {
+        let render = |selected_path: &str| {
+            let input = PromptInputs {
+                selected_path: selected_path.to_string(),
+                selected_node: default_tree(),
+                tree_summary: "summary".to_string(),
+                context_goal: "goal".to_string(),
+                context_history: None,
+                context_failure: None,
+                context_diff: None,
+                notes: None,
+                repo_map: None,
+                assumptions: "assumptions".to_string(),
+                questions: "questions".to_string(),
+            };
+            PromptBuilder::new(10_000)
+                .build_executor(&input, None)
+                .expect("build")
+                .render()
+        };
+
+        let child = render("root/child");
+        assert!(child.contains("put newly found required work in `follow_ups`"));
+        let root = render("root");
+        assert!(!root.contains("put newly found required work in `follow_ups`"));
+        assert!(root.contains("the root has no siblings"));
+    }
+
     /// Verifies budget enforcement drops less critical sections first.
     ///
     /// With a tight budget, tree and assumptions (low priority) should be dropped
@@ -372,17 +840,22 @@ mod tests {
     #[test]
     fn budget_drops_less_critical_sections_first() {
         let input = PromptInputs {
-            selected_path: "root>child".to_string(),
+            selected_path: "root/child".to_string(),
             selected_node: default_tree(),
             tree_summary: "tree".repeat(200),
             context_goal: "goal".to_string(),
             context_history: Some("history".to_string()),
             context_failure: Some("failure".to_string()),
+            context_diff: None,
+            notes: None,
+            repo_map: None,
             assumptions: "assumptions".repeat(50),
             questions: "questions".repeat(50),
         };
 
-        let pack = PromptBuilder::new(600).build_executor(&input, None);
+        let pack = PromptBuilder::new(900)
+            .build_executor(&input, None)
+            .expect("build");
         let content = pack.render();
 
         // Tree and assumptions should be dropped (low priority droppable sections)
@@ -416,11 +889,16 @@ mod tests {
             context_goal: "test goal".to_string(),
             context_history: None,
             context_failure: None,
+            context_diff: None,
+            notes: None,
+            repo_map: None,
             assumptions: "".to_string(),
             questions: "".to_string(),
         };
 
-        let pack = PromptBuilder::new(10_000).build_decomposer(&input);
+        let pack = PromptBuilder::new(10_000)
+            .build_decomposer(&input)
+            .expect("build");
         let content = pack.render();
 
         assert!(content.contains("<contract>"), "should have contract tag");
@@ -436,4 +914,188 @@ mod tests {
             "should have selected close tag"
         );
     }
+
+    #[test]
+    fn embedded_templates_are_valid() {
+        for kind in TemplateKind::ALL {
+            validate_template(kind.name(), kind.embedded()).expect("embedded template");
+        }
+    }
+
+    #[test]
+    fn override_template_is_used_and_referenced() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let root = temp.path();
+        let path = template_override_path(root, TemplateKind::Executor);
+        fs::create_dir_all(path.parent().unwrap()).expect("mkdir");
+        fs::write(
+            &path,
+            "<!-- section:contract required -->\nCUSTOM CONTRACT\n\
+             <!-- section:goal required -->\n{{ goal }}\n\
+             <!-- section:selected required -->\n{{ selected.id }}\n",
+        )
+        .expect("write override");
+
+        let templates = PromptTemplates::load(root).expect("load");
+        let executor = templates.get(TemplateKind::Executor).reference();
+        assert_eq!(executor.source, ".runner/prompts/executor.md");
+        assert_eq!(executor.sha256.len(), 64);
+        let decomposer = templates.get(TemplateKind::Decomposer).reference();
+        assert_eq!(decomposer.source, "embedded");
+        assert_ne!(decomposer.sha256, executor.sha256);
+
+        let input = PromptInputs {
+            selected_path: "root".to_string(),
+            selected_node: default_tree(),
+            tree_summary: String::new(),
+            context_goal: "ship it".to_string(),
+            context_history: None,
+            context_failure: None,
+            context_diff: None,
+            notes: None,
+            repo_map: None,
+            assumptions: String::new(),
+            questions: String::new(),
+        };
+        let content = PromptBuilder::new(10_000)
+            .with_templates(templates)
+            .build_executor(&input, None)
+            .expect("build")
+            .render();
+        assert!(content.starts_with("CUSTOM CONTRACT"), "{content}");
+        assert!(content.contains("ship it"));
+    }
+
+    #[test]
+    fn override_missing_required_section_is_rejected() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let root = temp.path();
+        let path = template_override_path(root, TemplateKind::Decomposer);
+        fs::create_dir_all(path.parent().unwrap()).expect("mkdir");
+
+        fs::write(
+            &path,
+            "<!-- section:contract required -->\nc\n<!-- section:goal droppable -->\n{{ goal }}\n",
+        )
+        .expect("write override");
+        let err = PromptTemplates::load(root).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("section:goal required"),
+            "{err:#}"
+        );
+
+        fs::write(&path, "{% if goal %}unterminated").expect("write override");
+        let err = PromptTemplates::load(root).unwrap_err();
+        assert!(format!("{err:#}").contains("does not compile"), "{err:#}");
+    }
+
+    #[test]
+    fn budget_report_records_drops_and_truncation() {
+        let mut selected_node = default_tree();
+        selected_node.goal = "ünïcödé goal ".repeat(200);
+        let input = PromptInputs {
+            selected_path: "root".to_string(),
+            selected_node,
+            tree_summary: "leaf ".repeat(400),
+            context_goal: "ship it".to_string(),
+            context_history: None,
+            context_failure: None,
+            context_diff: None,
+            notes: None,
+            repo_map: None,
+            assumptions: String::new(),
+            questions: String::new(),
+        };
+
+        let pack = PromptBuilder::with_budget(PromptBudget::tokens(300, Tokenizer::Words))
+            .build_executor(&input, None)
+            .expect("build");
+        let report = pack.budget_report();
+        assert_eq!(report.template, "executor");
+        assert_eq!(report.tokenizer, Tokenizer::Words);
+        assert!(report.tokens_before > 300);
+        assert!(report.tokens_after <= 300, "{report:?}");
+
+        let action = |key: &str| {
+            report
+                .sections
+                .iter()
+                .find(|s| s.key == key)
+                .map(|s| s.action)
+        };
+        assert_eq!(action("tree"), Some(SectionAction::Dropped));
+        assert_eq!(action("contract"), Some(SectionAction::Kept));
+        // Required sections stay; the last one absorbs the truncation.
+        assert_eq!(action("goal"), Some(SectionAction::Kept));
+        assert_eq!(action("selected"), Some(SectionAction::Truncated));
+        assert!(pack.render().contains("[truncated]"));
+    }
+
+    #[test]
+    fn byte_truncation_never_splits_characters() {
+        let mut sections = vec![ParsedSection {
+            key: "goal".to_string(),
+            required: true,
+            content: "ü".repeat(100),
+        }];
+        let report = apply_budget_to_sections(
+            &mut sections,
+            51,
+            &crate::core::tokens::ByteCount,
+            &PromptConfig::default(),
+        );
+        assert!(sections[0].content.ends_with("[truncated]"));
+        assert!(sections[0].content.len() <= 51);
+        assert_eq!(report[0].action, SectionAction::Truncated);
+    }
+
+    #[test]
+    fn caps_and_strategies_follow_config() {
+        let mut sections = vec![
+            ParsedSection {
+                key: "failure".to_string(),
+                required: false,
+                content: format!(
+                    "### Failure\n\n<failure>{}ERROR at the end</failure>",
+                    "noise ".repeat(50)
+                ),
+            },
+            ParsedSection {
+                key: "tree".to_string(),
+                required: false,
+                content: "tree ".repeat(50),
+            },
+            ParsedSection {
+                key: "goal".to_string(),
+                required: true,
+                content: "goal".to_string(),
+            },
+        ];
+        let rules = PromptConfig {
+            drop_order: vec!["tree".to_string(), "failure".to_string()],
+            sections: [(
+                "failure".to_string(),
+                crate::io::config::SectionLimits {
+                    max: Some(12),
+                    truncate: None,
+                },
+            )]
+            .into(),
+        };
+
+        let report =
+            apply_budget_to_sections(&mut sections, 40, &crate::core::tokens::WordCount, &rules);
+
+        // failure was capped (keeping its tail and tags), then tree fit the budget.
+        assert_eq!(report[0].action, SectionAction::Truncated);
+        assert_eq!(report[0].cap, Some(12));
+        let failure = &sections[0].content;
+        assert!(
+            failure.starts_with("### Failure\n\n<failure>[truncated]"),
+            "{failure}"
+        );
+        assert!(failure.ends_with("ERROR at the end</failure>"), "{failure}");
+        assert_eq!(report[1].action, SectionAction::Dropped);
+        assert_eq!(sections.len(), 2);
+    }
 }
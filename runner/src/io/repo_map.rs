@@ -0,0 +1,284 @@
+//! Repository map for prompts, cached at `.runner/cache/repo_map/`.
+//!
+//! Agents start every iteration in a fresh session and spend tool calls rediscovering the
+//! layout of the repo. The map gives them the tracked files with sizes, an outline of the
+//! top-level directories and the files changed by recent commits. Paths under `.runner/` are
+//! left out, so the map only changes with the last commit that touched project files; it is
+//! computed once per such commit and reused from the cache across iterations.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+
+use crate::core::tokens::{ByteCount, TruncateStrategy, truncate_to};
+use crate::io::config::RepoMapConfig;
+use crate::io::git::Git;
+
+/// Cache directory for the repo at `root`.
+pub fn repo_map_cache_dir(root: &Path) -> PathBuf {
+    root.join(".runner").join("cache").join("repo_map")
+}
+
+/// Map of the repo at `root` at HEAD, cut to `cfg.max_bytes`; `None` when disabled or empty.
+///
+/// The cache is keyed on [`Git::last_project_commit`] (and the settings); only the current
+/// entry is kept.
+pub fn load_repo_map(root: &Path, cfg: &RepoMapConfig) -> Result<Option<String>> {
+    if !cfg.enabled {
+        return Ok(None);
+    }
+    let git = Git::new(root);
+    let sha = git.last_project_commit()?;
+    let dir = repo_map_cache_dir(root);
+    let path = dir.join(format!("{sha}-{}-{}.md", cfg.max_files, cfg.recent_commits));
+    let map = if path.is_file() {
+        fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?
+    } else {
+        let files = git.tracked_file_sizes(&sha)?;
+        let recent = git.recently_changed_files(&sha, cfg.recent_commits)?;
+        let map = render_repo_map(&files, &recent, cfg.max_files);
+        if dir.exists() {
+            fs::remove_dir_all(&dir).with_context(|| format!("remove {}", dir.display()))?;
+        }
+        fs::create_dir_all(&dir).with_context(|| format!("create {}", dir.display()))?;
+        fs::write(&path, &map).with_context(|| format!("write {}", path.display()))?;
+        map
+    };
+    if map.trim().is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(truncate_to(
+        &map,
+        cfg.max_bytes,
+        TruncateStrategy::Head,
+        &ByteCount,
+    )))
+}
+
+/// Render the map: layout outline, recently changed files, then up to `max_files` tracked
+/// files with sizes. Paths under `.runner/` are left out.
+pub fn render_repo_map(files: &[(String, u64)], recent: &[String], max_files: usize) -> String {
+    let files: Vec<&(String, u64)> = files
+        .iter()
+        .filter(|(path, _)| !is_runner_path(path))
+        .collect();
+    if files.is_empty() {
+        return String::new();
+    }
+    let mut buf = String::from("Layout (tracked files, total size):\n");
+    for (name, entry) in outline(&files) {
+        match entry {
+            TopEntry::File(size) => buf.push_str(&format!("{name}  {}\n", format_size(size))),
+            TopEntry::Dir(dir) => {
+                buf.push_str(&format!(
+                    "{name}/  {}, {}\n",
+                    file_count(dir.files),
+                    format_size(dir.bytes)
+                ));
+                for (sub, stats) in &dir.subdirs {
+                    buf.push_str(&format!(
+                        "  {name}/{sub}/  {}, {}\n",
+                        file_count(stats.files),
+                        format_size(stats.bytes)
+                    ));
+                }
+            }
+        }
+    }
+
+    let tracked: HashSet<&str> = files.iter().map(|(path, _)| path.as_str()).collect();
+    let recent: Vec<&String> = recent.iter().filter(|path| !is_runner_path(path)).collect();
+    if !recent.is_empty() {
+        buf.push_str("\nRecently changed (newest first):\n");
+        for path in recent {
+            if tracked.contains(path.as_str()) {
+                buf.push_str(&format!("- {path}\n"));
+            } else {
+                buf.push_str(&format!("- {path} (deleted)\n"));
+            }
+        }
+    }
+
+    buf.push_str("\nTracked files:\n");
+    for (path, size) in files.iter().take(max_files) {
+        buf.push_str(&format!("{path}  {}\n", format_size(*size)));
+    }
+    if files.len() > max_files {
+        buf.push_str(&format!(
+            "({} more files not listed)\n",
+            files.len() - max_files
+        ));
+    }
+    buf
+}
+
+#[derive(Debug, Default)]
+struct DirStats {
+    files: usize,
+    bytes: u64,
+}
+
+#[derive(Debug, Default)]
+struct TopDir {
+    files: usize,
+    bytes: u64,
+    subdirs: BTreeMap<String, DirStats>,
+}
+
+#[derive(Debug)]
+enum TopEntry {
+    File(u64),
+    Dir(TopDir),
+}
+
+/// Top-level files and directories in path order; directories carry totals for themselves and
+/// their subdirectories.
+fn outline(files: &[&(String, u64)]) -> BTreeMap<String, TopEntry> {
+    let mut entries = BTreeMap::new();
+    for (path, size) in files {
+        let Some((top, rest)) = path.split_once('/') else {
+            entries.insert(path.clone(), TopEntry::File(*size));
+            continue;
+        };
+        let entry = entries
+            .entry(top.to_string())
+            .or_insert_with(|| TopEntry::Dir(TopDir::default()));
+        let TopEntry::Dir(dir) = entry else {
+            continue;
+        };
+        dir.files += 1;
+        dir.bytes += size;
+        if let Some((sub, _)) = rest.split_once('/') {
+            let stats = dir.subdirs.entry(sub.to_string()).or_default();
+            stats.files += 1;
+            stats.bytes += size;
+        }
+    }
+    entries
+}
+
+fn is_runner_path(path: &str) -> bool {
+    path == ".runner" || path.starts_with(".runner/")
+}
+
+fn file_count(files: usize) -> String {
+    if files == 1 {
+        "1 file".to_string()
+    } else {
+        format!("{files} files")
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    if bytes < KB {
+        format!("{bytes} B")
+    } else if bytes < KB * KB {
+        format!("{:.1} KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{:.1} MB", bytes as f64 / (KB * KB) as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    fn files(entries: &[(&str, u64)]) -> Vec<(String, u64)> {
+        entries
+            .iter()
+            .map(|(path, size)| (path.to_string(), *size))
+            .collect()
+    }
+
+    #[test]
+    fn map_outlines_directories_and_lists_files() {
+        let files = files(&[
+            (".runner/state/tree.json", 900),
+            ("Cargo.toml", 300),
+            ("src/io/git.rs", 2048),
+            ("src/lib.rs", 100),
+            ("src/main.rs", 200),
+            ("tests/cli.rs", 4096),
+        ]);
+        let recent = vec![
+            "src/lib.rs".to_string(),
+            ".runner/state/tree.json".to_string(),
+            "src/old.rs".to_string(),
+        ];
+        let map = render_repo_map(&files, &recent, 3);
+        let expected = "\
+Layout (tracked files, total size):
+Cargo.toml  300 B
+src/  3 files, 2.3 KB
+  src/io/  1 file, 2.0 KB
+tests/  1 file, 4.0 KB
+
+Recently changed (newest first):
+- src/lib.rs
+- src/old.rs (deleted)
+
+Tracked files:
+Cargo.toml  300 B
+src/io/git.rs  2.0 KB
+src/lib.rs  100 B
+(2 more files not listed)
+";
+        assert_eq!(map, expected);
+        assert_eq!(render_repo_map(&[], &recent, 3), "");
+    }
+
+    #[test]
+    fn map_is_cached_by_last_project_commit() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        fs::create_dir_all(root.join("src")).expect("dir");
+        fs::write(root.join("src/lib.rs"), "pub fn x() {}\n").expect("write");
+        let git = Git::new(root);
+        git.add_all().expect("add");
+        git.commit_staged("add lib").expect("commit");
+
+        let cfg = RepoMapConfig::default();
+        let map = load_repo_map(root, &cfg).expect("map").expect("some");
+        assert!(map.contains("README.md  3 B\n"), "{map}");
+        assert!(map.contains("Recently changed (newest first):\n- src/lib.rs\n- README.md\n"));
+
+        // Served from the cache while only runner state is committed.
+        let dir = repo_map_cache_dir(root);
+        let cached = fs::read_dir(&dir).expect("cache").next().expect("entry");
+        let cached = cached.expect("entry").path();
+        fs::write(&cached, "cached map\n").expect("write");
+        fs::create_dir_all(root.join(".runner/state")).expect("dir");
+        fs::write(root.join(".runner/state/tree.json"), "{}\n").expect("write");
+        git.add_all().expect("add");
+        git.commit_staged("chore(loop): iteration").expect("commit");
+        assert_eq!(
+            load_repo_map(root, &cfg).expect("map").as_deref(),
+            Some("cached map\n")
+        );
+
+        // A commit touching project files replaces the cache entry.
+        fs::write(root.join("src/lib.rs"), "pub fn y() {}\n").expect("write");
+        git.add_all().expect("add");
+        git.commit_staged("change lib").expect("commit");
+        let map = load_repo_map(root, &cfg).expect("map").expect("some");
+        assert!(map.starts_with("Layout"), "{map}");
+        assert!(!map.contains(".runner"), "{map}");
+        assert_eq!(fs::read_dir(&dir).expect("cache").count(), 1);
+
+        let small = RepoMapConfig {
+            max_bytes: 40,
+            ..RepoMapConfig::default()
+        };
+        let cut = load_repo_map(root, &small).expect("map").expect("some");
+        assert!(cut.contains("[truncated]"), "{cut}");
+        let off = RepoMapConfig {
+            enabled: false,
+            ..RepoMapConfig::default()
+        };
+        assert_eq!(load_repo_map(root, &off).expect("map"), None);
+    }
+}
@@ -7,7 +7,7 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
-use crate::core::types::{AgentStatus, GuardOutcome};
+use crate::core::types::{AgentStatus, GuardOutcome, ReviewVerdict};
 
 /// Persisted bookkeeping for the current run (`.runner/state/run_state.json`).
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -22,6 +22,23 @@ pub struct RunState {
     pub last_summary: Option<String>,
     /// Guard outcome from the previous iteration.
     pub last_guard: Option<GuardOutcome>,
+    /// Reviewer verdict from the previous iteration, when the reviewer ran.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_review: Option<ReviewVerdict>,
+    /// RFC 3339 time `runner start` began this run (absent for runs started by older runners).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    /// Goal verification rounds run in this run (`[verifier]`). Never reset, so
+    /// `verifier.max_rounds` bounds the rounds however often the verifier adds work.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub verify_rounds: u32,
+    /// The verifier approved the completed tree against the root goal.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub goal_verified: bool,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
 }
 
 impl Default for RunState {
@@ -32,6 +49,10 @@ impl Default for RunState {
             last_status: None,
             last_summary: None,
             last_guard: None,
+            last_review: None,
+            started_at: None,
+            verify_rounds: 0,
+            goal_verified: false,
         }
     }
 }
@@ -85,6 +106,10 @@ mod tests {
             last_status: Some(AgentStatus::Retry),
             last_summary: Some("summary".to_string()),
             last_guard: Some(GuardOutcome::Skipped),
+            last_review: Some(ReviewVerdict::Reject),
+            started_at: Some("2026-01-18T12:00:00Z".to_string()),
+            verify_rounds: 1,
+            goal_verified: true,
         };
 
         write_run_state(&path, &state).expect("write");
@@ -90,7 +90,7 @@ mod tests {
             &schema_path,
             include_str!(concat!(
                 env!("CARGO_MANIFEST_DIR"),
-                "/../schemas/task_tree/v1.schema.json"
+                "/../schemas/task_tree/v2.schema.json"
             )),
         )
         .expect("write schema");
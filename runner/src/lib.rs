@@ -9,8 +9,8 @@
 //! - **[`io`]**: Side-effecting operations (filesystem, git, process execution).
 //!   Isolated to enable mocking in tests.
 //!
-//! Orchestration modules ([`step`], [`start`], [`select`], [`validate`]) coordinate
-//! core logic with I/O to implement CLI commands.
+//! Orchestration modules ([`step`], [`start`], [`select`], [`validate`], [`recover`],
+//! [`skip`], [`status`]) coordinate core logic with I/O to implement CLI commands.
 //!
 //! # Execution Flow (Pseudo Code)
 //!
@@ -61,8 +61,11 @@ pub mod exit_codes;
 pub mod io;
 pub mod logging;
 pub mod looping;
+pub mod recover;
 pub mod select;
+pub mod skip;
 pub mod start;
+pub mod status;
 pub mod step;
 #[cfg(any(test, feature = "test-support"))]
 pub mod test_support;
@@ -1,15 +1,29 @@
 //! Multi-iteration looping helper for `runner loop`.
 
 use std::path::Path;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
 
+use crate::core::path::find_node;
+use crate::core::types::GuardOutcome;
+use crate::io::config::load_config;
+use crate::io::control::Control;
 use crate::io::executor::Executor;
 use crate::io::guards::GuardRunner;
 use crate::io::init::RunnerPaths;
-use crate::io::run_state::load_run_state;
-use crate::select::{SelectOutcome, select_from_root};
-use crate::step::{MaxIterationsExceededError, StepConfig, StepOutcome, StuckLeafError, run_step};
+use crate::io::interrupt::InterruptedError;
+use crate::io::journal::{Journal, JournalEvent};
+use crate::io::notify::NotifyEvent;
+use crate::io::run_state::{RunState, load_run_state};
+use crate::io::tree_store::load_tree;
+use crate::select::{BlockedNode, SelectOutcome, select_from_root};
+use crate::step::{
+    BlockedTreeError, MaxIterationsExceededError, StepConfig, StepOutcome, StuckLeafError,
+    node_passed, run_step,
+};
 
 /// Reason why `run_loop` stopped.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -23,8 +37,62 @@ pub enum LoopStop {
         attempts: u32,
         max_attempts: u32,
     },
+    /// Only blocked nodes are left; `ids` lists them in traversal order.
+    Blocked { ids: Vec<String> },
     /// The run exceeded the configured `max_iterations`.
     MaxIterationsExceeded { next_iter: u32, max_iterations: u32 },
+    /// SIGINT/SIGTERM was received. `aborted` is true when a forced interrupt cut the
+    /// in-flight iteration short (it will be retried by the next `runner step`).
+    Interrupted { aborted: bool },
+    /// This invocation executed `--max-steps` steps.
+    MaxSteps { steps: u32 },
+    /// The `--until-node` node passes.
+    NodePassed { id: String },
+    /// An iteration's guards failed and `--stop-on-guard-fail` was set.
+    GuardFailed { id: String, iter: u32 },
+    /// The `--deadline` passed; `deadline` is RFC 3339 (UTC).
+    Deadline { deadline: String },
+    /// The run has been going longer than `max_run_duration_secs` since `runner start`.
+    RunDurationExceeded {
+        elapsed_secs: u64,
+        max_run_duration_secs: u64,
+    },
+    /// `runner stop` asked the loop to stop after the current iteration.
+    Requested,
+}
+
+impl LoopStop {
+    /// Status label used in `runner loop` output and the run journal.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoopStop::Complete => "complete",
+            LoopStop::Stuck { .. } => "stuck",
+            LoopStop::Blocked { .. } => "blocked",
+            LoopStop::MaxIterationsExceeded { .. } => "limit",
+            LoopStop::Interrupted { .. } => "interrupted",
+            LoopStop::MaxSteps { .. } => "max_steps",
+            LoopStop::NodePassed { .. } => "node_passed",
+            LoopStop::GuardFailed { .. } => "guard_failed",
+            LoopStop::Deadline { .. } => "deadline",
+            LoopStop::RunDurationExceeded { .. } => "run_duration",
+            LoopStop::Requested => "requested",
+        }
+    }
+}
+
+/// Per-invocation stop conditions for `runner loop`, on top of the tree and config limits.
+///
+/// Limits are checked between steps; an in-flight step always runs to completion.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoopLimits {
+    /// Stop after this many steps in this invocation.
+    pub max_steps: Option<u32>,
+    /// Stop once the node with this id passes.
+    pub until_node: Option<String>,
+    /// Stop after the first iteration whose guards fail.
+    pub stop_on_guard_fail: bool,
+    /// Do not start a step after this time.
+    pub deadline: Option<DateTime<Utc>>,
 }
 
 /// Summary of a loop invocation.
@@ -37,14 +105,17 @@ pub struct LoopOutcome {
 }
 
 /// Run `runner step` repeatedly until the tree completes, a stuck leaf is selected,
-/// or a configured iteration limit is reached.
+/// a configured iteration or run duration limit is reached, one of `limits` is hit, or an
+/// interrupt is requested.
 ///
 /// This stops immediately on any other error (git, executor, guards, schema/invariant violations).
+/// Loop start and stop are recorded in the run journal.
 pub fn run_loop<E: Executor, G: GuardRunner, F: FnMut(&StepOutcome)>(
     root: &Path,
     executor: &E,
     guard_runner: &G,
     step_config: &StepConfig,
+    limits: &LoopLimits,
     mut on_step: F,
 ) -> Result<LoopOutcome> {
     let paths = RunnerPaths::new(root);
@@ -52,81 +123,356 @@ pub fn run_loop<E: Executor, G: GuardRunner, F: FnMut(&StepOutcome)>(
         .with_context(|| format!("load {}", paths.run_state_path.display()))?;
     let run_id = run_state
         .run_id
+        .clone()
         .ok_or_else(|| anyhow!("missing run id (run `runner start` first)"))?;
     let started_at_iter = run_state.next_iter;
+    let cfg = load_config(&paths.config_path)
+        .with_context(|| format!("load {}", paths.config_path.display()))?;
+    let run_clock = RunClock::new(&run_state, cfg.max_run_duration_secs);
+    if let Some(id) = &limits.until_node {
+        // Fail fast on a typo rather than looping until the tree completes.
+        node_passes(root, id)?;
+    }
+    let journal = Journal::new(root, &run_id);
+    journal.record(JournalEvent::LoopStarted {
+        next_iter: started_at_iter,
+    });
 
     let mut steps_executed = 0u32;
+    let stop = step_until_stop(
+        root,
+        executor,
+        guard_runner,
+        step_config,
+        limits,
+        &run_clock,
+        &journal,
+        &mut steps_executed,
+        &mut on_step,
+    );
+    journal.record(JournalEvent::LoopStopped {
+        reason: stop
+            .as_ref()
+            .map_or("error", |stop| stop.as_str())
+            .to_string(),
+        steps: steps_executed,
+    });
+
+    Ok(LoopOutcome {
+        run_id,
+        started_at_iter,
+        steps_executed,
+        stop: stop?,
+    })
+}
+
+/// How often a paused loop checks whether it was resumed.
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Block while `.runner/PAUSE` exists. Returns a stop when the loop should end instead of
+/// continuing: a stop request or an interrupt arrived while paused.
+fn wait_while_paused(
+    paths: &RunnerPaths,
+    step_config: &StepConfig,
+    journal: &Journal,
+) -> Option<LoopStop> {
+    if !Control::Pause.is_requested(&paths.runner_dir) {
+        return None;
+    }
+    info!("loop paused; run `runner resume` to continue");
+    journal.record(JournalEvent::Paused);
     loop {
+        if step_config.interrupt.stop_requested() {
+            return Some(LoopStop::Interrupted { aborted: false });
+        }
+        if Control::Stop.is_requested(&paths.runner_dir) {
+            return Some(LoopStop::Requested);
+        }
+        if !Control::Pause.is_requested(&paths.runner_dir) {
+            info!("loop resumed");
+            journal.record(JournalEvent::Resumed);
+            return None;
+        }
+        std::thread::sleep(PAUSE_POLL_INTERVAL);
+    }
+}
+
+/// Elapsed-time budget for the whole run, anchored at `RunState::started_at`.
+struct RunClock {
+    started_at: Option<DateTime<Utc>>,
+    loop_started: Instant,
+    max_run_duration_secs: Option<u64>,
+}
+
+impl RunClock {
+    fn new(run_state: &RunState, max_run_duration_secs: Option<u64>) -> Self {
+        let started_at = run_state.started_at.as_deref().and_then(|ts| {
+            DateTime::parse_from_rfc3339(ts)
+                .map(|ts| ts.with_timezone(&Utc))
+                .inspect_err(|err| warn!(started_at = ts, error = %err, "invalid run started_at"))
+                .ok()
+        });
+        if started_at.is_none() && max_run_duration_secs.is_some() {
+            warn!("run has no start time; max_run_duration_secs counts from this loop");
+        }
+        Self {
+            started_at,
+            loop_started: Instant::now(),
+            max_run_duration_secs,
+        }
+    }
+
+    fn elapsed_secs(&self) -> u64 {
+        match self.started_at {
+            Some(started_at) => (Utc::now() - started_at).num_seconds().max(0) as u64,
+            None => self.loop_started.elapsed().as_secs(),
+        }
+    }
+
+    /// When the run reaches `max_run_duration_secs`, if it is limited.
+    fn ends_at(&self) -> Option<Instant> {
+        let max_run_duration_secs = self.max_run_duration_secs?;
+        let left = max_run_duration_secs.saturating_sub(self.elapsed_secs());
+        Some(Instant::now() + Duration::from_secs(left))
+    }
+
+    fn exceeded(&self) -> Option<LoopStop> {
+        let max_run_duration_secs = self.max_run_duration_secs?;
+        let elapsed_secs = self.elapsed_secs();
+        (elapsed_secs >= max_run_duration_secs).then_some(LoopStop::RunDurationExceeded {
+            elapsed_secs,
+            max_run_duration_secs,
+        })
+    }
+}
+
+/// Whether node `id` passes; errors when the tree has no such node.
+fn node_passes(root: &Path, id: &str) -> Result<bool> {
+    let paths = RunnerPaths::new(root);
+    let tree = load_tree(&paths.schema_path, &paths.tree_path)
+        .with_context(|| "load tree for --until-node")?;
+    find_node(&tree, id)
+        .map(|node| node.passes)
+        .ok_or_else(|| anyhow!("--until-node {id}: no such node in the tree"))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn step_until_stop<E: Executor, G: GuardRunner, F: FnMut(&StepOutcome)>(
+    root: &Path,
+    executor: &E,
+    guard_runner: &G,
+    step_config: &StepConfig,
+    limits: &LoopLimits,
+    run_clock: &RunClock,
+    journal: &Journal,
+    steps_executed: &mut u32,
+    on_step: &mut F,
+) -> Result<LoopStop> {
+    let paths = RunnerPaths::new(root);
+    loop {
+        // A first interrupt lets the in-flight step finish; stop before starting another.
+        if step_config.interrupt.stop_requested() {
+            return Ok(LoopStop::Interrupted { aborted: false });
+        }
+        let control_stop = if Control::Stop.is_requested(&paths.runner_dir) {
+            Some(LoopStop::Requested)
+        } else {
+            wait_while_paused(&paths, step_config, journal)
+        };
+        if let Some(stop) = control_stop {
+            if stop == LoopStop::Requested {
+                // The request is consumed so the next `runner loop` starts normally.
+                Control::Stop.clear(&paths.runner_dir)?;
+            }
+            return Ok(stop);
+        }
+        if let Some(id) = &limits.until_node
+            && node_passes(root, id)?
+        {
+            return Ok(LoopStop::NodePassed { id: id.clone() });
+        }
+
         // Pre-check: exit early on Complete/Stuck without the heavier setup that
         // run_step performs (git policy, config, run_state). Redundant when Open,
         // but agent execution dominates runtime so the extra tree load is negligible.
         match select_from_root(root)? {
-            SelectOutcome::Complete => {
-                return Ok(LoopOutcome {
-                    run_id: run_id.clone(),
-                    started_at_iter,
-                    steps_executed,
-                    stop: LoopStop::Complete,
-                });
-            }
+            SelectOutcome::Complete => return Ok(LoopStop::Complete),
             SelectOutcome::Stuck(leaf) => {
-                return Ok(LoopOutcome {
-                    run_id: run_id.clone(),
-                    started_at_iter,
-                    steps_executed,
-                    stop: LoopStop::Stuck {
-                        id: leaf.id,
-                        path: leaf.path,
-                        attempts: leaf.attempts,
-                        max_attempts: leaf.max_attempts,
-                    },
+                return Ok(LoopStop::Stuck {
+                    id: leaf.id,
+                    path: leaf.path,
+                    attempts: leaf.attempts,
+                    max_attempts: leaf.max_attempts,
                 });
             }
-            SelectOutcome::Open(_) => {}
+            SelectOutcome::Blocked(blocked) => return Ok(blocked_stop(&blocked)),
+            SelectOutcome::Open(_) | SelectOutcome::Verify(_) => {}
+        }
+
+        if let Some(stop) = deadline_stop(limits, run_clock) {
+            return Ok(stop);
+        }
+        if let Some(max_steps) = limits.max_steps
+            && *steps_executed >= max_steps
+        {
+            return Ok(LoopStop::MaxSteps {
+                steps: *steps_executed,
+            });
         }
 
-        match run_step(root, executor, guard_runner, step_config) {
+        // The in-flight step must not overrun the loop's own limits either.
+        let limited = StepConfig {
+            deadline: [
+                step_config.deadline,
+                limits.deadline.map(instant_at),
+                run_clock.ends_at(),
+            ]
+            .into_iter()
+            .flatten()
+            .min(),
+            ..step_config.clone()
+        };
+        match run_step(root, executor, guard_runner, &limited) {
             Ok(step) => {
-                steps_executed += 1;
+                *steps_executed += 1;
                 on_step(&step);
+                if limits.stop_on_guard_fail && step.guard == GuardOutcome::Fail {
+                    return Ok(LoopStop::GuardFailed {
+                        id: step.selected_id,
+                        iter: step.iter,
+                    });
+                }
             }
             Err(err) => {
                 if let Some(stuck) = err.downcast_ref::<StuckLeafError>() {
-                    return Ok(LoopOutcome {
-                        run_id: run_id.clone(),
-                        started_at_iter,
-                        steps_executed,
-                        stop: LoopStop::Stuck {
-                            id: stuck.id.clone(),
-                            path: stuck.path.clone(),
-                            attempts: stuck.attempts,
-                            max_attempts: stuck.max_attempts,
-                        },
+                    return Ok(LoopStop::Stuck {
+                        id: stuck.id.clone(),
+                        path: stuck.path.clone(),
+                        attempts: stuck.attempts,
+                        max_attempts: stuck.max_attempts,
                     });
                 }
+                if let Some(blocked) = err.downcast_ref::<BlockedTreeError>() {
+                    return Ok(blocked_stop(&blocked.blocked));
+                }
                 if let Some(limit) = err.downcast_ref::<MaxIterationsExceededError>() {
-                    return Ok(LoopOutcome {
-                        run_id: run_id.clone(),
-                        started_at_iter,
-                        steps_executed,
-                        stop: LoopStop::MaxIterationsExceeded {
-                            next_iter: limit.next_iter,
-                            max_iterations: limit.max_iterations,
-                        },
+                    return Ok(LoopStop::MaxIterationsExceeded {
+                        next_iter: limit.next_iter,
+                        max_iterations: limit.max_iterations,
                     });
                 }
+                if err.downcast_ref::<InterruptedError>().is_some() {
+                    return Ok(LoopStop::Interrupted { aborted: true });
+                }
+                // A step cut short by the clamped deadline ends the loop like the limit itself.
+                if let Some(stop) = deadline_stop(limits, run_clock) {
+                    warn!(error = %err, "step ended at the loop time limit");
+                    return Ok(stop);
+                }
                 return Err(err);
             }
         }
     }
 }
 
+/// `--deadline` or `max_run_duration_secs` stop, once either has been reached.
+fn deadline_stop(limits: &LoopLimits, run_clock: &RunClock) -> Option<LoopStop> {
+    if let Some(deadline) = limits.deadline
+        && Utc::now() >= deadline
+    {
+        return Some(LoopStop::Deadline {
+            deadline: deadline.to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        });
+    }
+    run_clock.exceeded()
+}
+
+/// Monotonic instant for wall-clock `at` (now, if it already passed).
+fn instant_at(at: DateTime<Utc>) -> Instant {
+    Instant::now() + (at - Utc::now()).to_std().unwrap_or_default()
+}
+
+fn blocked_stop(blocked: &[BlockedNode]) -> LoopStop {
+    LoopStop::Blocked {
+        ids: blocked.iter().map(|node| node.id.clone()).collect(),
+    }
+}
+
+/// Webhook events for a committed iteration.
+pub fn step_events(step: &StepOutcome) -> Vec<NotifyEvent> {
+    let mut events = vec![NotifyEvent::IterationFinished {
+        run_id: step.run_id.clone(),
+        iter: step.iter,
+        node_id: step.selected_id.clone(),
+        status: step.status,
+        guard: step.guard,
+        review: step.review,
+    }];
+    if node_passed(step.status, step.guard, step.review) {
+        events.push(NotifyEvent::NodePassed {
+            run_id: step.run_id.clone(),
+            iter: step.iter,
+            node_id: step.selected_id.clone(),
+        });
+    }
+    events
+}
+
+/// Webhook event for the reason a loop stopped.
+///
+/// Interrupts, the per-invocation stops (`--max-steps`, `--deadline`, ...) and `runner stop`
+/// are not reported: the operator asked for them. Neither is the `max_run_duration_secs` limit
+/// from the run config: like `--deadline` it bounds wall-clock time, and the run can continue
+/// with another `runner loop`.
+pub fn stop_event(outcome: &LoopOutcome) -> Option<NotifyEvent> {
+    let run_id = outcome.run_id.clone();
+    match &outcome.stop {
+        LoopStop::Complete => Some(NotifyEvent::Complete {
+            run_id,
+            steps: outcome.steps_executed,
+        }),
+        LoopStop::Stuck {
+            id,
+            path,
+            attempts,
+            max_attempts,
+        } => Some(NotifyEvent::Stuck {
+            run_id,
+            node_id: id.clone(),
+            path: path.clone(),
+            attempts: *attempts,
+            max_attempts: *max_attempts,
+        }),
+        LoopStop::Blocked { ids } => Some(NotifyEvent::Blocked {
+            run_id,
+            node_ids: ids.clone(),
+        }),
+        LoopStop::MaxIterationsExceeded {
+            next_iter,
+            max_iterations,
+        } => Some(NotifyEvent::IterationLimit {
+            run_id,
+            next_iter: *next_iter,
+            max_iterations: *max_iterations,
+        }),
+        LoopStop::Interrupted { .. }
+        | LoopStop::MaxSteps { .. }
+        | LoopStop::NodePassed { .. }
+        | LoopStop::GuardFailed { .. }
+        | LoopStop::Deadline { .. }
+        | LoopStop::RunDurationExceeded { .. }
+        | LoopStop::Requested => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::types::{AgentOutput, AgentStatus, GuardOutcome};
+    use crate::core::types::{AgentOutput, AgentStatus, GuardOutcome, ReviewVerdict};
     use crate::io::config::{RunnerConfig, write_config};
     use crate::io::git::Git;
+    use crate::io::guards::GuardRequest;
+    use crate::io::journal::{journal_path, read_journal};
     use crate::test_support::{
         ScriptedExec, ScriptedExecutor, ScriptedGuardRunner, ScriptedOutput, TestRepo,
     };
@@ -165,6 +511,7 @@ mod tests {
             output: ScriptedOutput::AgentOutput(AgentOutput {
                 status: AgentStatus::Done,
                 summary: "done".to_string(),
+                follow_ups: Vec::new(),
             }),
             tree_update: None,
         }]);
@@ -178,12 +525,208 @@ mod tests {
             &executor,
             &guard_runner,
             &StepConfig::default(),
+            &LoopLimits::default(),
             |_| {},
         )
         .expect("loop");
 
         assert_eq!(outcome.steps_executed, 1);
         assert_eq!(outcome.stop, LoopStop::Complete);
+
+        let records = read_journal(&journal_path(root, &outcome.run_id)).expect("journal");
+        assert_eq!(
+            records.first().map(|record| &record.event),
+            Some(&JournalEvent::LoopStarted { next_iter: 1 })
+        );
+        assert_eq!(
+            records.last().map(|record| &record.event),
+            Some(&JournalEvent::LoopStopped {
+                reason: "complete".to_string(),
+                steps: 1,
+            })
+        );
+        assert!(
+            records
+                .iter()
+                .any(|record| record.iter == Some(1) && record.event == JournalEvent::StepStarted)
+        );
+    }
+
+    /// Verifies a completed tree is verified against the root goal: gaps become follow-up
+    /// children under the root, and the loop completes once the verifier approves.
+    #[test]
+    fn loop_completes_only_after_goal_verification_approves() {
+        use crate::core::types::{TreeChildSpec, VerificationOutput};
+        use crate::io::config::VerifierConfig;
+        use crate::test_support::ScriptedGuard;
+
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        repo.write_config(&RunnerConfig {
+            max_iterations: 10,
+            verifier: VerifierConfig {
+                enabled: true,
+                max_rounds: 2,
+            },
+            ..RunnerConfig::default()
+        })
+        .expect("config");
+        let mut tree = repo.read_tree().expect("read tree");
+        tree.next = NodeNext::Execute;
+        repo.write_tree(&tree).expect("write tree");
+        let git = Git::new(root);
+        git.add_all().expect("git add");
+        assert!(git.commit_staged("chore: setup").expect("git commit"));
+
+        let done = || ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Done,
+                summary: "done".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        };
+        let verdict = |approved: bool, children: Vec<TreeChildSpec>| ScriptedExec {
+            output: ScriptedOutput::VerificationOutput(VerificationOutput {
+                approved,
+                summary: if approved { "goal met" } else { "docs missing" }.to_string(),
+                children,
+            }),
+            tree_update: None,
+        };
+        let gap = TreeChildSpec {
+            title: "Document usage".to_string(),
+            goal: "Add a usage section".to_string(),
+            acceptance: vec!["README has a Usage heading".to_string()],
+            next: NodeNext::Execute,
+            depends_on: Vec::new(),
+        };
+        let executor = ScriptedExecutor::new(vec![
+            done(),
+            verdict(false, vec![gap]),
+            done(),
+            verdict(true, Vec::new()),
+        ]);
+        let pass = || ScriptedGuard {
+            outcome: GuardOutcome::Pass,
+            log: "ok".to_string(),
+        };
+        let guard_runner = ScriptedGuardRunner::new(vec![pass(), pass()]);
+
+        let mut steps = Vec::new();
+        let outcome = run_loop(
+            root,
+            &executor,
+            &guard_runner,
+            &StepConfig::default(),
+            &LoopLimits::default(),
+            |step| steps.push((step.selected_id.clone(), step.status)),
+        )
+        .expect("loop");
+        executor.assert_drained().expect("all agents ran");
+
+        assert_eq!(outcome.stop, LoopStop::Complete);
+        assert_eq!(
+            steps,
+            [
+                ("root".to_string(), AgentStatus::Done),
+                ("root".to_string(), AgentStatus::Decomposed),
+                ("root.1".to_string(), AgentStatus::Done),
+                ("root".to_string(), AgentStatus::Done),
+            ]
+        );
+        let tree = repo.read_tree().expect("tree");
+        assert!(tree.passes);
+        assert_eq!(tree.children.len(), 1);
+        assert_eq!(tree.children[0].title, "Document usage");
+        let run_state = repo.read_run_state().expect("run state");
+        assert_eq!(run_state.verify_rounds, 2);
+        assert!(run_state.goal_verified);
+        let iter_dir = root.join(".runner/iterations").join(&outcome.run_id);
+        assert!(iter_dir.join("2/verifier_output.json").exists());
+        assert!(iter_dir.join("4/verifier_prompt.md").exists());
+    }
+
+    /// Verifies the root counts as stuck once the verification rounds run out without approval.
+    #[test]
+    fn loop_stops_stuck_when_verification_rounds_run_out() {
+        use crate::core::types::{TreeChildSpec, VerificationOutput};
+        use crate::io::config::VerifierConfig;
+        use crate::test_support::ScriptedGuard;
+
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        repo.write_config(&RunnerConfig {
+            max_iterations: 10,
+            verifier: VerifierConfig {
+                enabled: true,
+                max_rounds: 1,
+            },
+            ..RunnerConfig::default()
+        })
+        .expect("config");
+        let mut tree = repo.read_tree().expect("read tree");
+        tree.next = NodeNext::Execute;
+        repo.write_tree(&tree).expect("write tree");
+        let git = Git::new(root);
+        git.add_all().expect("git add");
+        assert!(git.commit_staged("chore: setup").expect("git commit"));
+
+        let done = || ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Done,
+                summary: "done".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        };
+        let executor = ScriptedExecutor::new(vec![
+            done(),
+            ScriptedExec {
+                output: ScriptedOutput::VerificationOutput(VerificationOutput {
+                    approved: false,
+                    summary: "tests missing".to_string(),
+                    children: vec![TreeChildSpec {
+                        title: "Add tests".to_string(),
+                        goal: "Cover the parser".to_string(),
+                        acceptance: Vec::new(),
+                        next: NodeNext::Execute,
+                        depends_on: Vec::new(),
+                    }],
+                }),
+                tree_update: None,
+            },
+            done(),
+        ]);
+        let pass = || ScriptedGuard {
+            outcome: GuardOutcome::Pass,
+            log: "ok".to_string(),
+        };
+        let guard_runner = ScriptedGuardRunner::new(vec![pass(), pass()]);
+
+        let outcome = run_loop(
+            root,
+            &executor,
+            &guard_runner,
+            &StepConfig::default(),
+            &LoopLimits::default(),
+            |_| {},
+        )
+        .expect("loop");
+        executor.assert_drained().expect("all agents ran");
+
+        assert_eq!(
+            outcome.stop,
+            LoopStop::Stuck {
+                id: "root".to_string(),
+                path: "root".to_string(),
+                attempts: 1,
+                max_attempts: 1,
+            }
+        );
+        assert_eq!(outcome.steps_executed, 3);
     }
 
     #[test]
@@ -218,6 +761,7 @@ mod tests {
             output: ScriptedOutput::AgentOutput(AgentOutput {
                 status: AgentStatus::Retry,
                 summary: "keep going".to_string(),
+                follow_ups: Vec::new(),
             }),
             tree_update: None,
         }]);
@@ -228,6 +772,7 @@ mod tests {
             &executor,
             &guard_runner,
             &StepConfig::default(),
+            &LoopLimits::default(),
             |_| {},
         )
         .expect("loop");
@@ -241,4 +786,505 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn loop_stops_after_current_step_when_interrupt_requested() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+
+        let mut tree = repo.read_tree().expect("read tree");
+        tree.next = NodeNext::Execute;
+        repo.write_tree(&tree).expect("write tree");
+        let git = Git::new(root);
+        git.add_all().expect("git add");
+        assert!(
+            git.commit_staged("chore: set root next")
+                .expect("git commit")
+        );
+
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Retry,
+                summary: "more to do".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        }]);
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+        let step_config = StepConfig::default();
+        let interrupt = step_config.interrupt.clone();
+
+        let outcome = run_loop(
+            root,
+            &executor,
+            &guard_runner,
+            &step_config,
+            &LoopLimits::default(),
+            |_| {
+                // Simulate SIGINT arriving while the first iteration is in flight.
+                interrupt.request();
+            },
+        )
+        .expect("loop");
+
+        assert_eq!(outcome.steps_executed, 1);
+        assert_eq!(outcome.stop, LoopStop::Interrupted { aborted: false });
+        Git::new(root).ensure_clean().expect("clean worktree");
+    }
+
+    /// Start a run with an executable root and `cfg`, committing the setup.
+    fn prepare_run(repo: &TestRepo, cfg: &RunnerConfig) {
+        let root = repo.root();
+        repo.start_run().expect("start");
+        write_config(&root.join(".runner/state/config.toml"), cfg).expect("write config");
+        let mut tree = repo.read_tree().expect("read tree");
+        tree.next = NodeNext::Execute;
+        repo.write_tree(&tree).expect("write tree");
+        let git = Git::new(root);
+        git.add_all().expect("git add");
+        assert!(
+            git.commit_staged("chore: prepare loop")
+                .expect("git commit")
+        );
+    }
+
+    fn retry_executor(steps: usize) -> ScriptedExecutor {
+        ScriptedExecutor::new(
+            (0..steps)
+                .map(|_| ScriptedExec {
+                    output: ScriptedOutput::AgentOutput(AgentOutput {
+                        status: AgentStatus::Retry,
+                        summary: "keep going".to_string(),
+                        follow_ups: Vec::new(),
+                    }),
+                    tree_update: None,
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn loop_stops_after_max_steps() {
+        let repo = TestRepo::new().expect("repo");
+        prepare_run(&repo, &RunnerConfig::default());
+
+        let limits = LoopLimits {
+            max_steps: Some(2),
+            ..LoopLimits::default()
+        };
+        let outcome = run_loop(
+            repo.root(),
+            &retry_executor(2),
+            &ScriptedGuardRunner::new(Vec::new()),
+            &StepConfig::default(),
+            &limits,
+            |_| {},
+        )
+        .expect("loop");
+
+        assert_eq!(outcome.steps_executed, 2);
+        assert_eq!(outcome.stop, LoopStop::MaxSteps { steps: 2 });
+    }
+
+    #[test]
+    fn loop_stops_once_until_node_passes() {
+        let repo = TestRepo::new().expect("repo");
+        prepare_run(&repo, &RunnerConfig::default());
+
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Done,
+                summary: "done".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        }]);
+        let guard_runner = ScriptedGuardRunner::new(vec![crate::test_support::ScriptedGuard {
+            outcome: GuardOutcome::Pass,
+            log: "ok".to_string(),
+        }]);
+        let limits = LoopLimits {
+            until_node: Some("root".to_string()),
+            ..LoopLimits::default()
+        };
+        let outcome = run_loop(
+            repo.root(),
+            &executor,
+            &guard_runner,
+            &StepConfig::default(),
+            &limits,
+            |_| {},
+        )
+        .expect("loop");
+
+        assert_eq!(outcome.steps_executed, 1);
+        assert_eq!(
+            outcome.stop,
+            LoopStop::NodePassed {
+                id: "root".to_string()
+            }
+        );
+
+        let missing = LoopLimits {
+            until_node: Some("root.9".to_string()),
+            ..LoopLimits::default()
+        };
+        let err = run_loop(
+            repo.root(),
+            &retry_executor(0),
+            &ScriptedGuardRunner::new(Vec::new()),
+            &StepConfig::default(),
+            &missing,
+            |_| {},
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("no such node"), "{err:#}");
+    }
+
+    #[test]
+    fn loop_stops_on_guard_failure_when_requested() {
+        let repo = TestRepo::new().expect("repo");
+        prepare_run(&repo, &RunnerConfig::default());
+
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Done,
+                summary: "done".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        }]);
+        let guard_runner = ScriptedGuardRunner::new(vec![crate::test_support::ScriptedGuard {
+            outcome: GuardOutcome::Fail,
+            log: "tests failed".to_string(),
+        }]);
+        let limits = LoopLimits {
+            stop_on_guard_fail: true,
+            ..LoopLimits::default()
+        };
+        let outcome = run_loop(
+            repo.root(),
+            &executor,
+            &guard_runner,
+            &StepConfig::default(),
+            &limits,
+            |_| {},
+        )
+        .expect("loop");
+
+        assert_eq!(outcome.steps_executed, 1);
+        assert_eq!(
+            outcome.stop,
+            LoopStop::GuardFailed {
+                id: "root".to_string(),
+                iter: 1
+            }
+        );
+    }
+
+    #[test]
+    fn loop_does_not_start_steps_past_deadline_or_run_duration() {
+        let repo = TestRepo::new().expect("repo");
+        prepare_run(
+            &repo,
+            &RunnerConfig {
+                max_run_duration_secs: Some(60),
+                ..RunnerConfig::default()
+            },
+        );
+        let root = repo.root();
+
+        let deadline = Utc::now() - chrono::Duration::minutes(1);
+        let limits = LoopLimits {
+            deadline: Some(deadline),
+            ..LoopLimits::default()
+        };
+        let outcome = run_loop(
+            root,
+            &retry_executor(0),
+            &ScriptedGuardRunner::new(Vec::new()),
+            &StepConfig::default(),
+            &limits,
+            |_| {},
+        )
+        .expect("loop");
+        assert_eq!(outcome.steps_executed, 0);
+        assert_eq!(
+            outcome.stop,
+            LoopStop::Deadline {
+                deadline: deadline.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+            }
+        );
+
+        // Backdate the run start past the configured budget.
+        let run_state_path = root.join(".runner/state/run_state.json");
+        let mut run_state = load_run_state(&run_state_path).expect("run state");
+        assert!(run_state.started_at.is_some());
+        run_state.started_at = Some("2000-01-01T00:00:00Z".to_string());
+        crate::io::run_state::write_run_state(&run_state_path, &run_state).expect("write");
+
+        let outcome = run_loop(
+            root,
+            &retry_executor(0),
+            &ScriptedGuardRunner::new(Vec::new()),
+            &StepConfig::default(),
+            &LoopLimits::default(),
+            |_| {},
+        )
+        .expect("loop");
+        assert_eq!(outcome.steps_executed, 0);
+        assert!(matches!(
+            outcome.stop,
+            LoopStop::RunDurationExceeded {
+                max_run_duration_secs: 60,
+                ..
+            }
+        ));
+    }
+
+    /// The step's agent timeout is clamped to the earliest loop limit, not just the
+    /// iteration timeout.
+    #[test]
+    fn loop_clamps_step_timeout_to_deadline_and_run_duration() {
+        let repo = TestRepo::new().expect("repo");
+        prepare_run(
+            &repo,
+            &RunnerConfig {
+                max_run_duration_secs: Some(600),
+                ..RunnerConfig::default()
+            },
+        );
+        let limits = LoopLimits {
+            deadline: Some(Utc::now() + chrono::Duration::seconds(120)),
+            max_steps: Some(1),
+            ..LoopLimits::default()
+        };
+        let executor = retry_executor(1);
+        run_loop(
+            repo.root(),
+            &executor,
+            &ScriptedGuardRunner::new(Vec::new()),
+            &StepConfig::default(),
+            &limits,
+            |_| {},
+        )
+        .expect("loop");
+        let timeout = executor.last_request().expect("request").timeout;
+        assert!(timeout <= Duration::from_secs(120), "{timeout:?}");
+
+        let executor = retry_executor(1);
+        run_loop(
+            repo.root(),
+            &executor,
+            &ScriptedGuardRunner::new(Vec::new()),
+            &StepConfig::default(),
+            &LoopLimits {
+                max_steps: Some(1),
+                ..LoopLimits::default()
+            },
+            |_| {},
+        )
+        .expect("loop");
+        let timeout = executor.last_request().expect("request").timeout;
+        assert!(timeout <= Duration::from_secs(600), "{timeout:?}");
+        assert!(timeout > Duration::from_secs(120), "{timeout:?}");
+    }
+
+    /// Guard runner that runs until its timeout and reports the timeout as a failure, like
+    /// the command guard runner.
+    struct TimeoutGuardRunner;
+
+    impl GuardRunner for TimeoutGuardRunner {
+        fn run(&self, request: &GuardRequest) -> Result<GuardOutcome> {
+            std::thread::sleep(request.timeout);
+            std::fs::write(&request.log_path, "timed out\n").expect("write guard log");
+            Ok(GuardOutcome::Fail)
+        }
+    }
+
+    /// A guard cut off by `--deadline` ends the loop at the deadline without consuming a node
+    /// attempt or stopping as a guard failure.
+    #[test]
+    fn guard_outliving_deadline_is_not_a_guard_failure() {
+        let repo = TestRepo::new().expect("repo");
+        prepare_run(&repo, &RunnerConfig::default());
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Done,
+                summary: "done".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        }]);
+        let limits = LoopLimits {
+            deadline: Some(Utc::now() + chrono::Duration::seconds(2)),
+            stop_on_guard_fail: true,
+            ..LoopLimits::default()
+        };
+
+        let outcome = run_loop(
+            repo.root(),
+            &executor,
+            &TimeoutGuardRunner,
+            &StepConfig::default(),
+            &limits,
+            |_| {},
+        )
+        .expect("loop");
+
+        assert!(
+            matches!(outcome.stop, LoopStop::Deadline { .. }),
+            "{:?}",
+            outcome.stop
+        );
+        assert_eq!(outcome.steps_executed, 0);
+        assert_eq!(repo.read_tree().expect("tree").attempts, 0);
+        let run_state = repo.read_run_state().expect("run state");
+        assert_eq!(run_state.next_iter, 2);
+        assert_eq!(run_state.last_guard, Some(GuardOutcome::Skipped));
+        Git::new(repo.root())
+            .ensure_clean()
+            .expect("iteration committed");
+    }
+
+    #[test]
+    fn loop_honours_stop_and_consumes_request() {
+        let repo = TestRepo::new().expect("repo");
+        prepare_run(&repo, &RunnerConfig::default());
+        let runner_dir = repo.root().join(".runner");
+        Control::Stop.request(&runner_dir).expect("stop");
+
+        let outcome = run_loop(
+            repo.root(),
+            &retry_executor(0),
+            &ScriptedGuardRunner::new(Vec::new()),
+            &StepConfig::default(),
+            &LoopLimits::default(),
+            |_| {},
+        )
+        .expect("loop");
+
+        assert_eq!(outcome.steps_executed, 0);
+        assert_eq!(outcome.stop, LoopStop::Requested);
+        assert!(!Control::Stop.is_requested(&runner_dir));
+        Git::new(repo.root())
+            .ensure_clean()
+            .expect("clean worktree");
+    }
+
+    #[test]
+    fn paused_loop_waits_for_resume() {
+        let repo = TestRepo::new().expect("repo");
+        prepare_run(&repo, &RunnerConfig::default());
+        let runner_dir = repo.root().join(".runner");
+        Control::Pause.request(&runner_dir).expect("pause");
+        Git::new(repo.root())
+            .ensure_clean()
+            .expect("control files are gitignored");
+
+        let resume_dir = runner_dir.clone();
+        let resumer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            Control::Pause.clear(&resume_dir).expect("resume");
+        });
+        let limits = LoopLimits {
+            max_steps: Some(1),
+            ..LoopLimits::default()
+        };
+        let outcome = run_loop(
+            repo.root(),
+            &retry_executor(1),
+            &ScriptedGuardRunner::new(Vec::new()),
+            &StepConfig::default(),
+            &limits,
+            |_| {},
+        )
+        .expect("loop");
+        resumer.join().expect("resumer");
+
+        assert_eq!(outcome.stop, LoopStop::MaxSteps { steps: 1 });
+        let events: Vec<_> = read_journal(&journal_path(repo.root(), &outcome.run_id))
+            .expect("journal")
+            .into_iter()
+            .map(|record| record.event)
+            .collect();
+        let paused = events.iter().position(|e| *e == JournalEvent::Paused);
+        let resumed = events.iter().position(|e| *e == JournalEvent::Resumed);
+        let started = events.iter().position(|e| *e == JournalEvent::StepStarted);
+        assert!(paused < resumed && resumed < started, "{events:?}");
+        assert!(paused.is_some());
+    }
+
+    #[test]
+    fn notify_events_follow_step_and_stop_outcomes() {
+        let step = StepOutcome {
+            run_id: "run-1".to_string(),
+            iter: 4,
+            selected_id: "root.1".to_string(),
+            status: AgentStatus::Done,
+            guard: GuardOutcome::Pass,
+            review: None,
+        };
+        let names: Vec<_> = step_events(&step).iter().map(NotifyEvent::name).collect();
+        assert_eq!(names, ["iteration_finished", "node_passed"]);
+
+        let rejected = StepOutcome {
+            review: Some(ReviewVerdict::Reject),
+            ..step.clone()
+        };
+        assert_eq!(
+            step_events(&rejected),
+            [NotifyEvent::IterationFinished {
+                run_id: "run-1".to_string(),
+                iter: 4,
+                node_id: "root.1".to_string(),
+                status: AgentStatus::Done,
+                guard: GuardOutcome::Pass,
+                review: Some(ReviewVerdict::Reject),
+            }]
+        );
+
+        let retry = StepOutcome {
+            status: AgentStatus::Retry,
+            guard: GuardOutcome::Skipped,
+            ..step
+        };
+        assert_eq!(step_events(&retry).len(), 1);
+
+        let outcome = |stop| LoopOutcome {
+            run_id: "run-1".to_string(),
+            started_at_iter: 1,
+            steps_executed: 3,
+            stop,
+        };
+        assert_eq!(
+            stop_event(&outcome(LoopStop::Complete)),
+            Some(NotifyEvent::Complete {
+                run_id: "run-1".to_string(),
+                steps: 3,
+            })
+        );
+        assert_eq!(
+            stop_event(&outcome(LoopStop::MaxIterationsExceeded {
+                next_iter: 5,
+                max_iterations: 4,
+            }))
+            .map(|event| event.name()),
+            Some("iteration_limit")
+        );
+        assert_eq!(
+            stop_event(&outcome(LoopStop::Blocked {
+                ids: vec!["root.2".to_string()],
+            })),
+            Some(NotifyEvent::Blocked {
+                run_id: "run-1".to_string(),
+                node_ids: vec!["root.2".to_string()],
+            })
+        );
+        assert_eq!(
+            stop_event(&outcome(LoopStop::Interrupted { aborted: false })),
+            None
+        );
+    }
 }
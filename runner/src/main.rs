@@ -3,15 +3,26 @@ use std::path::Path;
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 
+use runner::core::budget::parse_deadline;
 use runner::exit_codes;
 use runner::io::config::load_config;
+use runner::io::control::Control;
 use runner::io::executor::CodexExecutor;
 use runner::io::guards::CommandGuardRunner;
 use runner::io::init::{InitOptions, init_runner};
-use runner::looping::{LoopStop, run_loop};
-use runner::select::{SelectOutcome, select_from_root};
+use runner::io::interrupt::{Interrupt, InterruptedError};
+use runner::io::lock::RunnerLock;
+use runner::io::notify::{Notifier, NotifyEvent};
+use runner::io::run_state::load_run_state;
+use runner::looping::{LoopLimits, LoopStop, run_loop, step_events, stop_event};
+use runner::recover::{RecoverAction, recover};
+use runner::select::{BlockedNode, SelectOutcome, select_from_root};
+use runner::skip::skip_node;
 use runner::start::start_run;
-use runner::step::{StepConfig, StuckLeafError, run_step};
+use runner::status::status;
+use runner::step::{
+    BlockedTreeError, MaxIterationsExceededError, StepConfig, StuckLeafError, run_step,
+};
 use runner::validate::{RunValidation, validate_runner};
 
 #[derive(Parser)]
@@ -46,18 +57,77 @@ enum Command {
         /// Prompt pack size budget in bytes.
         #[arg(long, default_value_t = StepConfig::default().prompt_budget_bytes)]
         prompt_budget: usize,
+        /// Stop after executing this many steps.
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        max_steps: Option<u32>,
+        /// Stop once the node with this id passes.
+        #[arg(long, value_name = "ID")]
+        until_node: Option<String>,
+        /// Stop after the first iteration whose guards fail.
+        #[arg(long)]
+        stop_on_guard_fail: bool,
+        /// Do not start a step after this time (RFC 3339 or local HH:MM).
+        #[arg(long, value_name = "TIME")]
+        deadline: Option<String>,
+    },
+    /// Pause a running `runner loop` before its next step (creates `.runner/PAUSE`).
+    Pause,
+    /// Let a paused `runner loop` continue (removes `.runner/PAUSE`).
+    Resume,
+    /// Stop a running `runner loop` after its current step (creates `.runner/STOP`).
+    Stop,
+    /// Show run progress, the next leaf and the runner lock holder.
+    Status,
+    /// Change the task tree by hand.
+    Tree {
+        #[command(subcommand)]
+        command: TreeCommand,
+    },
+    /// Inspect or repair a half-finished iteration left by a crashed runner.
+    Recover {
+        /// Commit the partial work as an interrupted iteration (no attempt consumed).
+        #[arg(long, conflicts_with = "restore")]
+        commit: bool,
+        /// Discard the partial work and restore the last iteration commit.
+        #[arg(long)]
+        restore: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum TreeCommand {
+    /// Mark a node out of scope: it is no longer selected and counts as done for its parent.
+    Skip {
+        /// Id of the node to skip.
+        id: String,
     },
 }
 
 fn main() -> Result<()> {
     runner::logging::init();
-    let cli = Cli::parse();
+    // Exit only after `run` returns so held locks are released by their destructors.
+    let code = run(Cli::parse())?;
+    if code != exit_codes::OK {
+        std::process::exit(code);
+    }
+    Ok(())
+}
+
+fn run(cli: Cli) -> Result<i32> {
+    let runner_dir = Path::new(".").join(".runner");
     match cli.command {
         Command::Init { force } => {
             init_runner(Path::new("."), &InitOptions { force })?;
             println!("initialized .runner/");
         }
         Command::Start => {
+            // Before the first start `.runner/` may not exist yet; creating it here would make
+            // `start_run` skip `init`, and there is no state to race on.
+            let _lock = if runner_dir.exists() {
+                Some(RunnerLock::acquire(&runner_dir, "start")?)
+            } else {
+                None
+            };
             let outcome = start_run(Path::new("."))?;
             println!("started run={} branch={}", outcome.run_id, outcome.branch);
         }
@@ -66,6 +136,7 @@ fn main() -> Result<()> {
                 println!("validate: layout=ok");
                 println!("validate: config=ok");
                 println!("validate: tree=ok");
+                println!("validate: prompts=ok");
                 match outcome.run {
                     RunValidation::NotStarted => {
                         println!("validate: run=not-started");
@@ -74,80 +145,175 @@ fn main() -> Result<()> {
                         println!("validate: run=ok id={run_id} branch={branch}");
                     }
                 }
-                std::process::exit(exit_codes::OK);
+                return Ok(exit_codes::OK);
             }
             Err(err) => {
                 eprintln!("{err}");
-                std::process::exit(exit_codes::INVALID);
+                return Ok(exit_codes::INVALID);
             }
         },
         Command::Select => match select_from_root(Path::new("."))? {
             SelectOutcome::Complete => {
                 println!("select: status=complete");
-                std::process::exit(exit_codes::COMPLETE);
+                return Ok(exit_codes::COMPLETE);
             }
             SelectOutcome::Open(leaf) => {
                 println!(
                     "select: status=open id={} path={} attempts={}/{}",
                     leaf.id, leaf.path, leaf.attempts, leaf.max_attempts
                 );
-                std::process::exit(exit_codes::OK);
+                return Ok(exit_codes::OK);
             }
             SelectOutcome::Stuck(leaf) => {
                 println!(
                     "select: status=stuck id={} path={} attempts={}/{}",
                     leaf.id, leaf.path, leaf.attempts, leaf.max_attempts
                 );
-                std::process::exit(exit_codes::STUCK);
+                return Ok(exit_codes::STUCK);
+            }
+            SelectOutcome::Verify(root) => {
+                println!(
+                    "select: status=verify id={} path={} rounds={}/{}",
+                    root.id, root.path, root.attempts, root.max_attempts
+                );
+                return Ok(exit_codes::OK);
+            }
+            SelectOutcome::Blocked(blocked) => {
+                println!("select: status=blocked ids={}", blocked_ids(&blocked));
+                print_blocked("select", &blocked);
+                return Ok(exit_codes::BLOCKED);
             }
         },
         Command::Step { prompt_budget } => {
+            let _lock = RunnerLock::acquire(&runner_dir, "step")?;
             let executor = CodexExecutor;
-            let state_dir = Path::new(".").join(".runner").join("state");
+            let state_dir = runner_dir.join("state");
             let cfg = load_config(&state_dir.join("config.toml"))?;
             let guard_runner = CommandGuardRunner::new(cfg.guard.command);
+            let notifier = Notifier::from_config(&cfg.notify);
+            let interrupt = Interrupt::new();
+            interrupt.install_signal_handlers()?;
             let outcome = match run_step(
                 Path::new("."),
                 &executor,
                 &guard_runner,
                 &StepConfig {
                     prompt_budget_bytes: prompt_budget,
+                    interrupt,
+                    deadline: None,
                 },
             ) {
                 Ok(outcome) => outcome,
                 Err(err) => {
                     if let Some(stuck) = err.downcast_ref::<StuckLeafError>() {
                         eprintln!("{stuck}");
-                        std::process::exit(exit_codes::STUCK);
+                        if let Some(notifier) = &notifier {
+                            notifier.notify(&NotifyEvent::Stuck {
+                                run_id: current_run_id(&runner_dir).unwrap_or_default(),
+                                node_id: stuck.id.clone(),
+                                path: stuck.path.clone(),
+                                attempts: stuck.attempts,
+                                max_attempts: stuck.max_attempts,
+                            });
+                        }
+                        return Ok(exit_codes::STUCK);
+                    }
+                    if let Some(blocked) = err.downcast_ref::<BlockedTreeError>() {
+                        eprintln!("{blocked}");
+                        if let Some(notifier) = &notifier {
+                            notifier.notify(&NotifyEvent::Blocked {
+                                run_id: current_run_id(&runner_dir).unwrap_or_default(),
+                                node_ids: blocked.blocked.iter().map(|n| n.id.clone()).collect(),
+                            });
+                        }
+                        return Ok(exit_codes::BLOCKED);
+                    }
+                    if let Some(limit) = err.downcast_ref::<MaxIterationsExceededError>() {
+                        eprintln!("{limit}");
+                        if let Some(notifier) = &notifier {
+                            notifier.notify(&NotifyEvent::IterationLimit {
+                                run_id: current_run_id(&runner_dir).unwrap_or_default(),
+                                next_iter: limit.next_iter,
+                                max_iterations: limit.max_iterations,
+                            });
+                        }
+                        return Ok(exit_codes::MAX_ITERATIONS);
                     }
+                    if let Some(interrupted) = err.downcast_ref::<InterruptedError>() {
+                        eprintln!("{interrupted}");
+                        return Ok(exit_codes::INTERRUPTED);
+                    }
+                    notify_error(notifier.as_ref(), &runner_dir, &err);
                     return Err(err);
                 }
             };
+            if let Some(notifier) = &notifier {
+                for event in step_events(&outcome) {
+                    notifier.notify(&event);
+                }
+            }
             println!(
                 "step: run={} iter={} node={} status={:?} guard={:?}",
                 outcome.run_id, outcome.iter, outcome.selected_id, outcome.status, outcome.guard
             );
         }
-        Command::Loop { prompt_budget } => {
+        Command::Loop {
+            prompt_budget,
+            max_steps,
+            until_node,
+            stop_on_guard_fail,
+            deadline,
+        } => {
+            let limits = LoopLimits {
+                max_steps,
+                until_node,
+                stop_on_guard_fail,
+                deadline: deadline
+                    .as_deref()
+                    .map(|value| parse_deadline(value, &chrono::Local::now()))
+                    .transpose()?,
+            };
+            let _lock = RunnerLock::acquire(&runner_dir, "loop")?;
             let executor = CodexExecutor;
-            let state_dir = Path::new(".").join(".runner").join("state");
+            let state_dir = runner_dir.join("state");
             let cfg = load_config(&state_dir.join("config.toml"))?;
             let guard_runner = CommandGuardRunner::new(cfg.guard.command);
+            let notifier = Notifier::from_config(&cfg.notify);
+            let interrupt = Interrupt::new();
+            interrupt.install_signal_handlers()?;
 
-            let outcome = run_loop(
+            let result = run_loop(
                 Path::new("."),
                 &executor,
                 &guard_runner,
                 &StepConfig {
                     prompt_budget_bytes: prompt_budget,
+                    interrupt,
+                    deadline: None,
                 },
+                &limits,
                 |step| {
                     println!(
                         "loop: step run={} iter={} node={} status={:?} guard={:?}",
                         step.run_id, step.iter, step.selected_id, step.status, step.guard
                     );
+                    if let Some(notifier) = &notifier {
+                        for event in step_events(step) {
+                            notifier.notify(&event);
+                        }
+                    }
                 },
-            )?;
+            );
+            let outcome = match result {
+                Ok(outcome) => outcome,
+                Err(err) => {
+                    notify_error(notifier.as_ref(), &runner_dir, &err);
+                    return Err(err);
+                }
+            };
+            if let (Some(notifier), Some(event)) = (&notifier, stop_event(&outcome)) {
+                notifier.notify(&event);
+            }
 
             match outcome.stop {
                 LoopStop::Complete => {
@@ -155,7 +321,7 @@ fn main() -> Result<()> {
                         "loop: status=complete run={} steps={} started_at_iter={}",
                         outcome.run_id, outcome.steps_executed, outcome.started_at_iter
                     );
-                    std::process::exit(exit_codes::OK);
+                    return Ok(exit_codes::OK);
                 }
                 LoopStop::Stuck {
                     id,
@@ -167,7 +333,17 @@ fn main() -> Result<()> {
                         "loop: status=stuck run={} id={} path={} attempts={}/{}",
                         outcome.run_id, id, path, attempts, max_attempts
                     );
-                    std::process::exit(exit_codes::STUCK);
+                    return Ok(exit_codes::STUCK);
+                }
+                LoopStop::Blocked { ids } => {
+                    println!(
+                        "loop: status=blocked run={} ids={} steps={} started_at_iter={}",
+                        outcome.run_id,
+                        ids.join(","),
+                        outcome.steps_executed,
+                        outcome.started_at_iter
+                    );
+                    return Ok(exit_codes::BLOCKED);
                 }
                 LoopStop::MaxIterationsExceeded {
                     next_iter,
@@ -181,10 +357,243 @@ fn main() -> Result<()> {
                         outcome.steps_executed,
                         outcome.started_at_iter
                     );
-                    std::process::exit(exit_codes::INVALID);
+                    return Ok(exit_codes::MAX_ITERATIONS);
+                }
+                LoopStop::Interrupted { aborted } => {
+                    println!(
+                        "loop: status=interrupted run={} aborted={} steps={} started_at_iter={}",
+                        outcome.run_id, aborted, outcome.steps_executed, outcome.started_at_iter
+                    );
+                    return Ok(exit_codes::INTERRUPTED);
+                }
+                LoopStop::MaxSteps { steps } => {
+                    println!(
+                        "loop: status=max_steps run={} steps={steps} started_at_iter={}",
+                        outcome.run_id, outcome.started_at_iter
+                    );
+                    return Ok(exit_codes::MAX_STEPS);
+                }
+                LoopStop::NodePassed { id } => {
+                    println!(
+                        "loop: status=node_passed run={} id={id} steps={} started_at_iter={}",
+                        outcome.run_id, outcome.steps_executed, outcome.started_at_iter
+                    );
+                    return Ok(exit_codes::NODE_PASSED);
+                }
+                LoopStop::GuardFailed { id, iter } => {
+                    println!(
+                        "loop: status=guard_failed run={} id={id} iter={iter} steps={} started_at_iter={}",
+                        outcome.run_id, outcome.steps_executed, outcome.started_at_iter
+                    );
+                    return Ok(exit_codes::GUARD_FAILED);
+                }
+                LoopStop::Deadline { deadline } => {
+                    println!(
+                        "loop: status=deadline run={} deadline={deadline} steps={} started_at_iter={}",
+                        outcome.run_id, outcome.steps_executed, outcome.started_at_iter
+                    );
+                    return Ok(exit_codes::DEADLINE);
+                }
+                LoopStop::RunDurationExceeded {
+                    elapsed_secs,
+                    max_run_duration_secs,
+                } => {
+                    println!(
+                        "loop: status=run_duration run={} elapsed_secs={elapsed_secs} max_run_duration_secs={max_run_duration_secs} steps={} started_at_iter={}",
+                        outcome.run_id, outcome.steps_executed, outcome.started_at_iter
+                    );
+                    return Ok(exit_codes::RUN_DURATION);
+                }
+                LoopStop::Requested => {
+                    println!(
+                        "loop: status=requested run={} steps={} started_at_iter={}",
+                        outcome.run_id, outcome.steps_executed, outcome.started_at_iter
+                    );
+                    return Ok(exit_codes::STOP_REQUESTED);
+                }
+            }
+        }
+        Command::Pause => {
+            if Control::Pause.request(&runner_dir)? {
+                println!("pause: requested");
+            } else {
+                println!("pause: already requested");
+            }
+        }
+        Command::Resume => {
+            if Control::Pause.clear(&runner_dir)? {
+                println!("resume: pause cleared");
+            } else {
+                println!("resume: not paused");
+            }
+        }
+        Command::Stop => {
+            if Control::Stop.request(&runner_dir)? {
+                println!("stop: requested");
+            } else {
+                println!("stop: already requested");
+            }
+        }
+        Command::Status => {
+            let report = status(Path::new("."))?;
+            let last_status = report.last_status.map_or("none", |s| s.as_str());
+            let last_guard = report.last_guard.map_or("none", |g| g.as_str());
+            println!(
+                "status: run={} branch={} next_iter={} last_status={last_status} last_guard={last_guard}",
+                report.run_id.as_deref().unwrap_or("none"),
+                report.branch,
+                report.next_iter,
+            );
+            match &report.selection {
+                SelectOutcome::Complete => println!("status: next=complete"),
+                SelectOutcome::Open(leaf) => println!(
+                    "status: next=open id={} path={} attempts={}/{}",
+                    leaf.id, leaf.path, leaf.attempts, leaf.max_attempts
+                ),
+                SelectOutcome::Stuck(leaf) => println!(
+                    "status: next=stuck id={} path={} attempts={}/{}",
+                    leaf.id, leaf.path, leaf.attempts, leaf.max_attempts
+                ),
+                SelectOutcome::Verify(root) => println!(
+                    "status: next=verify id={} path={} rounds={}/{}",
+                    root.id, root.path, root.attempts, root.max_attempts
+                ),
+                SelectOutcome::Blocked(blocked) => {
+                    println!("status: next=blocked ids={}", blocked_ids(blocked));
+                }
+            }
+            if let SelectOutcome::Blocked(blocked) = &report.selection {
+                print_blocked("status", blocked);
+            }
+            if !report.skipped.is_empty() {
+                println!("status: skipped ids={}", report.skipped.join(","));
+            }
+            match &report.lock {
+                None => println!("status: lock=free"),
+                Some((holder, state)) => println!(
+                    "status: lock=held pid={} host={} command={} started_at={} holder={}",
+                    holder.pid,
+                    holder.host,
+                    holder.command,
+                    holder.started_at,
+                    state.as_str()
+                ),
+            }
+            if report.paused || report.stop_requested {
+                println!(
+                    "status: control pause={} stop={}",
+                    report.paused, report.stop_requested
+                );
+            }
+            if let Some(meta) = &report.last_iteration {
+                println!(
+                    "status: last_iter={} node={} started_at={} ended_at={} duration_ms={}",
+                    meta.iter,
+                    meta.node_id,
+                    meta.started_at.as_deref().unwrap_or("none"),
+                    meta.ended_at.as_deref().unwrap_or("none"),
+                    meta.duration_ms
+                        .map_or_else(|| "none".to_string(), |ms| ms.to_string()),
+                );
+                let phases: Vec<String> = meta
+                    .phases
+                    .entries()
+                    .iter()
+                    .filter_map(|(name, ms)| ms.map(|ms| format!("{name}={ms}ms")))
+                    .collect();
+                if !phases.is_empty() {
+                    println!("status: phases {}", phases.join(" "));
+                }
+            }
+        }
+        Command::Tree {
+            command: TreeCommand::Skip { id },
+        } => {
+            let _lock = RunnerLock::acquire(&runner_dir, "tree skip")?;
+            let outcome = skip_node(Path::new("."), &id)?;
+            if outcome.already_skipped {
+                println!(
+                    "tree: skip id={} path={} already_skipped",
+                    outcome.id, outcome.path
+                );
+            } else {
+                println!(
+                    "tree: skip id={} path={} derived_passes={}",
+                    outcome.id,
+                    outcome.path,
+                    outcome.derived_passes.join(",")
+                );
+            }
+        }
+        Command::Recover { commit, restore } => {
+            let _lock = RunnerLock::acquire(&runner_dir, "recover")?;
+            let action = if commit {
+                RecoverAction::Commit
+            } else if restore {
+                RecoverAction::Restore
+            } else {
+                RecoverAction::Inspect
+            };
+            let report = recover(Path::new("."), action)?;
+            println!(
+                "recover: run={} committed_iter={} next_iter={} half_finished={}",
+                report.run_id,
+                report.committed_iter,
+                report.recorded_next_iter,
+                report.half_finished
+            );
+            for entry in &report.dirty {
+                println!("recover: dirty {} {}", entry.code, entry.path);
+            }
+            match action {
+                RecoverAction::Inspect if report.needs_recovery() => {
+                    println!(
+                        "recover: action=none (rerun with --commit or --restore) pending_iter={}",
+                        report.pending_iter
+                    );
+                    return Ok(exit_codes::INVALID);
+                }
+                RecoverAction::Inspect => println!("recover: action=none status=clean"),
+                RecoverAction::Commit => {
+                    println!("recover: action=committed next_iter={}", report.next_iter);
+                }
+                RecoverAction::Restore => {
+                    println!("recover: action=restored next_iter={}", report.next_iter);
                 }
             }
         }
     }
-    Ok(())
+    Ok(exit_codes::OK)
+}
+
+fn blocked_ids(blocked: &[BlockedNode]) -> String {
+    let ids: Vec<&str> = blocked.iter().map(|node| node.id.as_str()).collect();
+    ids.join(",")
+}
+
+/// One line per blocked node with the executor's reason.
+fn print_blocked(command: &str, blocked: &[BlockedNode]) {
+    for node in blocked {
+        println!(
+            "{command}: blocked id={} path={} reason={:?}",
+            node.id, node.path, node.reason
+        );
+    }
+}
+
+/// Run id from `run_state.json`, if a run has been started.
+fn current_run_id(runner_dir: &Path) -> Option<String> {
+    load_run_state(&runner_dir.join("state/run_state.json"))
+        .ok()
+        .and_then(|state| state.run_id)
+}
+
+/// Report an unexpected error to the webhook, if one is configured.
+fn notify_error(notifier: Option<&Notifier>, runner_dir: &Path, err: &anyhow::Error) {
+    if let Some(notifier) = notifier {
+        notifier.notify(&NotifyEvent::RunnerError {
+            run_id: current_run_id(runner_dir),
+            message: format!("{err:#}"),
+        });
+    }
 }
@@ -0,0 +1,436 @@
+//! Orchestration for `runner recover`.
+//!
+//! If the runner dies after the agent edited files but before the iteration commit, the
+//! worktree is left dirty and `runner step` refuses to run. Recovery inspects the pending
+//! iteration and then either commits the partial work as an interrupted iteration (no node
+//! attempt consumed) or restores the last iteration commit. In both cases
+//! `run_state.json:next_iter` is reconciled with the iteration commits in git history.
+
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow};
+use tracing::{info, warn};
+
+use crate::core::selector::leftmost_open_leaf;
+use crate::core::types::{AgentStatus, GuardOutcome};
+use crate::io::git::{Git, StatusEntry};
+use crate::io::init::RunnerPaths;
+use crate::io::iteration_log::{
+    InterruptedRecord, IterationMeta, IterationPaths, PhaseTimings, archive_stale_iteration_dir,
+    write_interrupted, write_meta,
+};
+use crate::io::journal::{Journal, JournalEvent};
+use crate::io::run_state::{RunState, load_run_state, write_run_state};
+use crate::io::tree_store::load_tree;
+use crate::step::{now_rfc3339, staged_diffstat};
+
+/// Runner-owned state files restored from HEAD before recording an interrupted iteration.
+const RESTORED_STATE_PATHS: &[&str] = &[".runner/state/tree.json", ".runner/state/run_state.json"];
+
+/// What `runner recover` should do with a half-finished iteration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoverAction {
+    /// Report only; change nothing.
+    Inspect,
+    /// Commit the partial work as an interrupted iteration.
+    Commit,
+    /// Discard the partial work and restore the last iteration commit.
+    Restore,
+}
+
+/// Findings and result of `runner recover`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecoverReport {
+    pub run_id: String,
+    /// Last iteration committed for this run (0 if none).
+    pub committed_iter: u32,
+    /// `next_iter` as found in `run_state.json` before recovery.
+    pub recorded_next_iter: u32,
+    /// The iteration that was in flight (`committed_iter + 1`).
+    pub pending_iter: u32,
+    /// True when the pending iteration dir exists without `meta.json`.
+    pub half_finished: bool,
+    /// Uncommitted changes in the worktree.
+    pub dirty: Vec<StatusEntry>,
+    /// `next_iter` after recovery (unchanged for `Inspect`).
+    pub next_iter: u32,
+}
+
+impl RecoverReport {
+    /// True if anything needs recovering.
+    pub fn needs_recovery(&self) -> bool {
+        !self.dirty.is_empty() || self.recorded_next_iter != self.pending_iter
+    }
+}
+
+/// Inspect (and optionally repair) the state left behind by an unfinished iteration.
+pub fn recover(root: &Path, action: RecoverAction) -> Result<RecoverReport> {
+    let paths = RunnerPaths::new(root);
+    let git = Git::new(root);
+
+    let run_state = load_run_state(&paths.run_state_path)
+        .with_context(|| format!("load {}", paths.run_state_path.display()))?;
+    let run_id = run_state
+        .run_id
+        .clone()
+        .ok_or_else(|| anyhow!("missing run id (run `runner start` first)"))?;
+    let expected_branch = format!("runner/{run_id}");
+    let branch = git.current_branch()?;
+    if branch != expected_branch {
+        return Err(anyhow!(
+            "expected to be on '{expected_branch}' but on '{branch}'"
+        ));
+    }
+
+    let committed_iter = last_committed_iter(&git.log_subjects()?, &run_id);
+    let pending_iter = committed_iter + 1;
+    let pending_paths = IterationPaths::new(root, &run_id, pending_iter);
+    let half_finished = pending_paths.dir.exists() && !pending_paths.meta_path.exists();
+    let dirty = git.status_porcelain()?;
+
+    let mut report = RecoverReport {
+        run_id: run_id.clone(),
+        committed_iter,
+        recorded_next_iter: run_state.next_iter,
+        pending_iter,
+        half_finished,
+        dirty,
+        next_iter: run_state.next_iter,
+    };
+
+    match action {
+        RecoverAction::Inspect => {}
+        RecoverAction::Commit => {
+            if report.dirty.is_empty() {
+                return Err(anyhow!(
+                    "nothing to commit: worktree is clean (use --restore to reconcile run state)"
+                ));
+            }
+            if !report.half_finished {
+                return Err(anyhow!(
+                    "no half-finished iteration: iteration {pending_iter} has no partial log dir \
+                     (inspect the changes, then commit them yourself or use --restore)"
+                ));
+            }
+            commit_interrupted_iteration(root, &git, &paths, &run_id, pending_iter)?;
+            report.next_iter = pending_iter + 1;
+        }
+        RecoverAction::Restore => {
+            git.discard_worktree_changes()?;
+            reconcile_run_state(&git, &paths, &run_id, pending_iter)?;
+            report.next_iter = pending_iter;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Commit the dirty worktree as iteration `iter` with status `interrupted`.
+///
+/// Runner-owned state is restored from HEAD first, so the selected node's attempts are not
+/// consumed and `run_state.json` is rewritten from the committed copy. The iteration gets a
+/// `meta.json` marked `interrupted` so history and attempt tooling can see it.
+fn commit_interrupted_iteration(
+    root: &Path,
+    git: &Git,
+    paths: &RunnerPaths,
+    run_id: &str,
+    iter: u32,
+) -> Result<()> {
+    git.restore_paths_from_head(RESTORED_STATE_PATHS)?;
+
+    let tree = load_tree(&paths.schema_path, &paths.tree_path)?;
+    let node_id = leftmost_open_leaf(&tree)
+        .map(|node| node.id.clone())
+        .ok_or_else(|| anyhow!("no open leaf found (tree already complete)"))?;
+
+    if let Some(archived) = archive_stale_iteration_dir(root, run_id, iter)? {
+        info!(path = %archived.display(), "archived partial iteration dir");
+    }
+    Journal::new(root, run_id)
+        .for_iter(iter)
+        .record(JournalEvent::Interrupted {
+            phase: "crash".to_string(),
+        });
+    write_interrupted(
+        root,
+        &InterruptedRecord {
+            run_id: run_id.to_string(),
+            iter,
+            node_id: node_id.clone(),
+            phase: "crash".to_string(),
+            duration_ms: None,
+        },
+    )?;
+
+    let mut run_state = load_run_state(&paths.run_state_path)?;
+    run_state.run_id = Some(run_id.to_string());
+    run_state.next_iter = iter + 1;
+    run_state.last_status = None;
+    run_state.last_guard = None;
+    run_state.last_review = None;
+    run_state.last_summary = Some(format!(
+        "iteration {iter} was interrupted; its partial work was committed by `runner recover`"
+    ));
+    write_run_state(&paths.run_state_path, &run_state)?;
+
+    write_meta(
+        root,
+        &IterationMeta {
+            run_id: run_id.to_string(),
+            iter,
+            node_id: node_id.clone(),
+            status: AgentStatus::Retry,
+            guard: GuardOutcome::Skipped,
+            review: None,
+            started_at: None,
+            ended_at: Some(now_rfc3339()),
+            duration_ms: None,
+            diffstat: Some(staged_diffstat(root)?),
+            phases: PhaseTimings::default(),
+            prompt_templates: Vec::new(),
+            interrupted: Some("crash".to_string()),
+        },
+    )?;
+
+    git.add_all()?;
+    let msg = format!(
+        "chore(loop): run {run_id} iter {iter} node {node_id} status=interrupted guard=skipped\n\n\
+         Partial work recovered after the runner exited mid-iteration.\n\n\
+         Runner-Run: {run_id}\n\
+         Runner-Iter: {iter}\n\
+         Runner-Node: {node_id}\n\
+         Runner-Status: interrupted\n\
+         Runner-Guard: skipped\n"
+    );
+    if !git.commit_staged(&msg)? {
+        return Err(anyhow!("expected changes to commit for iteration {iter}"));
+    }
+    info!(run_id, iter, node_id = %node_id, "committed interrupted iteration");
+    Ok(())
+}
+
+/// Make `run_state.json:next_iter` agree with the last iteration commit.
+fn reconcile_run_state(git: &Git, paths: &RunnerPaths, run_id: &str, next_iter: u32) -> Result<()> {
+    let mut run_state: RunState = load_run_state(&paths.run_state_path)?;
+    if run_state.next_iter == next_iter {
+        return Ok(());
+    }
+    warn!(
+        recorded = run_state.next_iter,
+        expected = next_iter,
+        "run_state next_iter disagrees with git history"
+    );
+    run_state.next_iter = next_iter;
+    write_run_state(&paths.run_state_path, &run_state)?;
+    git.add_all()?;
+    git.commit_staged(&format!(
+        "chore(loop): recover run {run_id} next_iter={next_iter}"
+    ))?;
+    Ok(())
+}
+
+/// Highest iteration number among `chore(loop): run <run_id> iter <n> ...` subjects.
+fn last_committed_iter(subjects: &[String], run_id: &str) -> u32 {
+    let prefix = format!("chore(loop): run {run_id} iter ");
+    subjects
+        .iter()
+        .filter_map(|subject| subject.strip_prefix(&prefix))
+        .filter_map(|rest| rest.split_whitespace().next())
+        .filter_map(|iter| iter.parse::<u32>().ok())
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::{AgentOutput, AgentStatus};
+    use crate::io::attempts::{
+        AttemptRecord, append_attempt, attempts_path, keep_landed_attempts, read_node_attempts,
+    };
+    use crate::step::{StepConfig, run_step};
+    use crate::test_support::{
+        ScriptedExec, ScriptedExecutor, ScriptedGuardRunner, ScriptedOutput, TestRepo,
+    };
+    use crate::tree::NodeNext;
+    use std::fs;
+
+    /// Simulate a runner crash: one committed iteration, then a second iteration that wrote
+    /// its dir, edited files and advanced run_state without committing.
+    fn crashed_repo() -> (TestRepo, String) {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        let mut tree = repo.read_tree().expect("read tree");
+        tree.next = NodeNext::Execute;
+        repo.write_tree(&tree).expect("write tree");
+        let git = Git::new(root);
+        git.add_all().expect("git add");
+        git.commit_staged("chore: set root next").expect("commit");
+
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Retry,
+                summary: "first".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        }]);
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+        let outcome =
+            run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+
+        let paths = RunnerPaths::new(root);
+        let iter_dir = IterationPaths::new(root, &outcome.run_id, 2).dir;
+        fs::create_dir_all(&iter_dir).expect("iter dir");
+        fs::write(root.join("partial.txt"), "wip\n").expect("write partial");
+        let mut run_state = load_run_state(&paths.run_state_path).expect("state");
+        run_state.next_iter = 3;
+        write_run_state(&paths.run_state_path, &run_state).expect("write state");
+        (repo, outcome.run_id)
+    }
+
+    #[test]
+    fn last_committed_iter_parses_loop_subjects() {
+        let subjects = vec![
+            "chore(loop): run run-a iter 2 node x status=done guard=pass".to_string(),
+            "chore(loop): run run-b iter 9 node x status=done guard=pass".to_string(),
+            "chore(loop): run run-a iter 1 node x status=retry guard=skipped".to_string(),
+            "chore(loop): start run run-a".to_string(),
+        ];
+        assert_eq!(last_committed_iter(&subjects, "run-a"), 2);
+        assert_eq!(last_committed_iter(&subjects, "run-c"), 0);
+    }
+
+    #[test]
+    fn inspect_reports_half_finished_iteration_without_changes() {
+        let (repo, run_id) = crashed_repo();
+        let report = recover(repo.root(), RecoverAction::Inspect).expect("inspect");
+
+        assert_eq!(report.run_id, run_id);
+        assert_eq!(report.committed_iter, 1);
+        assert_eq!(report.recorded_next_iter, 3);
+        assert_eq!(report.pending_iter, 2);
+        assert!(report.half_finished);
+        assert!(report.needs_recovery());
+        assert!(report.dirty.iter().any(|entry| entry.path == "partial.txt"));
+        assert!(repo.root().join("partial.txt").exists());
+    }
+
+    #[test]
+    fn restore_discards_partial_work_and_reconciles_run_state() {
+        let (repo, _run_id) = crashed_repo();
+        let root = repo.root();
+        let report = recover(root, RecoverAction::Restore).expect("restore");
+
+        assert_eq!(report.next_iter, 2);
+        assert!(!root.join("partial.txt").exists());
+        Git::new(root).ensure_clean().expect("clean");
+        let run_state = load_run_state(&RunnerPaths::new(root).run_state_path).expect("state");
+        assert_eq!(run_state.next_iter, 2);
+    }
+
+    /// An attempt record appended before the lost iteration commit is not shown once the
+    /// iteration is restored, and the retried iteration number appears only once.
+    #[test]
+    fn restore_drops_attempt_record_of_the_lost_commit() {
+        let (repo, run_id) = crashed_repo();
+        let root = repo.root();
+        let path = attempts_path(root);
+        append_attempt(
+            &path,
+            &AttemptRecord {
+                run_id: run_id.clone(),
+                node_id: "root".to_string(),
+                iter: 2,
+                status: AgentStatus::Retry,
+                guard: GuardOutcome::Skipped,
+                review: None,
+                summary: "lost".to_string(),
+                failure: None,
+                error: None,
+                commit: None,
+            },
+        )
+        .expect("append stale record");
+        let git = Git::new(root);
+        let landed = |git: &Git| {
+            let records = read_node_attempts(&path, &run_id, "root").expect("read attempts");
+            keep_landed_attempts(git, records).expect("landed attempts")
+        };
+
+        recover(root, RecoverAction::Restore).expect("restore");
+        let summaries: Vec<String> = landed(&git).into_iter().map(|r| r.summary).collect();
+        assert_eq!(summaries, vec!["first".to_string()]);
+
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Retry,
+                summary: "redo".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        }]);
+        let outcome = run_step(
+            root,
+            &executor,
+            &ScriptedGuardRunner::new(Vec::new()),
+            &StepConfig::default(),
+        )
+        .expect("step");
+        assert_eq!(outcome.iter, 2);
+        let records = landed(&git);
+        let got: Vec<(u32, &str)> = records
+            .iter()
+            .map(|r| (r.iter, r.summary.as_str()))
+            .collect();
+        assert_eq!(got, vec![(1, "first"), (2, "redo")]);
+        assert!(records.iter().all(|record| record.commit.is_some()));
+    }
+
+    #[test]
+    fn commit_records_interrupted_iteration_without_consuming_attempt() {
+        let (repo, run_id) = crashed_repo();
+        let root = repo.root();
+        let attempts_before = repo.read_tree().expect("tree").attempts;
+
+        let report = recover(root, RecoverAction::Commit).expect("commit");
+
+        assert_eq!(report.next_iter, 3);
+        Git::new(root).ensure_clean().expect("clean");
+        let subjects = Git::new(root).log_subjects().expect("log");
+        assert_eq!(
+            subjects[0],
+            format!("chore(loop): run {run_id} iter 2 node root status=interrupted guard=skipped")
+        );
+        let run_state = load_run_state(&RunnerPaths::new(root).run_state_path).expect("state");
+        assert_eq!(run_state.next_iter, 3);
+        assert_eq!(repo.read_tree().expect("tree").attempts, attempts_before);
+        assert!(
+            IterationPaths::new(root, &run_id, 2)
+                .interrupted_path
+                .exists()
+        );
+        let meta = crate::io::iteration_log::read_meta(root, &run_id, 2)
+            .expect("read meta")
+            .expect("meta written");
+        assert_eq!(meta.interrupted.as_deref(), Some("crash"));
+        assert_eq!(meta.status, AgentStatus::Retry);
+        assert_eq!(meta.diffstat.expect("diffstat").files_changed, 1);
+    }
+
+    #[test]
+    fn commit_refuses_without_half_finished_iteration() {
+        let (repo, run_id) = crashed_repo();
+        let root = repo.root();
+        fs::remove_dir_all(IterationPaths::new(root, &run_id, 2).dir).expect("remove iter dir");
+
+        let err = recover(root, RecoverAction::Commit).expect_err("no half-finished iteration");
+        assert!(
+            err.to_string().contains("no half-finished iteration"),
+            "{err}"
+        );
+        assert!(root.join("partial.txt").exists());
+    }
+}
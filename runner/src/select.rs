@@ -5,20 +5,37 @@ use std::path::Path;
 use anyhow::{Context, Result, anyhow};
 
 use crate::core::path::node_path;
-use crate::core::selector::{is_stuck, leftmost_open_leaf};
+use crate::core::selector::{blocked_nodes, is_stuck, leftmost_open_leaf};
+use crate::io::config::{VerifierConfig, load_config};
 use crate::io::init::RunnerPaths;
+use crate::io::run_state::{RunState, load_run_state};
 use crate::io::tree_store::load_tree;
 use crate::tree::Node;
 
 /// Structured selection outcome.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SelectOutcome {
-    /// Tree is complete (no open leaves).
+    /// Tree is complete (no open leaves, and the goal verified when `[verifier]` is enabled).
     Complete,
     /// Open leaf selected.
     Open(SelectedLeaf),
-    /// Selected leaf is stuck (attempts exhausted).
+    /// Selected leaf is stuck (attempts exhausted). With `[verifier]` enabled this is also the
+    /// root once every leaf passed but the verification rounds ran out without approval.
     Stuck(SelectedLeaf),
+    /// Every leaf passed; the root is due for goal verification. `attempts` counts the rounds
+    /// run so far and `max_attempts` is `verifier.max_rounds`.
+    Verify(SelectedLeaf),
+    /// No selectable leaf is left, but blocked nodes keep the tree from completing. They need
+    /// a human: resolve the blocker or `runner tree skip` the node.
+    Blocked(Vec<BlockedNode>),
+}
+
+/// A blocked node and the reason its executor gave.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockedNode {
+    pub id: String,
+    pub path: String,
+    pub reason: String,
 }
 
 /// Minimal selected leaf metadata for reporting.
@@ -34,7 +51,7 @@ pub struct SelectedLeaf {
 pub fn select_leaf(tree: &Node) -> Result<SelectOutcome> {
     let selected = match leftmost_open_leaf(tree) {
         Some(node) => node,
-        None => return Ok(SelectOutcome::Complete),
+        None => return Ok(blocked_or_complete(tree)),
     };
     let path =
         node_path(tree, &selected.id).ok_or_else(|| anyhow!("selected node path not found"))?;
@@ -50,12 +67,56 @@ pub fn select_leaf(tree: &Node) -> Result<SelectOutcome> {
     Ok(SelectOutcome::Open(leaf))
 }
 
-/// Load tree from disk and select the next leaf.
+fn blocked_or_complete(tree: &Node) -> SelectOutcome {
+    let blocked: Vec<BlockedNode> = blocked_nodes(tree)
+        .into_iter()
+        .map(|node| BlockedNode {
+            id: node.id.clone(),
+            path: node_path(tree, &node.id).unwrap_or_else(|| node.id.clone()),
+            reason: node.blocked.clone().unwrap_or_default(),
+        })
+        .collect();
+    if blocked.is_empty() {
+        SelectOutcome::Complete
+    } else {
+        SelectOutcome::Blocked(blocked)
+    }
+}
+
+/// Select the next leaf, or the root for goal verification once every leaf passed.
+pub fn select_with_verification(
+    tree: &Node,
+    verifier: &VerifierConfig,
+    run_state: &RunState,
+) -> Result<SelectOutcome> {
+    let outcome = select_leaf(tree)?;
+    if outcome != SelectOutcome::Complete || !verifier.enabled || run_state.goal_verified {
+        return Ok(outcome);
+    }
+    let root = SelectedLeaf {
+        id: tree.id.clone(),
+        path: tree.id.clone(),
+        attempts: run_state.verify_rounds,
+        max_attempts: verifier.max_rounds,
+    };
+    if run_state.verify_rounds >= verifier.max_rounds {
+        return Ok(SelectOutcome::Stuck(root));
+    }
+    Ok(SelectOutcome::Verify(root))
+}
+
+/// Load tree, config and run state from disk and select the next leaf.
 pub fn select_from_root(root: &Path) -> Result<SelectOutcome> {
     let paths = RunnerPaths::new(root);
     let tree = load_tree(&paths.schema_path, &paths.tree_path)
         .with_context(|| "load tree for selection")?;
-    select_leaf(&tree)
+    let cfg = load_config(&paths.config_path)?;
+    let run_state = if paths.run_state_path.exists() {
+        load_run_state(&paths.run_state_path)?
+    } else {
+        RunState::default()
+    };
+    select_with_verification(&tree, &cfg.verifier, &run_state)
 }
 
 #[cfg(test)]
@@ -101,4 +162,67 @@ mod tests {
         let outcome = select_leaf(&tree).expect("select");
         assert_eq!(outcome, SelectOutcome::Complete);
     }
+
+    #[test]
+    fn select_returns_blocked_when_only_blocked_work_remains() {
+        let mut tree = load_tree_fixture("tree_with_passed_node").expect("fixture");
+        let open = tree
+            .children
+            .iter_mut()
+            .find(|child| child.id == "open")
+            .expect("open node");
+        open.blocked = Some("needs a product decision".to_string());
+
+        let outcome = select_leaf(&tree).expect("select");
+        assert_eq!(
+            outcome,
+            SelectOutcome::Blocked(vec![BlockedNode {
+                id: "open".to_string(),
+                path: "root/open".to_string(),
+                reason: "needs a product decision".to_string(),
+            }])
+        );
+    }
+
+    #[test]
+    fn completed_tree_is_verified_before_it_counts_as_complete() {
+        let mut tree = default_tree();
+        tree.passes = true;
+        let verifier = VerifierConfig {
+            enabled: true,
+            max_rounds: 2,
+        };
+        let mut run_state = RunState {
+            verify_rounds: 1,
+            ..RunState::default()
+        };
+        let root = SelectedLeaf {
+            id: tree.id.clone(),
+            path: tree.id.clone(),
+            attempts: 1,
+            max_attempts: 2,
+        };
+
+        let outcome = select_with_verification(&tree, &verifier, &run_state).expect("select");
+        assert_eq!(outcome, SelectOutcome::Verify(root.clone()));
+
+        run_state.verify_rounds = 2;
+        let outcome = select_with_verification(&tree, &verifier, &run_state).expect("select");
+        assert_eq!(
+            outcome,
+            SelectOutcome::Stuck(SelectedLeaf {
+                attempts: 2,
+                ..root
+            })
+        );
+
+        run_state.goal_verified = true;
+        let outcome = select_with_verification(&tree, &verifier, &run_state).expect("select");
+        assert_eq!(outcome, SelectOutcome::Complete);
+
+        let disabled = VerifierConfig::default();
+        let outcome =
+            select_with_verification(&tree, &disabled, &RunState::default()).expect("select");
+        assert_eq!(outcome, SelectOutcome::Complete);
+    }
 }
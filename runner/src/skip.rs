@@ -0,0 +1,160 @@
+//! Orchestration for `runner tree skip`.
+//!
+//! A human marks a node out of scope: it and its subtree are no longer selected, and it counts
+//! as done when its parent's `passes` is derived. This is how a blocked node is released
+//! without resolving it. The change is committed so `runner step` finds a clean worktree.
+
+use std::path::Path;
+
+use anyhow::{Result, anyhow};
+use tracing::info;
+
+use crate::core::path::{find_node, node_path};
+use crate::core::state_update::derive_passes;
+use crate::io::git::Git;
+use crate::io::init::{RunnerPaths, upgrade_tree_schema};
+use crate::io::run_state::load_run_state;
+use crate::io::tree_store::{load_tree, write_tree};
+use crate::step::enforce_on_run_branch;
+use crate::tree::Node;
+
+/// Result of `runner tree skip`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkipOutcome {
+    pub id: String,
+    pub path: String,
+    /// The node was already skipped; nothing changed.
+    pub already_skipped: bool,
+    /// Ancestors that pass now that the node counts as done.
+    pub derived_passes: Vec<String>,
+}
+
+/// Mark node `id` skipped in `tree.json` and commit the change.
+pub fn skip_node(root: &Path, id: &str) -> Result<SkipOutcome> {
+    let paths = RunnerPaths::new(root);
+    let mut tree = load_tree(&paths.schema_path, &paths.tree_path)?;
+    let node = find_node(&tree, id).ok_or_else(|| anyhow!("node '{id}' not found in tree"))?;
+    if node.id == tree.id {
+        return Err(anyhow!("cannot skip the root node '{id}'"));
+    }
+    if node.passes {
+        return Err(anyhow!("node '{id}' already passes"));
+    }
+    let path = node_path(&tree, id).unwrap_or_else(|| id.to_string());
+    if node.skipped {
+        return Ok(SkipOutcome {
+            id: id.to_string(),
+            path,
+            already_skipped: true,
+            derived_passes: Vec::new(),
+        });
+    }
+
+    // The skip commit belongs on the run branch, like every other runner commit.
+    let run_id = load_run_state(&paths.run_state_path)?
+        .run_id
+        .ok_or_else(|| anyhow!("missing run id (run `runner start` first)"))?;
+    enforce_on_run_branch(root, &run_id)?;
+    let git = Git::new(root);
+    git.ensure_clean()?;
+    // `skipped` needs the v2 tree schema; runs started on v1 get it with this commit.
+    upgrade_tree_schema(&paths.schema_path)?;
+    set_skipped(&mut tree, id);
+    let derived_passes = derive_passes(&mut tree);
+    write_tree(&paths.tree_path, &tree)?;
+    git.add_all()?;
+    git.commit_staged(&format!("chore(loop): skip node {id}"))?;
+    info!(node_id = id, derived_passes = ?derived_passes, "skipped node");
+
+    Ok(SkipOutcome {
+        id: id.to_string(),
+        path,
+        already_skipped: false,
+        derived_passes,
+    })
+}
+
+fn set_skipped(node: &mut Node, id: &str) {
+    if node.id == id {
+        node.skipped = true;
+        return;
+    }
+    for child in &mut node.children {
+        set_skipped(child, id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::select::{SelectOutcome, select_leaf};
+    use crate::test_support::{TestRepo, leaf, node_with_children};
+
+    #[test]
+    fn skip_releases_a_blocked_node_and_commits() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        let mut blocked = leaf("root.2", 1, false);
+        blocked.blocked = Some("needs production access".to_string());
+        repo.write_tree(&node_with_children(
+            "root",
+            0,
+            vec![leaf("root.1", 0, true), blocked],
+        ))
+        .expect("write tree");
+        let git = Git::new(root);
+        git.add_all().expect("git add");
+        assert!(git.commit_staged("chore: fixture").expect("git commit"));
+
+        let outcome = skip_node(root, "root.2").expect("skip");
+        assert_eq!(
+            outcome,
+            SkipOutcome {
+                id: "root.2".to_string(),
+                path: "root/root.2".to_string(),
+                already_skipped: false,
+                derived_passes: vec!["root".to_string()],
+            }
+        );
+
+        let tree = repo.read_tree().expect("tree");
+        assert!(tree.children[1].skipped);
+        assert!(tree.passes);
+        assert_eq!(select_leaf(&tree).expect("select"), SelectOutcome::Complete);
+        git.ensure_clean().expect("skip is committed");
+        assert_eq!(
+            git.log_subjects().expect("log").first().map(String::as_str),
+            Some("chore(loop): skip node root.2")
+        );
+
+        let again = skip_node(root, "root.2").expect("skip again");
+        assert!(again.already_skipped);
+        assert!(skip_node(root, "root").is_err());
+        assert!(skip_node(root, "root.1").is_err());
+    }
+
+    #[test]
+    fn skip_refuses_off_the_run_branch() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        let mut blocked = leaf("root.1", 0, false);
+        blocked.blocked = Some("needs production access".to_string());
+        repo.write_tree(&node_with_children("root", 0, vec![blocked]))
+            .expect("write tree");
+        let git = Git::new(root);
+        git.add_all().expect("git add");
+        assert!(git.commit_staged("chore: fixture").expect("git commit"));
+        git.checkout_new_branch("feature")
+            .expect("checkout feature");
+
+        let err = skip_node(root, "root.1").expect_err("wrong branch");
+        assert!(err.to_string().contains("expected to be on"), "{err}");
+        assert!(!repo.read_tree().expect("tree").children[0].skipped);
+        assert_eq!(
+            git.log_subjects().expect("log").first().map(String::as_str),
+            Some("chore: fixture")
+        );
+    }
+}
@@ -12,7 +12,7 @@ use tracing::{debug, info};
 
 use crate::io::git::Git;
 use crate::io::goal::{ensure_goal_id, read_goal_id, validate_id};
-use crate::io::init::{InitOptions, init_runner};
+use crate::io::init::{InitOptions, ensure_runner_gitignore, init_runner, upgrade_tree_schema};
 use crate::io::run_state::{RunState, load_run_state, write_run_state};
 
 /// Outcome of `runner start`.
@@ -81,6 +81,9 @@ pub fn start_run(root: &Path) -> Result<StartOutcome> {
     }
 
     ensure_runner_gitignore(&runner_dir.join(".gitignore"))?;
+    if let Some(previous) = upgrade_tree_schema(&state_dir.join("schema.json"))? {
+        info!(previous = %previous, "upgraded .runner/state/schema.json");
+    }
 
     if !goal_path.exists() {
         return Err(anyhow!(
@@ -100,6 +103,8 @@ pub fn start_run(root: &Path) -> Result<StartOutcome> {
     if run_state.run_id.as_deref() != Some(&run_id) {
         run_state = RunState::default();
         run_state.run_id = Some(run_id.clone());
+        run_state.started_at =
+            Some(chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true));
     }
 
     write_run_state(&run_state_path, &run_state)?;
@@ -134,46 +139,6 @@ fn generate_run_id(git: &Git) -> Result<String> {
     ))
 }
 
-fn ensure_runner_gitignore(path: &Path) -> Result<()> {
-    const REQUIRED_LINES: [&str; 2] = ["context/", "iterations/"];
-
-    let mut existing = String::new();
-    if path.exists() {
-        existing =
-            std::fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
-    }
-
-    let mut lines: Vec<String> = existing
-        .lines()
-        .map(str::trim)
-        .filter(|s| !s.is_empty())
-        .map(|s| s.to_string())
-        .collect();
-
-    for req in REQUIRED_LINES {
-        if !lines.iter().any(|l| l == req) {
-            lines.push(req.to_string());
-        }
-    }
-
-    // Stable ordering.
-    lines.sort();
-    lines.dedup();
-
-    let mut out = lines.join("\n");
-    out.push('\n');
-
-    if out != existing {
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)
-                .with_context(|| format!("create {}", parent.display()))?;
-        }
-        std::fs::write(path, out).with_context(|| format!("write {}", path.display()))?;
-    }
-
-    Ok(())
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,6 +170,7 @@ mod tests {
         let run_state =
             load_run_state(&root.join(".runner/state/run_state.json")).expect("load run_state");
         assert_eq!(run_state.run_id, Some(outcome.run_id.clone()));
+        assert!(run_state.started_at.is_some());
 
         let last_msg = capture(root, &["git", "log", "-1", "--pretty=%B"]);
         assert!(last_msg.contains(&format!("start run {}", outcome.run_id)));
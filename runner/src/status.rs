@@ -0,0 +1,146 @@
+//! Read-only run summary for `runner status`.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::core::selector::skipped_nodes;
+use crate::core::types::{AgentStatus, GuardOutcome};
+use crate::io::control::Control;
+use crate::io::git::Git;
+use crate::io::init::RunnerPaths;
+use crate::io::iteration_log::{IterationMeta, read_meta};
+use crate::io::lock::{HolderState, LockInfo, holder_state, read_lock};
+use crate::io::run_state::load_run_state;
+use crate::io::tree_store::load_tree;
+use crate::select::{SelectOutcome, select_from_root};
+
+/// Snapshot of the run as reported by `runner status`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusReport {
+    pub run_id: Option<String>,
+    pub branch: String,
+    pub next_iter: u32,
+    pub last_status: Option<AgentStatus>,
+    pub last_guard: Option<GuardOutcome>,
+    pub selection: SelectOutcome,
+    /// Ids of nodes skipped with `runner tree skip`; their work is not done.
+    pub skipped: Vec<String>,
+    /// Current lock holder and whether it is still running.
+    pub lock: Option<(LockInfo, HolderState)>,
+    /// `meta.json` of the last finished iteration, with timestamps and phase timings.
+    pub last_iteration: Option<IterationMeta>,
+    /// `.runner/PAUSE` exists (`runner pause`).
+    pub paused: bool,
+    /// `.runner/STOP` exists (`runner stop`) and no loop has honoured it yet.
+    pub stop_requested: bool,
+}
+
+/// Collect status without taking the runner lock or modifying anything.
+pub fn status(root: &Path) -> Result<StatusReport> {
+    let paths = RunnerPaths::new(root);
+    let run_state = load_run_state(&paths.run_state_path)
+        .with_context(|| format!("load {}", paths.run_state_path.display()))?;
+    let branch = Git::new(root).current_branch()?;
+    let selection = select_from_root(root)?;
+    let tree = load_tree(&paths.schema_path, &paths.tree_path)?;
+    let skipped = skipped_nodes(&tree)
+        .into_iter()
+        .map(|node| node.id.clone())
+        .collect();
+    let lock = read_lock(&paths.runner_dir)?.map(|info| {
+        let state = holder_state(&info);
+        (info, state)
+    });
+
+    let last_iteration = match &run_state.run_id {
+        Some(run_id) if run_state.next_iter > 1 => {
+            read_meta(root, run_id, run_state.next_iter - 1)?
+        }
+        _ => None,
+    };
+
+    Ok(StatusReport {
+        run_id: run_state.run_id,
+        branch,
+        next_iter: run_state.next_iter,
+        last_status: run_state.last_status,
+        last_guard: run_state.last_guard,
+        selection,
+        skipped,
+        lock,
+        last_iteration,
+        paused: Control::Pause.is_requested(&paths.runner_dir),
+        stop_requested: Control::Stop.is_requested(&paths.runner_dir),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::types::AgentOutput;
+    use crate::io::lock::RunnerLock;
+    use crate::step::{StepConfig, run_step};
+    use crate::test_support::{
+        ScriptedExec, ScriptedExecutor, ScriptedGuardRunner, ScriptedOutput, TestRepo,
+    };
+    use crate::tree::NodeNext;
+
+    #[test]
+    fn status_reports_run_and_lock_holder() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        let outcome = repo.start_run().expect("start");
+
+        let report = status(root).expect("status");
+        assert_eq!(report.run_id.as_deref(), Some(outcome.run_id.as_str()));
+        assert_eq!(report.branch, outcome.branch);
+        assert_eq!(report.next_iter, 1);
+        assert!(matches!(report.selection, SelectOutcome::Open(_)));
+        assert!(report.lock.is_none());
+        assert!(report.last_iteration.is_none());
+        assert!(!report.paused && !report.stop_requested);
+
+        Control::Pause
+            .request(&root.join(".runner"))
+            .expect("pause");
+        assert!(status(root).expect("status").paused);
+
+        let _lock = RunnerLock::acquire(&root.join(".runner"), "loop").expect("lock");
+        let report = status(root).expect("status");
+        let (holder, state) = report.lock.expect("lock holder");
+        assert_eq!(holder.command, "loop");
+        assert_eq!(state, HolderState::Alive);
+    }
+
+    #[test]
+    fn status_reports_last_iteration_timings() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        let mut tree = repo.read_tree().expect("read tree");
+        tree.next = NodeNext::Execute;
+        repo.write_tree(&tree).expect("write tree");
+        let git = Git::new(root);
+        git.add_all().expect("git add");
+        git.commit_staged("chore: set root next").expect("commit");
+
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Retry,
+                summary: "again".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        }]);
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+        run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+
+        let report = status(root).expect("status");
+        let last = report.last_iteration.expect("last iteration");
+        assert_eq!(last.iter, 1);
+        assert!(last.ended_at.is_some());
+        assert!(last.phases.agent_exec_ms.is_some());
+        assert!(last.phases.guards_ms.is_none());
+    }
+}
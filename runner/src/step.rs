@@ -8,24 +8,49 @@ use anyhow::{Context, Result, anyhow};
 
 use crate::agents::decomposer::DecomposerAgent;
 use crate::agents::executor::ExecutorAgent;
+use crate::agents::planner::{PlannerAgent, render_plan};
+use crate::agents::reviewer::{ReviewerAgent, render_review};
+use crate::agents::verifier::VerifierAgent;
+use crate::agents::{AgentRun, write_output_schema};
 use crate::core::budget::remaining_budget;
 use crate::core::child_additions::validate_child_additions_restricted;
+use crate::core::decomposition::{DecompositionLimits, check_decomposition};
 use crate::core::immutability::check_passed_node_immutability;
-use crate::core::path::node_path;
-use crate::core::selector::{is_stuck, leftmost_open_leaf};
-use crate::core::state_update::apply_state_updates;
+use crate::core::path::{find_node, node_lineage};
+use crate::core::state_update::{apply_state_updates, mark_blocked};
 use crate::core::status_validator::validate_status_invariants;
-use crate::core::types::{AgentOutput, AgentStatus, GuardOutcome, TreeChildSpec};
-use crate::io::config::load_config;
+use crate::core::tokens::{ByteCount, TruncateStrategy, truncate_to};
+use crate::core::tree_summary::summarize_tree;
+use crate::core::types::{
+    AgentOutput, AgentStatus, GuardOutcome, ReviewOutput, ReviewVerdict, TreeChildSpec,
+};
+use crate::io::attempts::{
+    AttemptRecord, append_attempt, attempts_path, failure_digest, keep_landed_attempts,
+    read_node_attempts, render_attempt_history,
+};
+use crate::io::config::{
+    HooksConfig, RepoMapConfig, RetryDiffConfig, ReviewerConfig, RunnerConfig, load_config,
+};
 use crate::io::context::{ContextPayload, write_context};
 use crate::io::executor::Executor;
-use crate::io::git::Git;
+use crate::io::git::{DiffStat, Git};
 use crate::io::goal::read_goal_id;
 use crate::io::guards::{GuardRequest, GuardRunner, run_guards_if_needed};
-use crate::io::iteration_log::{IterationMeta, IterationWriteRequest, write_iteration};
-use crate::io::prompt::PromptInputs;
+use crate::io::hooks::{HookEvent, HookOutcome, HookPayload, run_hook};
+use crate::io::init::{ensure_runner_gitignore, upgrade_tree_schema};
+use crate::io::interrupt::{Interrupt, InterruptedError};
+use crate::io::iteration_log::{
+    DroppedFollowUps, InterruptedRecord, IterationMeta, IterationWriteRequest, PhaseTimings,
+    archive_stale_iteration_dir, write_dropped_follow_ups, write_interrupted, write_iteration,
+    write_meta,
+};
+use crate::io::journal::{Journal, JournalEvent};
+use crate::io::notes::{NotesSnapshot, check_notes_changes, render_lineage_notes};
+use crate::io::prompt::{PromptBudget, PromptInputs, PromptTemplates, TemplateKind};
+use crate::io::repo_map::load_repo_map;
 use crate::io::run_state::{RunState, load_run_state, write_run_state};
 use crate::io::tree_store::{load_tree, write_tree};
+use crate::select::{BlockedNode, SelectOutcome, select_with_verification};
 use crate::tree::{Node, NodeNext};
 
 /// Configuration for a single step iteration.
@@ -33,12 +58,19 @@ use crate::tree::{Node, NodeNext};
 pub struct StepConfig {
     /// Maximum bytes for the prompt pack before dropping sections.
     pub prompt_budget_bytes: usize,
+    /// Interrupt handle shared with signal handlers; a forced interrupt aborts the iteration.
+    pub interrupt: Interrupt,
+    /// Hard end for the step (`runner loop --deadline`, `max_run_duration_secs`); the
+    /// iteration timeout is clamped to it.
+    pub deadline: Option<Instant>,
 }
 
 impl Default for StepConfig {
     fn default() -> Self {
         Self {
             prompt_budget_bytes: 40_000,
+            interrupt: Interrupt::new(),
+            deadline: None,
         }
     }
 }
@@ -56,15 +88,20 @@ pub struct StepOutcome {
     pub status: AgentStatus,
     /// Guard outcome (pass/fail/skipped).
     pub guard: GuardOutcome,
+    /// Reviewer verdict, when the reviewer ran (`[reviewer]`).
+    pub review: Option<ReviewVerdict>,
 }
 
 #[derive(Debug)]
 struct StepAttemptResult {
     output: AgentOutput,
     guard: GuardOutcome,
+    review: Option<ReviewVerdict>,
     tree_after: Node,
     runner_error_log: Option<String>,
     agent_error_log: Option<String>,
+    /// Phases timed inside the attempt (prompt render through state update).
+    phases: PhaseTimings,
 }
 
 struct AttemptContext<'a, E: Executor> {
@@ -73,16 +110,33 @@ struct AttemptContext<'a, E: Executor> {
     iter_dir: &'a Path,
     prompt_inputs: &'a PromptInputs,
     deadline: Instant,
+    /// Loop time limit (`--deadline`, `max_run_duration_secs`) when it ends the step before
+    /// `iteration_timeout_secs` does.
+    loop_limit: Option<Instant>,
+    interrupt: &'a Interrupt,
     prev_tree: &'a Node,
     selected_id: &'a str,
     tree_path: &'a Path,
+    journal: &'a Journal,
+    /// `.runner/state/notes/` before the agent ran.
+    notes_before: &'a NotesSnapshot,
 }
 
 struct ExecuteContext<'a, G: GuardRunner> {
+    /// Read-only planner run before the executor, when enabled for the node.
+    planner: Option<&'a PlannerAgent>,
+    /// Read-only reviewer run after guards pass, when enabled.
+    reviewer: Option<&'a ReviewerAgent>,
+    reviewer_cfg: &'a ReviewerConfig,
+    run_id: &'a str,
     guard_runner: &'a G,
     schema_path: &'a Path,
     guard_log_path: &'a Path,
+    review_log_path: &'a Path,
     guard_output_limit_bytes: usize,
+    /// Limits applied to executor follow-ups, like to decompositions.
+    decomposition: &'a DecompositionLimits,
+    max_attempts_default: u32,
 }
 
 /// Error when a stuck leaf is selected (hard-stop).
@@ -106,6 +160,25 @@ impl std::fmt::Display for StuckLeafError {
 
 impl std::error::Error for StuckLeafError {}
 
+/// Error when only blocked nodes are left to work on (hard-stop until a human intervenes).
+#[derive(Debug, Clone)]
+pub struct BlockedTreeError {
+    pub blocked: Vec<BlockedNode>,
+}
+
+impl std::fmt::Display for BlockedTreeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ids: Vec<&str> = self.blocked.iter().map(|node| node.id.as_str()).collect();
+        write!(
+            f,
+            "tree blocked (hard-stop): blocked={} (resolve or `runner tree skip <id>`)",
+            ids.join(",")
+        )
+    }
+}
+
+impl std::error::Error for BlockedTreeError {}
+
 /// Error when the run has exceeded the configured maximum number of iterations.
 ///
 /// Note: `next_iter` is the *next* iteration number to run (1-indexed).
@@ -137,8 +210,30 @@ pub fn run_step<E: Executor, G: GuardRunner>(
     executor: &E,
     guard_runner: &G,
     config: &StepConfig,
+) -> Result<StepOutcome> {
+    let mut journal = None;
+    let result = run_step_journaled(root, executor, guard_runner, config, &mut journal);
+    if let (Err(err), Some(journal)) = (&result, &journal)
+        && err.downcast_ref::<InterruptedError>().is_none()
+    {
+        journal.record(JournalEvent::Error {
+            message: format!("{err:#}"),
+        });
+    }
+    result
+}
+
+/// Body of [`run_step`]; sets `journal_slot` once the run and iteration are known so the
+/// caller can journal any error this returns.
+fn run_step_journaled<E: Executor, G: GuardRunner>(
+    root: &Path,
+    executor: &E,
+    guard_runner: &G,
+    config: &StepConfig,
+    journal_slot: &mut Option<Journal>,
 ) -> Result<StepOutcome> {
     let start = Instant::now();
+    let started_at = now_rfc3339();
     enforce_git_policy_pre_step(root)?;
     let state_dir = root.join(".runner").join("state");
     let tree_path = state_dir.join("tree.json");
@@ -146,7 +241,11 @@ pub fn run_step<E: Executor, G: GuardRunner>(
     let run_state_path = state_dir.join("run_state.json");
     let config_path = state_dir.join("config.toml");
     let cfg = load_config(&config_path)?;
-    let deadline = start + Duration::from_secs(cfg.iteration_timeout_secs);
+    let templates = PromptTemplates::load(root)?;
+    let iteration_deadline = start + Duration::from_secs(cfg.iteration_timeout_secs);
+    let deadline = config
+        .deadline
+        .map_or(iteration_deadline, |limit| limit.min(iteration_deadline));
 
     let mut run_state = load_or_default_run_state(&run_state_path)?;
     let run_id = run_state
@@ -155,7 +254,10 @@ pub fn run_step<E: Executor, G: GuardRunner>(
         .ok_or_else(|| anyhow!("missing run id (run `runner start` first)"))?;
     enforce_run_id_matches_goal(root, &run_id)?;
     enforce_on_run_branch(root, &run_id)?;
+    update_runner_files(root)?;
     let iter = run_state.next_iter;
+    let journal: &Journal = journal_slot.insert(Journal::new(root, &run_id).for_iter(iter));
+    journal.record(JournalEvent::StepStarted);
     if iter > cfg.max_iterations {
         return Err(MaxIterationsExceededError {
             next_iter: iter,
@@ -165,61 +267,162 @@ pub fn run_step<E: Executor, G: GuardRunner>(
     }
 
     let prev_tree = load_tree(&schema_path, &tree_path)?;
-    let selected = leftmost_open_leaf(&prev_tree)
-        .ok_or_else(|| anyhow!("no open leaf found (tree already complete)"))?;
+    // Once every leaf passed, the root is selected for goal verification (`[verifier]`).
+    let selection = select_with_verification(&prev_tree, &cfg.verifier, &run_state)?;
+    let verifying = matches!(selection, SelectOutcome::Verify(_));
+    let stuck = matches!(selection, SelectOutcome::Stuck(_));
+    let leaf = match selection {
+        SelectOutcome::Complete => {
+            return Err(anyhow!("no open leaf found (tree already complete)"));
+        }
+        SelectOutcome::Blocked(blocked) => return Err(BlockedTreeError { blocked }.into()),
+        SelectOutcome::Open(leaf) | SelectOutcome::Stuck(leaf) | SelectOutcome::Verify(leaf) => {
+            leaf
+        }
+    };
+    let selected =
+        find_node(&prev_tree, &leaf.id).ok_or_else(|| anyhow!("selected node not found"))?;
     let selected_id = selected.id.clone();
     tracing::Span::current().record("run_id", &run_id);
     tracing::Span::current().record("iter", iter);
     tracing::Span::current().record("node_id", &selected_id);
 
-    let selected_path = node_path(&prev_tree, &selected_id)
-        .ok_or_else(|| anyhow!("selected node path not found"))?;
+    let selected_path = leaf.path.clone();
+    journal.record(JournalEvent::NodeSelected {
+        node_id: selected_id.clone(),
+        path: selected_path.clone(),
+        attempts: leaf.attempts,
+        max_attempts: leaf.max_attempts,
+    });
 
-    if is_stuck(selected) {
+    if stuck {
         return Err(StuckLeafError {
-            id: selected.id.clone(),
-            path: selected_path.clone(),
-            attempts: selected.attempts,
-            max_attempts: selected.max_attempts,
+            id: leaf.id,
+            path: leaf.path,
+            attempts: leaf.attempts,
+            max_attempts: leaf.max_attempts,
         }
         .into());
     }
 
+    let context_start = Instant::now();
     let goal_body = render_goal(selected);
-    let history = history_from_run_state(&run_state);
+    let history = attempt_history(root, &run_id, &selected_id, cfg.history_attempts)
+        .or_else(|| history_from_run_state(&run_state));
     let failure = failure_from_run_state(root, &run_id, iter, &run_state);
+    let diff = previous_attempt_diff(root, &run_id, selected, &cfg.retry_diff);
     write_context(
         root,
         &ContextPayload {
             goal: goal_body,
             history,
             failure,
+            diff,
         },
     )?;
+    journal.record(JournalEvent::ContextWritten);
 
-    let tree_summary = summarize_tree(&prev_tree, 200);
-    let prompt_inputs =
+    let tree_summary = summarize_tree(&prev_tree, &selected_id, cfg.tree_summary_bytes);
+    let mut prompt_inputs =
         PromptInputs::from_root(root, selected_path, selected.to_owned(), tree_summary)?;
+    let notes_before = NotesSnapshot::capture(root)?;
+    let lineage = node_lineage(&prev_tree, &selected_id).unwrap_or_default();
+    prompt_inputs.notes = render_lineage_notes(&notes_before, &lineage);
+    prompt_inputs.repo_map = repo_map(root, &cfg.repo_map);
+    let context_build_ms = elapsed_ms(context_start);
+    let prompt_budget = cfg
+        .prompt_budget_tokens
+        .map_or(PromptBudget::bytes(config.prompt_budget_bytes), |limit| {
+            PromptBudget::tokens(limit, cfg.prompt_tokenizer)
+        });
     let decomposer_agent = DecomposerAgent::new(
         &state_dir,
         config.prompt_budget_bytes,
         cfg.executor_output_limit_bytes,
-    );
+    )
+    .with_interrupt(config.interrupt.clone())
+    .with_templates(templates.clone())
+    .with_prompt_budget(prompt_budget)
+    .with_prompt_config(cfg.prompt.clone());
     let executor_agent = ExecutorAgent::new(
         &state_dir,
         config.prompt_budget_bytes,
         cfg.executor_output_limit_bytes,
-    );
+    )
+    .with_interrupt(config.interrupt.clone())
+    .with_templates(templates.clone())
+    .with_prompt_budget(prompt_budget)
+    .with_prompt_config(cfg.prompt.clone());
+    let verifier_agent = VerifierAgent::new(
+        &state_dir,
+        config.prompt_budget_bytes,
+        cfg.executor_output_limit_bytes,
+    )
+    .with_interrupt(config.interrupt.clone())
+    .with_templates(templates.clone())
+    .with_prompt_budget(prompt_budget)
+    .with_prompt_config(cfg.prompt.clone());
+    let plan_first = !verifying
+        && selected.next == NodeNext::Execute
+        && cfg
+            .planner
+            .applies_to(lineage.iter().map(|node| node.id.as_str()));
+    let planner_agent = plan_first.then(|| {
+        PlannerAgent::new(
+            &state_dir,
+            config.prompt_budget_bytes,
+            cfg.executor_output_limit_bytes,
+        )
+        .with_interrupt(config.interrupt.clone())
+        .with_templates(templates.clone())
+        .with_prompt_budget(prompt_budget)
+        .with_prompt_config(cfg.prompt.clone())
+    });
+    let review_after = !verifying && selected.next == NodeNext::Execute && cfg.reviewer.enabled;
+    let reviewer_agent = review_after.then(|| {
+        ReviewerAgent::new(
+            &state_dir,
+            config.prompt_budget_bytes,
+            cfg.executor_output_limit_bytes,
+        )
+        .with_interrupt(config.interrupt.clone())
+        .with_templates(templates.clone())
+        .with_prompt_budget(prompt_budget)
+        .with_prompt_config(cfg.prompt.clone())
+    });
+    let mut template_kinds = Vec::new();
+    if plan_first {
+        template_kinds.push(TemplateKind::Planner);
+    }
+    template_kinds.push(match selected.next {
+        _ if verifying => TemplateKind::Verifier,
+        NodeNext::Decompose => TemplateKind::Decomposer,
+        NodeNext::Execute => TemplateKind::Executor,
+    });
+    if review_after {
+        template_kinds.push(TemplateKind::Reviewer);
+    }
 
     let iter_dir = root
         .join(".runner")
         .join("iterations")
         .join(&run_id)
         .join(iter.to_string());
+    // A directory for this iteration can only exist if a previous attempt was aborted before
+    // it committed; keep it for inspection but start fresh.
+    archive_stale_iteration_dir(root, &run_id, iter)?;
     fs::create_dir_all(&iter_dir)
         .with_context(|| format!("create iteration dir {}", iter_dir.display()))?;
 
+    run_step_hook(
+        &cfg.hooks,
+        root,
+        &HookPayload::new(HookEvent::PreStep, &run_id, iter, &selected_id, &iter_dir),
+        &config.interrupt,
+    )?;
+
     let guard_log_path = iter_dir.join("guard.log");
+    let review_log_path = iter_dir.join("review.log");
     let runner_error_log_path = iter_dir.join("runner_error.log");
     let agent_error_log_path = iter_dir.join("agent_error.log");
 
@@ -230,43 +433,76 @@ pub fn run_step<E: Executor, G: GuardRunner>(
         iter_dir: &iter_dir,
         prompt_inputs: &prompt_inputs,
         deadline,
+        loop_limit: config.deadline.filter(|limit| *limit < iteration_deadline),
+        interrupt: &config.interrupt,
         prev_tree: &prev_tree,
         selected_id: &selected_id,
         tree_path: &tree_path,
+        journal,
+        notes_before: &notes_before,
     };
     let execute_ctx = ExecuteContext {
+        planner: planner_agent.as_ref(),
+        reviewer: reviewer_agent.as_ref(),
+        reviewer_cfg: &cfg.reviewer,
+        run_id: &run_id,
         guard_runner,
         schema_path: &schema_path,
         guard_log_path: &guard_log_path,
+        review_log_path: &review_log_path,
         guard_output_limit_bytes: cfg.guard_output_limit_bytes,
+        decomposition: &cfg.decomposition,
+        max_attempts_default: cfg.max_attempts_default,
     };
     let attempt = match selected.next {
-        NodeNext::Decompose => {
-            attempt_decompose(&decomposer_agent, &attempt_ctx, cfg.max_attempts_default)
-        }
+        _ if verifying => attempt_verify(&verifier_agent, &attempt_ctx, &cfg),
+        NodeNext::Decompose => attempt_decompose(&decomposer_agent, &attempt_ctx, &cfg),
         NodeNext::Execute => attempt_execute(&executor_agent, &attempt_ctx, &execute_ctx),
     };
 
     let attempt_result = match attempt {
         Ok(result) => result,
         Err(err) => {
+            if let Some(phase) = err.downcast_ref::<InterruptedError>().map(|e| e.phase) {
+                journal.record(JournalEvent::Interrupted {
+                    phase: phase.to_string(),
+                });
+                abort_interrupted_iteration(
+                    root,
+                    &InterruptedRecord {
+                        run_id: run_id.clone(),
+                        iter,
+                        node_id: selected_id.clone(),
+                        phase: phase.to_string(),
+                        duration_ms: Some(start.elapsed().as_millis() as u64),
+                    },
+                )?;
+                return Err(err);
+            }
             step_error = Some(err);
             let runner_error_log = Some(format!("runner error: {}", step_error.as_ref().unwrap()));
             let output = AgentOutput {
                 status: AgentStatus::Retry,
                 summary: "runner error (see runner_error.log)".to_string(),
+                follow_ups: Vec::new(),
             };
             let guard = GuardOutcome::Skipped;
             // Runner-internal failures do not consume node attempts. Attempts increment only from
             // successful agent outputs via `apply_state_updates()`.
             let tree_after = prev_tree.clone();
+            let restore_start = Instant::now();
             write_tree(&tree_path, &tree_after)?;
             StepAttemptResult {
                 output,
                 guard,
+                review: None,
                 tree_after,
                 runner_error_log,
                 agent_error_log: None,
+                phases: PhaseTimings {
+                    state_update_ms: Some(elapsed_ms(restore_start)),
+                    ..PhaseTimings::default()
+                },
             }
         }
     };
@@ -290,21 +526,34 @@ pub fn run_step<E: Executor, G: GuardRunner>(
     let StepAttemptResult {
         output,
         guard: guard_outcome,
+        review,
         tree_after,
-        runner_error_log: _,
-        agent_error_log: _,
+        runner_error_log,
+        agent_error_log,
+        mut phases,
     } = attempt_result;
+    phases.context_build_ms = Some(context_build_ms);
 
     let guard_log = fs::read_to_string(&guard_log_path).ok();
-    let meta = IterationMeta {
+    let diffstat = staged_diffstat(root)?;
+    let logs_start = Instant::now();
+    let mut meta = IterationMeta {
         run_id: run_id.clone(),
         iter,
         node_id: selected_id.clone(),
         status: output.status,
         guard: guard_outcome,
-        started_at: None,
+        review,
+        started_at: Some(started_at),
         ended_at: None,
-        duration_ms: Some(start.elapsed().as_millis() as u64),
+        duration_ms: Some(elapsed_ms(start)),
+        diffstat: Some(diffstat),
+        phases,
+        prompt_templates: template_kinds
+            .iter()
+            .map(|kind| templates.get(*kind).reference())
+            .collect(),
+        interrupted: None,
     };
     write_iteration(&IterationWriteRequest {
         root,
@@ -328,15 +577,65 @@ pub fn run_step<E: Executor, G: GuardRunner>(
         Some(output.summary.clone())
     };
     run_state.last_guard = Some(guard_outcome);
+    run_state.last_review = review;
+    // Each verification round counts, agent errors included; runner errors do not.
+    if verifying && step_error.is_none() {
+        run_state.verify_rounds += 1;
+        run_state.goal_verified = output.status == AgentStatus::Done;
+    }
     write_run_state(&run_state_path, &run_state)?;
+    meta.phases.state_update_ms =
+        Some(meta.phases.state_update_ms.unwrap_or(0) + elapsed_ms(logs_start));
+    journal.record(JournalEvent::StateUpdated {
+        status: output.status,
+        guard: guard_outcome,
+        next_iter: run_state.next_iter,
+    });
+
+    // attempts.jsonl is gitignored: append before committing so a crash in the commit keeps
+    // the record.
+    let review_log = fs::read_to_string(&review_log_path).ok();
+    let failure = match (guard_outcome, review) {
+        (GuardOutcome::Fail, _) => guard_log.as_deref(),
+        (_, Some(ReviewVerdict::Reject)) => review_log.as_deref(),
+        _ => agent_error_log.as_deref(),
+    };
+    append_attempt(
+        &attempts_path(root),
+        &AttemptRecord {
+            run_id: run_id.clone(),
+            node_id: selected_id.clone(),
+            iter,
+            status: output.status,
+            guard: guard_outcome,
+            review,
+            summary: output.summary.clone(),
+            failure: failure.and_then(failure_digest),
+            error: runner_error_log.as_deref().and_then(failure_digest),
+            commit: None,
+        },
+    )?;
 
-    commit_iteration(
+    let commit_start = Instant::now();
+    commit_iteration(root, &meta, &output.summary)?;
+    meta.phases.commit_ms = Some(elapsed_ms(commit_start));
+    journal.record(JournalEvent::Committed {
+        node_id: selected_id.clone(),
+        status: output.status,
+        guard: guard_outcome,
+    });
+    // meta.json is gitignored, so it can record the commit's outcome.
+    meta.ended_at = Some(now_rfc3339());
+    meta.duration_ms = Some(elapsed_ms(start));
+    write_meta(root, &meta)?;
+    run_post_commit_hooks(
         root,
-        &run_id,
-        iter,
-        &selected_id,
-        output.status,
-        guard_outcome,
+        &cfg,
+        &config.interrupt,
+        &meta,
+        &iter_dir,
+        &tree_after,
+        &run_state,
     )?;
 
     if let Some(err) = step_error {
@@ -349,36 +648,64 @@ pub fn run_step<E: Executor, G: GuardRunner>(
         selected_id,
         status: output.status,
         guard: guard_outcome,
+        review,
     })
 }
 
 fn attempt_decompose<E: Executor>(
     decomposer_agent: &DecomposerAgent,
     ctx: &AttemptContext<'_, E>,
-    max_attempts_default: u32,
+    cfg: &RunnerConfig,
 ) -> Result<StepAttemptResult> {
     // Phase 1: decomposer agent expands the selected leaf into children.
+    let exec_start = journal_exec_started(ctx, "decompose");
     let decomposition = decomposer_agent.run(
         ctx.executor,
         ctx.root,
         ctx.iter_dir,
         ctx.prompt_inputs,
         ctx.deadline,
-    )?;
-
+    );
+    journal_exec_finished(
+        ctx,
+        "decompose",
+        exec_start,
+        decomposition.as_ref().ok().map(|_| AgentStatus::Decomposed),
+    );
+    let (decomposition, mut phases) = agent_phases(decomposition?);
+
+    let validation_start = Instant::now();
+    let notes_errors = check_notes(ctx)?;
+    if !notes_errors.is_empty() {
+        let msg = format!("agent error: {}", notes_errors.join("; "));
+        phases.tree_validation_ms = Some(elapsed_ms(validation_start));
+        return retry_with_log(ctx.prev_tree, ctx.tree_path, ctx.selected_id, msg, phases);
+    }
     if decomposition.children.is_empty() {
         let msg = format!(
             "agent error: decomposer returned empty children list for '{}'",
             ctx.selected_id
         );
-        return retry_with_log(ctx.prev_tree, ctx.tree_path, ctx.selected_id, msg);
+        phases.tree_validation_ms = Some(elapsed_ms(validation_start));
+        return retry_with_log(ctx.prev_tree, ctx.tree_path, ctx.selected_id, msg, phases);
+    }
+    let limit_errors = check_decomposition(
+        ctx.prev_tree,
+        ctx.selected_id,
+        &decomposition.children,
+        &cfg.decomposition,
+    );
+    if !limit_errors.is_empty() {
+        let msg = format!("agent error: {}", limit_errors.join("; "));
+        phases.tree_validation_ms = Some(elapsed_ms(validation_start));
+        return retry_with_log(ctx.prev_tree, ctx.tree_path, ctx.selected_id, msg, phases);
     }
 
     let next_tree = decompose_tree(
         ctx.prev_tree,
         ctx.selected_id,
         &decomposition.children,
-        max_attempts_default,
+        cfg.max_attempts_default,
     )?;
 
     // Sanity-check: decomposition must not introduce children elsewhere.
@@ -398,12 +725,134 @@ fn attempt_decompose<E: Executor>(
         ctx.selected_id,
         AgentStatus::Decomposed,
     )?;
+    phases.tree_validation_ms = Some(elapsed_ms(validation_start));
 
     let output = AgentOutput {
         status: AgentStatus::Decomposed,
         summary: decomposition.summary,
+        follow_ups: Vec::new(),
+    };
+    let guard_outcome = GuardOutcome::Skipped;
+    let update_start = Instant::now();
+    let updated_tree = apply_state_updates_and_write(
+        ctx.prev_tree,
+        ctx.tree_path,
+        ctx.selected_id,
+        output.status,
+        guard_outcome,
+        next_tree,
+        None,
+    )?;
+
+    phases.state_update_ms = Some(elapsed_ms(update_start));
+
+    Ok(StepAttemptResult {
+        output,
+        guard: guard_outcome,
+        review: None,
+        tree_after: updated_tree,
+        runner_error_log: None,
+        agent_error_log: None,
+        phases,
+    })
+}
+
+/// Verify the completed tree against the root goal: approve it, or append follow-up children
+/// for the gaps under the root.
+///
+/// Agent errors do not touch the root's attempts; the round still counts (see
+/// `verifier.max_rounds`).
+fn attempt_verify<E: Executor>(
+    verifier_agent: &VerifierAgent,
+    ctx: &AttemptContext<'_, E>,
+    cfg: &RunnerConfig,
+) -> Result<StepAttemptResult> {
+    let exec_start = journal_exec_started(ctx, "verify");
+    let verification = verifier_agent.run(
+        ctx.executor,
+        ctx.root,
+        ctx.iter_dir,
+        ctx.prompt_inputs,
+        ctx.deadline,
+    );
+    journal_exec_finished(ctx, "verify", exec_start, None);
+    let (verification, mut phases) = agent_phases(verification?);
+
+    let validation_start = Instant::now();
+    let mut agent_errors = check_notes(ctx)?;
+    agent_errors.extend(discard_read_only_edits(
+        ctx.root,
+        "verifier",
+        verifier_agent.output_schema(),
+    )?);
+    if verification.approved && !verification.children.is_empty() {
+        agent_errors.push("agent error: verifier approved but returned children".to_string());
+    }
+    if !verification.approved && verification.children.is_empty() {
+        agent_errors.push("agent error: verifier found gaps but returned no children".to_string());
+    }
+    if !verification.approved {
+        agent_errors.extend(
+            check_decomposition(
+                ctx.prev_tree,
+                ctx.selected_id,
+                &verification.children,
+                &cfg.decomposition,
+            )
+            .into_iter()
+            .map(|error| format!("agent error: {error}")),
+        );
+    }
+    if !agent_errors.is_empty() {
+        phases.tree_validation_ms = Some(elapsed_ms(validation_start));
+        let msg = agent_errors.join("; ");
+        write_tree(ctx.tree_path, ctx.prev_tree)?;
+        return Ok(StepAttemptResult {
+            output: AgentOutput {
+                status: AgentStatus::Retry,
+                summary: msg.clone(),
+                follow_ups: Vec::new(),
+            },
+            guard: GuardOutcome::Skipped,
+            review: None,
+            tree_after: ctx.prev_tree.clone(),
+            runner_error_log: None,
+            agent_error_log: Some(msg),
+            phases,
+        });
+    }
+
+    let (status, next_tree) = if verification.approved {
+        (AgentStatus::Done, ctx.prev_tree.clone())
+    } else {
+        let next_tree = decompose_tree(
+            ctx.prev_tree,
+            ctx.selected_id,
+            &verification.children,
+            cfg.max_attempts_default,
+        )?;
+        // The root passed with the rest of the tree; apart from the appended children,
+        // nothing may change.
+        let mut existing = next_tree.clone();
+        existing.children.truncate(ctx.prev_tree.children.len());
+        validate_post_exec_tree(ctx.prev_tree, &existing)?;
+        validate_status(
+            ctx.prev_tree,
+            &next_tree,
+            ctx.selected_id,
+            AgentStatus::Decomposed,
+        )?;
+        (AgentStatus::Decomposed, next_tree)
+    };
+    phases.tree_validation_ms = Some(elapsed_ms(validation_start));
+
+    let output = AgentOutput {
+        status,
+        summary: verification.summary,
+        follow_ups: Vec::new(),
     };
     let guard_outcome = GuardOutcome::Skipped;
+    let update_start = Instant::now();
     let updated_tree = apply_state_updates_and_write(
         ctx.prev_tree,
         ctx.tree_path,
@@ -411,14 +860,18 @@ fn attempt_decompose<E: Executor>(
         output.status,
         guard_outcome,
         next_tree,
+        None,
     )?;
+    phases.state_update_ms = Some(elapsed_ms(update_start));
 
     Ok(StepAttemptResult {
         output,
         guard: guard_outcome,
+        review: None,
         tree_after: updated_tree,
         runner_error_log: None,
         agent_error_log: None,
+        phases,
     })
 }
 
@@ -427,21 +880,52 @@ fn attempt_execute<E: Executor, G: GuardRunner>(
     ctx: &AttemptContext<'_, E>,
     exec: &ExecuteContext<'_, G>,
 ) -> Result<StepAttemptResult> {
+    // Optional read-only planner; its plan becomes the executor's planner notes.
+    let mut planner_notes = None;
+    let mut planning_ms = None;
+    if let Some(planner) = exec.planner {
+        let plan_start = Instant::now();
+        let planned = run_planner(planner, ctx)?;
+        planning_ms = Some(elapsed_ms(plan_start));
+        match planned {
+            Ok(notes) => planner_notes = Some(notes),
+            Err(msg) => {
+                let phases = PhaseTimings {
+                    planning_ms,
+                    ..PhaseTimings::default()
+                };
+                return retry_with_log(ctx.prev_tree, ctx.tree_path, ctx.selected_id, msg, phases);
+            }
+        }
+    }
+
     // Phase 2: executor agent performs work for the selected node.
+    let exec_start = journal_exec_started(ctx, "execute");
     let output = executor_agent.run(
         ctx.executor,
         ctx.root,
         ctx.iter_dir,
         ctx.prompt_inputs,
-        None,
+        planner_notes.as_deref(),
         ctx.deadline,
-    )?;
+    );
+    journal_exec_finished(
+        ctx,
+        "execute",
+        exec_start,
+        output.as_ref().ok().map(|run| run.output.status),
+    );
+    let (output, mut phases) = agent_phases(output?);
+    phases.planning_ms = planning_ms;
 
+    let validation_start = Instant::now();
     let next_tree = match load_tree(exec.schema_path, ctx.tree_path) {
         Ok(tree) => tree,
         Err(err) => {
             let msg = format!("tree invalid after executor: {err}");
-            return retry_with_log(ctx.prev_tree, ctx.tree_path, ctx.selected_id, msg);
+            phases.tree_validation_ms = Some(elapsed_ms(validation_start));
+            log_dropped_follow_ups(ctx.iter_dir, &output.follow_ups, "tree invalid")?;
+            return retry_with_log(ctx.prev_tree, ctx.tree_path, ctx.selected_id, msg, phases);
         }
     };
 
@@ -460,17 +944,33 @@ fn attempt_execute<E: Executor, G: GuardRunner>(
         ctx.selected_id,
         output.status,
     ));
+    if output.status == AgentStatus::Blocked && output.summary.trim().is_empty() {
+        agent_errors.push("status=blocked requires the reason as summary".to_string());
+    }
+    if output.status == AgentStatus::Done {
+        agent_errors.extend(check_follow_ups(
+            ctx.prev_tree,
+            ctx.selected_id,
+            &output.follow_ups,
+            exec.decomposition,
+        ));
+    }
+    agent_errors.extend(check_notes(ctx)?);
+    phases.tree_validation_ms = Some(elapsed_ms(validation_start));
     if !agent_errors.is_empty() {
         agent_errors.sort();
         let msg = format!("agent error: {}", agent_errors.join("; "));
-        return retry_with_log(ctx.prev_tree, ctx.tree_path, ctx.selected_id, msg);
+        log_dropped_follow_ups(ctx.iter_dir, &output.follow_ups, "agent error")?;
+        return retry_with_log(ctx.prev_tree, ctx.tree_path, ctx.selected_id, msg, phases);
     }
 
     let guard_outcome = if output.status == AgentStatus::Done {
         // Guards only run when the agent claims completion, and they receive the remaining
         // budget from the per-iteration timeout.
         let guard_timeout = remaining_budget(ctx.deadline)?;
-        run_guards_if_needed(
+        ctx.journal.record(JournalEvent::GuardStarted);
+        let guard_start = Instant::now();
+        let guard_outcome = run_guards_if_needed(
             output.status,
             exec.guard_runner,
             &GuardRequest {
@@ -478,30 +978,295 @@ fn attempt_execute<E: Executor, G: GuardRunner>(
                 log_path: exec.guard_log_path.to_path_buf(),
                 timeout: guard_timeout,
                 output_limit_bytes: exec.guard_output_limit_bytes,
+                interrupt: ctx.interrupt.clone(),
             },
-        )?
+        );
+        let guards_ms = elapsed_ms(guard_start);
+        phases.guards_ms = Some(guards_ms);
+        ctx.journal.record(JournalEvent::GuardFinished {
+            outcome: guard_outcome.as_ref().ok().copied(),
+            duration_ms: guards_ms,
+        });
+        let guard_outcome = guard_outcome?;
+        // Guards cut off by the loop's time limit did not fail on the agent's work: end the
+        // step with a runner error so no node attempt is consumed.
+        if guard_outcome == GuardOutcome::Fail
+            && ctx.loop_limit.is_some_and(|limit| Instant::now() >= limit)
+        {
+            return Err(anyhow!("guards cut off by the loop time limit"));
+        }
+        guard_outcome
     } else {
         GuardOutcome::Skipped
     };
 
+    // The verdict is recorded on its own (`review.log`, `review` in meta), but a rejection
+    // fails the node like a guard failure: the attempt counts and the review becomes the next
+    // attempt's failure context.
+    let mut review = None;
+    if let Some(reviewer) = exec
+        .reviewer
+        .filter(|_| guard_outcome == GuardOutcome::Pass)
+    {
+        let review_start = Instant::now();
+        let reviewed = run_reviewer(reviewer, ctx, exec)?;
+        phases.review_ms = Some(elapsed_ms(review_start));
+        match reviewed {
+            Ok(output) => {
+                fs::write(exec.review_log_path, render_review(&output))
+                    .with_context(|| format!("write {}", exec.review_log_path.display()))?;
+                review = Some(output.verdict);
+            }
+            Err(msg) => {
+                log_dropped_follow_ups(ctx.iter_dir, &output.follow_ups, "reviewer error")?;
+                return retry_with_log(ctx.prev_tree, ctx.tree_path, ctx.selected_id, msg, phases);
+            }
+        }
+    }
+    let gate = match review {
+        Some(ReviewVerdict::Reject) => GuardOutcome::Fail,
+        _ => guard_outcome,
+    };
+
+    let update_start = Instant::now();
+    let next_tree = if output.status != AgentStatus::Done {
+        let reason = format!("status={}", output.status.as_str());
+        log_dropped_follow_ups(ctx.iter_dir, &output.follow_ups, &reason)?;
+        next_tree
+    } else if gate != GuardOutcome::Pass {
+        let reason = match review {
+            Some(ReviewVerdict::Reject) => "review=reject".to_string(),
+            _ => format!("guard={}", guard_outcome.as_str()),
+        };
+        log_dropped_follow_ups(ctx.iter_dir, &output.follow_ups, &reason)?;
+        next_tree
+    } else {
+        insert_follow_ups(
+            &next_tree,
+            ctx.selected_id,
+            &output.follow_ups,
+            exec.max_attempts_default,
+        )?
+    };
+    let blocked_reason = (output.status == AgentStatus::Blocked).then(|| output.summary.trim());
     let updated_tree = apply_state_updates_and_write(
         ctx.prev_tree,
         ctx.tree_path,
         ctx.selected_id,
         output.status,
-        guard_outcome,
+        gate,
         next_tree,
+        blocked_reason,
     )?;
+    if let Some(reason) = blocked_reason {
+        append_blocked_question(
+            ctx.root,
+            ctx.selected_id,
+            &ctx.prompt_inputs.selected_path,
+            reason,
+        )?;
+    }
+
+    phases.state_update_ms = Some(elapsed_ms(update_start));
 
     Ok(StepAttemptResult {
         output,
         guard: guard_outcome,
+        review,
         tree_after: updated_tree,
         runner_error_log: None,
         agent_error_log: None,
+        phases,
     })
 }
 
+/// Run the planner and render its plan, or return an agent error when it changed any file
+/// (those changes are discarded).
+fn run_planner<E: Executor>(
+    planner: &PlannerAgent,
+    ctx: &AttemptContext<'_, E>,
+) -> Result<std::result::Result<String, String>> {
+    let exec_start = journal_exec_started(ctx, "plan");
+    let plan = planner.run(
+        ctx.executor,
+        ctx.root,
+        ctx.iter_dir,
+        ctx.prompt_inputs,
+        ctx.deadline,
+    );
+    journal_exec_finished(ctx, "plan", exec_start, None);
+    let plan = plan?.output;
+
+    if let Some(msg) = discard_read_only_edits(ctx.root, "planner", planner.output_schema())? {
+        return Ok(Err(msg));
+    }
+    Ok(Ok(render_plan(&plan)))
+}
+
+/// Discard the changes a read-only agent made, returning the agent error to record when there
+/// were any.
+///
+/// Everything the runner wants to keep at this point is committed or staged, so any unstaged
+/// or untracked change is the agent's, runner state under `.runner/` included. The exceptions
+/// are gitignored paths (iteration logs, caches, `attempts.jsonl`), which git does not report,
+/// and the agent's `output_schema`, which the runner wrote itself and restores if it changed.
+fn discard_read_only_edits(
+    root: &Path,
+    agent: &str,
+    output_schema: (&Path, &str),
+) -> Result<Option<String>> {
+    let (schema_path, schema) = output_schema;
+    let schema_intact = fs::read_to_string(schema_path).is_ok_and(|current| current == schema);
+    let git = Git::new(root);
+    let changed: Vec<String> = git
+        .status_porcelain()?
+        .into_iter()
+        .filter(|entry| entry.code == "??" || !entry.code.ends_with(' '))
+        .map(|entry| entry.path)
+        .filter(|path| !(schema_intact && root.join(path) == schema_path))
+        .collect();
+    if changed.is_empty() {
+        return Ok(None);
+    }
+    git.discard_unstaged_changes()?;
+    write_output_schema(schema_path, schema)?;
+    Ok(Some(format!(
+        "agent error: {agent} must not modify files (changed: {})",
+        changed.join(", ")
+    )))
+}
+
+/// Run the reviewer on the node's changes: this attempt's (staged here) and those of earlier
+/// commits for the node in this run. Returns an agent error when the reviewer changed any file
+/// (those changes are discarded).
+fn run_reviewer<E: Executor, G: GuardRunner>(
+    reviewer: &ReviewerAgent,
+    ctx: &AttemptContext<'_, E>,
+    exec: &ExecuteContext<'_, G>,
+) -> Result<std::result::Result<ReviewOutput, String>> {
+    let git = Git::new(ctx.root);
+    git.add_all()?;
+    let changes = node_changes(&git, exec.run_id, ctx.selected_id, exec.reviewer_cfg)?;
+
+    let exec_start = journal_exec_started(ctx, "review");
+    let review = reviewer.run(
+        ctx.executor,
+        ctx.root,
+        ctx.iter_dir,
+        ctx.prompt_inputs,
+        &changes,
+        ctx.deadline,
+    );
+    journal_exec_finished(ctx, "review", exec_start, None);
+    let review = review?.output;
+
+    // The attempt's changes are staged, so the reviewer's own show up as unstaged.
+    if let Some(msg) = discard_read_only_edits(ctx.root, "reviewer", reviewer.output_schema())? {
+        return Ok(Err(msg));
+    }
+    Ok(Ok(review))
+}
+
+/// Diff shown to the reviewer: staged changes first, then earlier commits for the node, newest
+/// first, cut to `cfg.max_diff_bytes`.
+fn node_changes(git: &Git, run_id: &str, node_id: &str, cfg: &ReviewerConfig) -> Result<String> {
+    let mut sections = Vec::new();
+    let staged = git.staged_diff(&[], &cfg.exclude)?;
+    if !staged.trim().is_empty() {
+        sections.push(format!(
+            "Changes in this attempt:\n\n```diff\n{}\n```",
+            staged.trim_end()
+        ));
+    }
+    for commit in git.commits_with_trailers(&[("Runner-Run", run_id), ("Runner-Node", node_id)])? {
+        let diff = git.commit_diff(&commit.sha, &[], &cfg.exclude)?;
+        if diff.trim().is_empty() {
+            continue;
+        }
+        let short_sha = commit.sha.get(..12).unwrap_or(&commit.sha);
+        sections.push(format!(
+            "Changes from {short_sha} ({}):\n\n```diff\n{}\n```",
+            commit.subject,
+            diff.trim_end()
+        ));
+    }
+    if sections.is_empty() {
+        return Ok("(no changes)".to_string());
+    }
+    Ok(truncate_to(
+        &sections.join("\n\n"),
+        cfg.max_diff_bytes,
+        TruncateStrategy::Head,
+        &ByteCount,
+    ))
+}
+
+/// List a blocked node in `.runner/state/questions.md` so a human sees what it needs.
+fn append_blocked_question(root: &Path, id: &str, path: &str, reason: &str) -> Result<()> {
+    let questions_path = root.join(".runner/state/questions.md");
+    let mut questions = fs::read_to_string(&questions_path).unwrap_or_default();
+    if !questions.is_empty() && !questions.ends_with('\n') {
+        questions.push('\n');
+    }
+    let reason = reason.split_whitespace().collect::<Vec<_>>().join(" ");
+    questions.push_str(&format!("- [blocked] {id} ({path}): {reason}\n"));
+    fs::write(&questions_path, questions)
+        .with_context(|| format!("write {}", questions_path.display()))
+}
+
+/// Notes violations by the agent; on violation the notes are restored to their pre-agent state.
+fn check_notes<E: Executor>(ctx: &AttemptContext<'_, E>) -> Result<Vec<String>> {
+    let errors = check_notes_changes(
+        ctx.notes_before,
+        &NotesSnapshot::capture(ctx.root)?,
+        ctx.selected_id,
+    );
+    if !errors.is_empty() {
+        ctx.notes_before.restore(ctx.root)?;
+    }
+    Ok(errors)
+}
+
+fn journal_exec_started<E: Executor>(ctx: &AttemptContext<'_, E>, phase: &str) -> Instant {
+    ctx.journal.record(JournalEvent::ExecStarted {
+        node_id: ctx.selected_id.to_string(),
+        phase: phase.to_string(),
+    });
+    Instant::now()
+}
+
+fn journal_exec_finished<E: Executor>(
+    ctx: &AttemptContext<'_, E>,
+    phase: &str,
+    started: Instant,
+    status: Option<AgentStatus>,
+) {
+    ctx.journal.record(JournalEvent::ExecFinished {
+        node_id: ctx.selected_id.to_string(),
+        phase: phase.to_string(),
+        status,
+        duration_ms: elapsed_ms(started),
+    });
+}
+
+/// Split an agent run into its output and the phases it timed.
+fn agent_phases<T>(run: AgentRun<T>) -> (T, PhaseTimings) {
+    let phases = PhaseTimings {
+        prompt_render_ms: Some(run.prompt_render.as_millis() as u64),
+        agent_exec_ms: Some(run.exec.as_millis() as u64),
+        ..PhaseTimings::default()
+    };
+    (run.output, phases)
+}
+
+fn elapsed_ms(start: Instant) -> u64 {
+    start.elapsed().as_millis() as u64
+}
+
+pub(crate) fn now_rfc3339() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
 fn apply_state_updates_and_write(
     prev_tree: &Node,
     tree_path: &Path,
@@ -509,6 +1274,7 @@ fn apply_state_updates_and_write(
     status: AgentStatus,
     guard_outcome: GuardOutcome,
     mut next_tree: Node,
+    blocked_reason: Option<&str>,
 ) -> Result<Node> {
     apply_state_updates(
         prev_tree,
@@ -518,6 +1284,10 @@ fn apply_state_updates_and_write(
         guard_outcome,
     )
     .map_err(|err| anyhow!("state update failed: {err}"))?;
+    if let Some(reason) = blocked_reason {
+        mark_blocked(&mut next_tree, selected_id, reason)
+            .map_err(|err| anyhow!("state update failed: {err}"))?;
+    }
     write_tree(tree_path, &next_tree)?;
     Ok(next_tree)
 }
@@ -527,18 +1297,34 @@ fn retry_with_log(
     tree_path: &Path,
     selected_id: &str,
     msg: String,
+    mut phases: PhaseTimings,
 ) -> Result<StepAttemptResult> {
+    let update_start = Instant::now();
     let (output, tree_after) = apply_agent_retry(prev_tree, selected_id, msg.clone())?;
     write_tree(tree_path, &tree_after)?;
+    phases.state_update_ms = Some(elapsed_ms(update_start));
     Ok(StepAttemptResult {
         output,
         guard: GuardOutcome::Skipped,
+        review: None,
         tree_after,
         runner_error_log: None,
         agent_error_log: Some(msg),
+        phases,
     })
 }
 
+/// Record a forced interrupt and roll the worktree back to the last iteration commit.
+///
+/// `tree.json` and `run_state.json` are tracked, so the reset also restores them; the
+/// iteration directory is ignored and survives for inspection. `runner step` then retries
+/// the same iteration number.
+fn abort_interrupted_iteration(root: &Path, record: &InterruptedRecord) -> Result<()> {
+    tracing::warn!(phase = %record.phase, "iteration interrupted, discarding changes");
+    write_interrupted(root, record)?;
+    Git::new(root).discard_worktree_changes()
+}
+
 fn load_or_default_run_state(path: &Path) -> Result<RunState> {
     if path.exists() {
         return load_run_state(path);
@@ -554,25 +1340,30 @@ fn enforce_git_policy_pre_step(root: &Path) -> Result<()> {
             "refuse to run on '{branch}' (run `runner start` to create runner/<run-id> branch)"
         ));
     }
-    git.ensure_clean()?;
-    ensure_runner_gitignore(root)?;
+    git.ensure_clean().map_err(|err| {
+        anyhow!("{err}\n(if a previous iteration was cut short, run `runner recover`)")
+    })?;
     Ok(())
 }
 
-fn ensure_runner_gitignore(root: &Path) -> Result<()> {
-    let path = root.join(".runner").join(".gitignore");
-    if !path.exists() {
-        return Err(anyhow!("missing {} (run `runner start`)", path.display()));
-    }
-    let contents = fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
-    for required in ["context/", "iterations/"] {
-        if !contents.lines().any(|l| l.trim() == required) {
-            return Err(anyhow!(
-                "missing '{}' in {} (run `runner start`)",
-                required,
-                path.display()
-            ));
-        }
+/// Bring runner-owned files written by older runner versions up to date, each change in its own
+/// commit so the iteration commit stays the agent's. Runs only once the run id and branch
+/// checks passed, so nothing is committed on a branch the step then refuses.
+fn update_runner_files(root: &Path) -> Result<()> {
+    let git = Git::new(root);
+    // Lines required by newer runner versions are added, and an older tree schema upgraded,
+    // instead of failing runs started by older ones.
+    let added = ensure_runner_gitignore(&root.join(".runner").join(".gitignore"))?;
+    if !added.is_empty() {
+        tracing::info!(lines = ?added, "added missing .runner/.gitignore lines");
+        git.add_all()?;
+        git.commit_staged("chore(loop): update .runner/.gitignore")?;
+    }
+    let schema_path = root.join(".runner").join("state").join("schema.json");
+    if let Some(previous) = upgrade_tree_schema(&schema_path)? {
+        tracing::info!(previous = %previous, "upgraded .runner/state/schema.json");
+        git.add_all()?;
+        git.commit_staged("chore(loop): upgrade .runner/state/schema.json")?;
     }
     Ok(())
 }
@@ -593,7 +1384,7 @@ fn enforce_run_id_matches_goal(root: &Path, run_id: &str) -> Result<()> {
     Ok(())
 }
 
-fn enforce_on_run_branch(root: &Path, run_id: &str) -> Result<()> {
+pub(crate) fn enforce_on_run_branch(root: &Path, run_id: &str) -> Result<()> {
     let git = Git::new(root);
     let expected = format!("runner/{run_id}");
     let branch = git.current_branch()?;
@@ -605,61 +1396,183 @@ fn enforce_on_run_branch(root: &Path, run_id: &str) -> Result<()> {
     Ok(())
 }
 
-fn commit_iteration(
-    root: &Path,
-    run_id: &str,
-    iter: u32,
-    node_id: &str,
+/// Whether an iteration passed its node: done, guards passed and the review (if any) approved.
+pub fn node_passed(
     status: AgentStatus,
     guard: GuardOutcome,
-) -> Result<()> {
-    let git = Git::new(root);
-    git.add_all()?;
+    review: Option<ReviewVerdict>,
+) -> bool {
+    status == AgentStatus::Done
+        && guard == GuardOutcome::Pass
+        && review != Some(ReviewVerdict::Reject)
+}
 
-    let status_str = match status {
-        AgentStatus::Done => "done",
-        AgentStatus::Retry => "retry",
-        AgentStatus::Decomposed => "decomposed",
-    };
-    let guard_str = match guard {
-        GuardOutcome::Pass => "pass",
-        GuardOutcome::Fail => "fail",
-        GuardOutcome::Skipped => "skipped",
+/// Fire `post_step` plus whichever outcome hooks this iteration triggered.
+///
+/// Outcome hooks fire once, from the iteration that caused the transition: the node passed,
+/// the tree became complete, the next leaf is stuck, or `max_iterations` is used up.
+fn run_post_commit_hooks(
+    root: &Path,
+    cfg: &RunnerConfig,
+    interrupt: &Interrupt,
+    meta: &IterationMeta,
+    iter_dir: &Path,
+    tree_after: &Node,
+    run_state: &RunState,
+) -> Result<()> {
+    let payload = |event| {
+        let mut payload = HookPayload::new(event, &meta.run_id, meta.iter, &meta.node_id, iter_dir);
+        payload.status = Some(meta.status);
+        payload.guard = Some(meta.guard);
+        payload.review = meta.review;
+        payload
     };
-    let msg = format!(
-        "chore(loop): run {run_id} iter {iter} node {node_id} status={status_str} guard={guard_str}"
-    );
-    let committed = git.commit_staged(&msg)?;
-    if !committed {
-        return Err(anyhow!("expected changes to commit for iteration {iter}"));
-    }
-    Ok(())
-}
 
-fn validate_post_exec_tree(prev: &Node, next: &Node) -> Result<()> {
-    let errors = check_passed_node_immutability(prev, next);
-    if !errors.is_empty() {
-        return Err(anyhow!("immutability failed: {}", errors.join("; ")));
+    run_step_hook(&cfg.hooks, root, &payload(HookEvent::PostStep), interrupt)?;
+    if node_passed(meta.status, meta.guard, meta.review) {
+        run_step_hook(
+            &cfg.hooks,
+            root,
+            &payload(HookEvent::OnNodePassed),
+            interrupt,
+        )?;
+    }
+    match select_with_verification(tree_after, &cfg.verifier, run_state)? {
+        SelectOutcome::Complete => {
+            run_step_hook(&cfg.hooks, root, &payload(HookEvent::OnComplete), interrupt)?;
+        }
+        SelectOutcome::Stuck(next) => {
+            let mut stuck = payload(HookEvent::OnStuck);
+            stuck.stuck_node_id = Some(next.id);
+            run_step_hook(&cfg.hooks, root, &stuck, interrupt)?;
+        }
+        // A blocked tree needs a human just like a stuck leaf.
+        SelectOutcome::Blocked(blocked) => {
+            let mut stuck = payload(HookEvent::OnStuck);
+            stuck.stuck_node_id = blocked.into_iter().next().map(|node| node.id);
+            run_step_hook(&cfg.hooks, root, &stuck, interrupt)?;
+        }
+        SelectOutcome::Open(_) | SelectOutcome::Verify(_) if meta.iter >= cfg.max_iterations => {
+            run_step_hook(&cfg.hooks, root, &payload(HookEvent::OnLimit), interrupt)?;
+        }
+        SelectOutcome::Open(_) | SelectOutcome::Verify(_) => {}
     }
     Ok(())
 }
 
-fn validate_status(prev: &Node, next: &Node, selected_id: &str, status: AgentStatus) -> Result<()> {
-    let errors = validate_status_invariants(prev, next, selected_id, status);
-    if errors.is_empty() {
+/// Run a hook and discard any worktree changes it made.
+///
+/// Hooks run on a clean worktree (before the agent, after the iteration commit), so their edits
+/// would otherwise be committed as the agent's work or make the next step refuse to run. The
+/// discarded paths are noted in the hook log.
+fn run_step_hook(
+    cfg: &HooksConfig,
+    root: &Path,
+    payload: &HookPayload,
+    interrupt: &Interrupt,
+) -> Result<()> {
+    if run_hook(cfg, root, payload, interrupt)? == HookOutcome::NotConfigured {
         return Ok(());
     }
-    Err(anyhow!("status invariants failed: {}", errors.join("; ")))
+    let git = Git::new(root);
+    let dirty = git.status_porcelain()?;
+    if dirty.is_empty() {
+        return Ok(());
+    }
+    git.discard_worktree_changes()?;
+    let paths: Vec<&str> = dirty.iter().map(|entry| entry.path.as_str()).collect();
+    tracing::warn!(hook = payload.event.as_str(), paths = ?paths, "discarded hook worktree changes");
+    let log_path = payload
+        .iteration_dir
+        .join("hooks")
+        .join(format!("{}.log", payload.event.as_str()));
+    let mut log = fs::read_to_string(&log_path).unwrap_or_default();
+    log.push_str(&format!(
+        "\n[worktree changes discarded (hooks must not edit the repo): {}]\n",
+        paths.join(", ")
+    ));
+    fs::write(&log_path, log).with_context(|| format!("write hook log {}", log_path.display()))
 }
 
-fn apply_agent_retry(
-    prev_tree: &Node,
-    selected_id: &str,
-    summary: String,
-) -> Result<(AgentOutput, Node)> {
+/// Stage all changes and summarize them, excluding runner-owned state under `.runner/`.
+pub(crate) fn staged_diffstat(root: &Path) -> Result<DiffStat> {
+    let git = Git::new(root);
+    git.add_all()?;
+    git.staged_diff_stat_excluding(&[".runner/"])
+}
+
+/// Commit the iteration with a stable subject, the agent summary as body, and `Runner-*`
+/// trailers so history can be reconstructed from `git log` alone.
+fn commit_iteration(root: &Path, meta: &IterationMeta, summary: &str) -> Result<()> {
+    let git = Git::new(root);
+    git.add_all()?;
+
+    let msg = render_commit_message(meta, summary);
+    let committed = git.commit_staged(&msg)?;
+    if !committed {
+        return Err(anyhow!(
+            "expected changes to commit for iteration {}",
+            meta.iter
+        ));
+    }
+    Ok(())
+}
+
+fn render_commit_message(meta: &IterationMeta, summary: &str) -> String {
+    let status = meta.status.as_str();
+    let guard = meta.guard.as_str();
+    let mut msg = format!(
+        "chore(loop): run {} iter {} node {} status={status} guard={guard}",
+        meta.run_id, meta.iter, meta.node_id
+    );
+    if let Some(review) = meta.review {
+        msg.push_str(&format!(" review={}", review.as_str()));
+    }
+    msg.push_str("\n\n");
+    let summary = summary.trim();
+    if !summary.is_empty() {
+        msg.push_str(summary);
+        msg.push_str("\n\n");
+    }
+    msg.push_str(&format!("Runner-Run: {}\n", meta.run_id));
+    msg.push_str(&format!("Runner-Iter: {}\n", meta.iter));
+    msg.push_str(&format!("Runner-Node: {}\n", meta.node_id));
+    msg.push_str(&format!("Runner-Status: {status}\n"));
+    msg.push_str(&format!("Runner-Guard: {guard}\n"));
+    if let Some(review) = meta.review {
+        msg.push_str(&format!("Runner-Review: {}\n", review.as_str()));
+    }
+    if let Some(duration_ms) = meta.duration_ms {
+        msg.push_str(&format!("Runner-Duration-Ms: {duration_ms}\n"));
+    }
+    msg
+}
+
+fn validate_post_exec_tree(prev: &Node, next: &Node) -> Result<()> {
+    let errors = check_passed_node_immutability(prev, next);
+    if !errors.is_empty() {
+        return Err(anyhow!("immutability failed: {}", errors.join("; ")));
+    }
+    Ok(())
+}
+
+fn validate_status(prev: &Node, next: &Node, selected_id: &str, status: AgentStatus) -> Result<()> {
+    let errors = validate_status_invariants(prev, next, selected_id, status);
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(anyhow!("status invariants failed: {}", errors.join("; ")))
+}
+
+fn apply_agent_retry(
+    prev_tree: &Node,
+    selected_id: &str,
+    summary: String,
+) -> Result<(AgentOutput, Node)> {
     let output = AgentOutput {
         status: AgentStatus::Retry,
         summary,
+        follow_ups: Vec::new(),
     };
     let guard_outcome = GuardOutcome::Skipped;
 
@@ -687,29 +1600,178 @@ fn decompose_tree(
     let mut next_tree = prev_tree.clone();
     let selected = find_node_mut(&mut next_tree, selected_id)
         .ok_or_else(|| anyhow!("selected node '{}' not found in tree", selected_id))?;
-
+    // Children added to a node that already has some (goal verification) go after them.
+    let first_order = selected
+        .children
+        .iter()
+        .map(|child| child.order + 1)
+        .max()
+        .unwrap_or(0);
+
+    let ids = allocate_child_ids(selected_id, children.len(), &mut used_ids);
     for (idx, child) in children.iter().enumerate() {
-        let id = allocate_child_id(selected_id, &used_ids);
-        used_ids.insert(id.clone());
-
-        selected.children.push(Node {
-            id,
-            order: idx as i64,
-            title: child.title.clone(),
-            goal: child.goal.clone(),
-            acceptance: child.acceptance.clone(),
-            next: child.next,
-            passes: false,
-            attempts: 0,
-            max_attempts: max_attempts_default,
-            children: Vec::new(),
-        });
+        selected.children.push(open_node(
+            &ids,
+            idx,
+            first_order + idx as i64,
+            child,
+            max_attempts_default,
+        ));
     }
 
     next_tree.sort_children();
     Ok(next_tree)
 }
 
+/// Agent errors for executor follow-ups: the root has no siblings to add them to, and they are
+/// held to the decomposition limits as children of the selected node's parent.
+fn check_follow_ups(
+    prev_tree: &Node,
+    selected_id: &str,
+    follow_ups: &[TreeChildSpec],
+    limits: &DecompositionLimits,
+) -> Vec<String> {
+    if follow_ups.is_empty() {
+        return Vec::new();
+    }
+    match node_lineage(prev_tree, selected_id).as_deref() {
+        Some([.., parent, _]) => check_decomposition(prev_tree, &parent.id, follow_ups, limits)
+            .into_iter()
+            .map(|error| format!("follow_ups: {error}"))
+            .collect(),
+        _ => vec![format!(
+            "follow_ups: '{selected_id}' is the root and has no siblings; \
+             list the extra work in questions.md instead"
+        )],
+    }
+}
+
+/// Record executor follow-ups the attempt did not insert in `dropped_follow_ups.json`, so the
+/// reported work stays visible; a no-op without follow-ups.
+fn log_dropped_follow_ups(
+    iter_dir: &Path,
+    follow_ups: &[TreeChildSpec],
+    reason: &str,
+) -> Result<()> {
+    if follow_ups.is_empty() {
+        return Ok(());
+    }
+    tracing::warn!(
+        count = follow_ups.len(),
+        reason,
+        "executor follow-ups dropped"
+    );
+    write_dropped_follow_ups(
+        iter_dir,
+        &DroppedFollowUps {
+            reason: reason.to_string(),
+            follow_ups: follow_ups.to_vec(),
+        },
+    )?;
+    Ok(())
+}
+
+/// Insert executor follow-ups as open siblings directly after the selected node, moving later
+/// open siblings down.
+///
+/// Passed siblings keep their `order` (they are immutable); the follow-ups and the open
+/// siblings after the selected node take the next free orders around them.
+fn insert_follow_ups(
+    tree: &Node,
+    selected_id: &str,
+    follow_ups: &[TreeChildSpec],
+    max_attempts_default: u32,
+) -> Result<Node> {
+    if follow_ups.is_empty() {
+        return Ok(tree.clone());
+    }
+    let (parent_id, selected_order) = match node_lineage(tree, selected_id).as_deref() {
+        Some([.., parent, selected]) => (parent.id.clone(), selected.order),
+        _ => return Err(anyhow!("follow-ups for '{selected_id}' need a parent node")),
+    };
+    let mut used_ids = std::collections::HashSet::new();
+    collect_ids(tree, &mut used_ids);
+
+    let mut next_tree = tree.clone();
+    let parent = find_node_mut(&mut next_tree, &parent_id)
+        .ok_or_else(|| anyhow!("parent node '{}' not found in tree", parent_id))?;
+    let passed_orders: std::collections::HashSet<i64> = parent
+        .children
+        .iter()
+        .filter(|sibling| sibling.passes)
+        .map(|sibling| sibling.order)
+        .collect();
+    let mut next_order = selected_order;
+    let mut take_order = || {
+        next_order += 1;
+        while passed_orders.contains(&next_order) {
+            next_order += 1;
+        }
+        next_order
+    };
+
+    let ids = allocate_child_ids(&parent_id, follow_ups.len(), &mut used_ids);
+    let inserted: Vec<Node> = follow_ups
+        .iter()
+        .enumerate()
+        .map(|(idx, follow_up)| open_node(&ids, idx, take_order(), follow_up, max_attempts_default))
+        .collect();
+    parent.children.sort_by_key(|sibling| sibling.order);
+    for sibling in &mut parent.children {
+        if sibling.order > selected_order && !sibling.passes {
+            sibling.order = take_order();
+        }
+    }
+    parent.children.extend(inserted);
+
+    next_tree.sort_children();
+    Ok(next_tree)
+}
+
+/// Open node for `specs[idx]`, where `ids` are the ids allocated for the whole spec list;
+/// `depends_on` indexes resolve against them.
+fn open_node(
+    ids: &[String],
+    idx: usize,
+    order: i64,
+    spec: &TreeChildSpec,
+    max_attempts_default: u32,
+) -> Node {
+    Node {
+        id: ids[idx].clone(),
+        order,
+        title: spec.title.clone(),
+        goal: spec.goal.clone(),
+        acceptance: spec.acceptance.clone(),
+        next: spec.next,
+        passes: false,
+        attempts: 0,
+        max_attempts: max_attempts_default,
+        blocked: None,
+        skipped: false,
+        depends_on: spec
+            .depends_on
+            .iter()
+            .filter_map(|&dep| ids.get(dep).cloned())
+            .collect(),
+        children: Vec::new(),
+    }
+}
+
+fn allocate_child_ids(
+    parent_id: &str,
+    count: usize,
+    used_ids: &mut std::collections::HashSet<String>,
+) -> Vec<String> {
+    (0..count)
+        .map(|_| {
+            let id = allocate_child_id(parent_id, used_ids);
+            used_ids.insert(id.clone());
+            id
+        })
+        .collect()
+}
+
 fn allocate_child_id(parent_id: &str, used_ids: &std::collections::HashSet<String>) -> String {
     // Deterministic: lowest available numeric suffix wins.
     for n in 1u32.. {
@@ -753,6 +1815,33 @@ fn render_goal(node: &Node) -> String {
     buf
 }
 
+/// Earlier attempts on `node_id` in this run whose iteration commit landed, from
+/// `.runner/state/attempts.jsonl`.
+///
+/// Best-effort: an unreadable file is logged and the caller falls back to `run_state.json`.
+fn attempt_history(root: &Path, run_id: &str, node_id: &str, limit: usize) -> Option<String> {
+    let records = read_node_attempts(&attempts_path(root), run_id, node_id)
+        .and_then(|records| keep_landed_attempts(&Git::new(root), records));
+    match records {
+        Ok(records) => render_attempt_history(&records, limit),
+        Err(err) => {
+            tracing::warn!(error = %err, "attempt history unavailable");
+            None
+        }
+    }
+}
+
+/// Repository map for the prompt; best-effort like the other optional sections.
+fn repo_map(root: &Path, cfg: &RepoMapConfig) -> Option<String> {
+    match load_repo_map(root, cfg) {
+        Ok(map) => map,
+        Err(err) => {
+            tracing::warn!(error = %err, "repo map unavailable");
+            None
+        }
+    }
+}
+
 fn history_from_run_state(run_state: &RunState) -> Option<String> {
     if run_state.last_status == Some(AgentStatus::Retry) {
         return run_state.last_summary.clone();
@@ -760,55 +1849,74 @@ fn history_from_run_state(run_state: &RunState) -> Option<String> {
     None
 }
 
+/// Changes committed by the last iteration on `node` in this run, when `node` is being retried.
+///
+/// Best-effort: git failures are logged and leave the diff out of the context.
+fn previous_attempt_diff(
+    root: &Path,
+    run_id: &str,
+    node: &Node,
+    cfg: &RetryDiffConfig,
+) -> Option<String> {
+    if !cfg.enabled || node.attempts == 0 {
+        return None;
+    }
+    let git = Git::new(root);
+    let found = git
+        .last_commit_with_trailers(&[("Runner-Run", run_id), ("Runner-Node", &node.id)])
+        .and_then(|commit| {
+            let Some(commit) = commit else {
+                return Ok(None);
+            };
+            let diff = git.commit_diff(&commit.sha, &cfg.include, &cfg.exclude)?;
+            Ok(Some((commit, diff)))
+        });
+    let (commit, diff) = match found {
+        Ok(Some(found)) => found,
+        Ok(None) => return None,
+        Err(err) => {
+            tracing::warn!(error = %err, node_id = %node.id, "previous attempt diff unavailable");
+            return None;
+        }
+    };
+    if diff.trim().is_empty() {
+        return None;
+    }
+    let diff = truncate_to(&diff, cfg.max_bytes, TruncateStrategy::Head, &ByteCount);
+    let short_sha = commit.sha.get(..12).unwrap_or(&commit.sha);
+    Some(format!(
+        "Changes from {short_sha} ({}):\n\n```diff\n{}\n```",
+        commit.subject,
+        diff.trim_end()
+    ))
+}
+
 fn failure_from_run_state(
     root: &Path,
     run_id: &str,
     iter: u32,
     run_state: &RunState,
 ) -> Option<String> {
-    // Only show guard output, never runner-internal errors.
+    // Only show guard output or a rejecting review, never runner-internal errors.
     if run_state.last_status != Some(AgentStatus::Done) {
         return None;
     }
-    if run_state.last_guard != Some(GuardOutcome::Fail) {
-        return None;
-    }
+    let log_name = match (run_state.last_guard, run_state.last_review) {
+        (Some(GuardOutcome::Fail), _) => "guard.log",
+        (Some(GuardOutcome::Pass), Some(ReviewVerdict::Reject)) => "review.log",
+        _ => return None,
+    };
     let prev_iter = iter.saturating_sub(1);
     if prev_iter == 0 {
         return None;
     }
-    let guard_log = root
+    let log = root
         .join(".runner")
         .join("iterations")
         .join(run_id)
         .join(prev_iter.to_string())
-        .join("guard.log");
-    fs::read_to_string(guard_log).ok()
-}
-
-fn summarize_tree(root: &Node, max_nodes: usize) -> String {
-    let mut lines = Vec::new();
-    summarize_tree_inner(root, 0, max_nodes, &mut lines);
-    lines.join("\n")
-}
-
-fn summarize_tree_inner(node: &Node, depth: usize, max_nodes: usize, lines: &mut Vec<String>) {
-    if lines.len() >= max_nodes {
-        return;
-    }
-    let indent = "  ".repeat(depth);
-    lines.push(format!(
-        "{}- {} (next={}, passes={}, attempts={}/{})",
-        indent,
-        node.id,
-        node.next.as_str(),
-        node.passes,
-        node.attempts,
-        node.max_attempts
-    ));
-    for child in &node.children {
-        summarize_tree_inner(child, depth + 1, max_nodes, lines);
-    }
+        .join(log_name);
+    fs::read_to_string(log).ok()
 }
 
 #[cfg(test)]
@@ -817,9 +1925,10 @@ mod tests {
     use crate::core::types::{AgentOutput, DecompositionOutput, TreeChildSpec};
     use crate::io::git::Git;
     use crate::io::guards::GuardRunner;
+    use crate::io::journal::{journal_path, read_journal};
     use crate::test_support::{
         ScriptedExec, ScriptedExecutor, ScriptedGuard, ScriptedGuardRunner, ScriptedOutput,
-        TestRepo, load_tree_fixture,
+        TestRepo, leaf, load_tree_fixture, node, node_with_children,
     };
     use std::path::Path;
 
@@ -830,20 +1939,931 @@ mod tests {
         let git = Git::new(root);
         git.add_all().expect("git add");
         assert!(
-            git.commit_staged("chore: set root next")
-                .expect("git commit")
+            git.commit_staged("chore: set root next")
+                .expect("git commit")
+        );
+    }
+
+    /// Verifies a retry iteration updates run_state and writes iteration logs.
+    ///
+    /// Uses scripted executor returning Retry status. Asserts:
+    /// - run_state.next_iter increments
+    /// - run_state.last_status is Retry
+    /// - Iteration logs (meta, output, tree snapshots) exist
+    /// - No guard.log (guards skipped for retry)
+    #[test]
+    fn step_updates_run_state_and_tree_on_retry() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        set_root_next(&repo, root, NodeNext::Execute);
+
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Retry,
+                summary: "needs more".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        }]);
+        let guard_runner = ScriptedGuardRunner::new(vec![ScriptedGuard {
+            outcome: GuardOutcome::Pass,
+            log: "guard output".to_string(),
+        }]);
+
+        let outcome =
+            run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+
+        assert_eq!(outcome.iter, 1);
+        let run_state =
+            load_run_state(&root.join(".runner/state/run_state.json")).expect("run state");
+        assert_eq!(run_state.next_iter, 2);
+        assert_eq!(run_state.last_status, Some(AgentStatus::Retry));
+        assert_eq!(run_state.last_summary, Some("needs more".to_string()));
+
+        let iter_dir = root
+            .join(".runner/iterations")
+            .join(&outcome.run_id)
+            .join(outcome.iter.to_string());
+        assert!(iter_dir.join("meta.json").exists());
+        assert!(iter_dir.join("output.json").exists());
+        assert!(iter_dir.join("tree.before.json").exists());
+        assert!(iter_dir.join("tree.after.json").exists());
+        assert!(!iter_dir.join("guard.log").exists());
+        assert_eq!(guard_runner.remaining(), 1);
+        executor.assert_drained().expect("executor drained");
+    }
+
+    /// A `.runner/.gitignore` written by an older runner gets the missing lines in its own
+    /// commit instead of failing the step.
+    #[test]
+    fn step_adds_missing_gitignore_lines() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        set_root_next(&repo, root, NodeNext::Execute);
+        let gitignore = root.join(".runner/.gitignore");
+        fs::write(&gitignore, "context/\niterations/\nrunner.lock\n").expect("old gitignore");
+        let git = Git::new(root);
+        git.add_all().expect("git add");
+        assert!(git.commit_staged("chore: old gitignore").expect("commit"));
+
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Retry,
+                summary: "needs more".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        }]);
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+        run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+
+        let contents = fs::read_to_string(&gitignore).expect("read gitignore");
+        assert!(contents.starts_with("context/\niterations/\nrunner.lock\n"));
+        assert!(contents.lines().any(|line| line == "state/attempts.jsonl"));
+        let subjects = git.log_subjects().expect("log");
+        assert_eq!(subjects[1], "chore(loop): update .runner/.gitignore");
+        git.ensure_clean().expect("clean");
+    }
+
+    /// Off the run branch the step fails before touching `.runner/.gitignore` or the tree
+    /// schema, so nothing is committed on that branch.
+    #[test]
+    fn step_off_run_branch_leaves_runner_files_alone() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        set_root_next(&repo, root, NodeNext::Execute);
+        let gitignore = root.join(".runner/.gitignore");
+        fs::write(&gitignore, "context/\niterations/\nrunner.lock\n").expect("old gitignore");
+        let schema_path = root.join(".runner/state/schema.json");
+        let v1_schema = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../schemas/task_tree/v1.schema.json"
+        ));
+        fs::write(&schema_path, v1_schema).expect("write v1 schema");
+        let git = Git::new(root);
+        git.add_all().expect("git add");
+        assert!(
+            git.commit_staged("chore: old runner files")
+                .expect("commit")
+        );
+        git.checkout_new_branch("feature")
+            .expect("checkout feature");
+        let head = git.log_subjects().expect("log")[0].clone();
+
+        let executor = ScriptedExecutor::new(Vec::new());
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+        let err = run_step(root, &executor, &guard_runner, &StepConfig::default())
+            .expect_err("wrong branch");
+        assert!(err.to_string().contains("expected to be on"), "{err}");
+        assert_eq!(git.log_subjects().expect("log")[0], head);
+        assert_eq!(
+            fs::read_to_string(&gitignore).expect("read gitignore"),
+            "context/\niterations/\nrunner.lock\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&schema_path).expect("read schema"),
+            v1_schema
+        );
+        git.ensure_clean().expect("clean");
+    }
+
+    /// Verifies Done + Pass marks the node as passed and writes guard log.
+    ///
+    /// Uses scripted executor returning Done status with passing guards. Asserts:
+    /// - outcome.guard is Pass
+    /// - guard.log exists (guards ran)
+    /// - tree.passes is true (node completed successfully)
+    /// - Iteration logs exist
+    #[test]
+    fn step_marks_done_and_writes_guard_log() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        set_root_next(&repo, root, NodeNext::Execute);
+
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Done,
+                summary: "done".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        }]);
+        let guard_runner = ScriptedGuardRunner::new(vec![ScriptedGuard {
+            outcome: GuardOutcome::Pass,
+            log: "guard output".to_string(),
+        }]);
+
+        let outcome =
+            run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+        assert_eq!(outcome.guard, GuardOutcome::Pass);
+
+        let guard_log = root
+            .join(".runner/iterations")
+            .join(&outcome.run_id)
+            .join(outcome.iter.to_string())
+            .join("guard.log");
+        assert!(guard_log.exists());
+
+        let tree = load_tree(
+            &root.join(".runner/state/schema.json"),
+            &root.join(".runner/state/tree.json"),
+        )
+        .expect("load tree");
+        assert!(tree.passes);
+
+        let iter_dir = root
+            .join(".runner/iterations")
+            .join(&outcome.run_id)
+            .join(outcome.iter.to_string());
+        assert!(iter_dir.join("meta.json").exists());
+        assert!(iter_dir.join("output.json").exists());
+        guard_runner.assert_drained().expect("guard drained");
+        executor.assert_drained().expect("executor drained");
+    }
+
+    /// Verifies the iteration commit carries the summary body, `Runner-*` trailers, and that
+    /// meta.json records a diffstat that excludes `.runner/`.
+    #[test]
+    fn step_commit_has_trailers_and_meta_records_diffstat() {
+        struct WritingExecutor {
+            inner: ScriptedExecutor,
+        }
+        impl Executor for WritingExecutor {
+            fn exec(&self, request: &crate::io::executor::ExecRequest) -> Result<()> {
+                fs::write(request.workdir.join("notes.txt"), "one\ntwo\n")?;
+                self.inner.exec(request)
+            }
+        }
+
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        set_root_next(&repo, root, NodeNext::Execute);
+
+        let executor = WritingExecutor {
+            inner: ScriptedExecutor::new(vec![ScriptedExec {
+                output: ScriptedOutput::AgentOutput(AgentOutput {
+                    status: AgentStatus::Done,
+                    summary: "wrote notes".to_string(),
+                    follow_ups: Vec::new(),
+                }),
+                tree_update: None,
+            }]),
+        };
+        let guard_runner = ScriptedGuardRunner::new(vec![ScriptedGuard {
+            outcome: GuardOutcome::Pass,
+            log: "ok".to_string(),
+        }]);
+
+        let outcome =
+            run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+
+        let out = std::process::Command::new("git")
+            .args(["log", "-1", "--pretty=%B"])
+            .current_dir(root)
+            .output()
+            .expect("git log");
+        let msg = String::from_utf8_lossy(&out.stdout);
+        assert!(msg.starts_with(&format!(
+            "chore(loop): run {} iter 1 node root status=done guard=pass",
+            outcome.run_id
+        )));
+        assert!(msg.contains("\n\nwrote notes\n\n"));
+        assert!(msg.contains(&format!("Runner-Run: {}\n", outcome.run_id)));
+        assert!(msg.contains("Runner-Iter: 1\n"));
+        assert!(msg.contains("Runner-Node: root\n"));
+        assert!(msg.contains("Runner-Status: done\n"));
+        assert!(msg.contains("Runner-Guard: pass\n"));
+        assert!(msg.contains("Runner-Duration-Ms: "));
+
+        let meta_path = root
+            .join(".runner/iterations")
+            .join(&outcome.run_id)
+            .join("1/meta.json");
+        let meta: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(meta_path).expect("read meta"))
+                .expect("parse meta");
+        assert_eq!(
+            meta["diffstat"],
+            serde_json::json!({"files_changed": 1, "insertions": 2, "deletions": 0})
+        );
+    }
+
+    /// Verifies a retried node sees the diff its previous attempt committed, as context file
+    /// and as a prompt section.
+    #[test]
+    fn retry_context_includes_previous_attempt_diff() {
+        struct WritingExecutor {
+            inner: ScriptedExecutor,
+            prompts: std::cell::RefCell<Vec<String>>,
+        }
+        impl Executor for WritingExecutor {
+            fn exec(&self, request: &crate::io::executor::ExecRequest) -> Result<()> {
+                if self.prompts.borrow().is_empty() {
+                    fs::write(request.workdir.join("notes.txt"), "first try\n")?;
+                } else {
+                    let diff = fs::read_to_string(request.workdir.join(".runner/context/diff.md"))?;
+                    assert!(diff.contains("+first try"), "{diff}");
+                }
+                self.prompts.borrow_mut().push(request.prompt.clone());
+                self.inner.exec(request)
+            }
+        }
+
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        set_root_next(&repo, root, NodeNext::Execute);
+
+        let retry = || ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Retry,
+                summary: "not yet".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        };
+        let executor = WritingExecutor {
+            inner: ScriptedExecutor::new(vec![retry(), retry()]),
+            prompts: Default::default(),
+        };
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+
+        run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step 1");
+        run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step 2");
+
+        let prompts = executor.prompts.borrow();
+        assert!(!prompts[0].contains("### Diff (previous attempt)"));
+        assert!(prompts[1].contains("### Diff (previous attempt)"));
+        assert!(prompts[1].contains("+first try"));
+        assert!(prompts[1].contains("iter 1 node root status=retry"));
+        assert!(
+            !prompts[1].contains("a/.runner/"),
+            "runner state is excluded"
+        );
+    }
+
+    /// Verifies the prompt carries the repository map and that the map is cached by HEAD sha.
+    #[test]
+    fn prompt_includes_repo_map() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        set_root_next(&repo, root, NodeNext::Execute);
+
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Retry,
+                summary: "looked around".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        }]);
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+        run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+
+        let prompt = executor.last_request().expect("request").prompt;
+        assert!(prompt.contains("### Repository Map"), "{prompt}");
+        assert!(
+            prompt.contains("Tracked files:\nREADME.md  3 B"),
+            "{prompt}"
+        );
+        assert!(!prompt.contains("\n.runner/state/tree.json  "));
+        let cached = fs::read_dir(crate::io::repo_map::repo_map_cache_dir(root))
+            .expect("cache")
+            .count();
+        assert_eq!(cached, 1);
+        Git::new(root).ensure_clean().expect("cache is gitignored");
+    }
+
+    /// Verifies an enabled planner runs before the executor, and that its plan reaches the
+    /// executor prompt and the iteration logs.
+    #[test]
+    fn planner_plan_feeds_the_executor() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        repo.write_config(&RunnerConfig {
+            planner: crate::io::config::PlannerConfig {
+                enabled: false,
+                nodes: vec!["root".to_string()],
+            },
+            ..RunnerConfig::default()
+        })
+        .expect("config");
+        set_root_next(&repo, root, NodeNext::Execute);
+
+        let executor = ScriptedExecutor::new(vec![
+            ScriptedExec {
+                output: ScriptedOutput::PlanOutput(crate::core::types::PlanOutput {
+                    plan: "1. Add the parser".to_string(),
+                    files: vec!["src/parse.rs".to_string()],
+                }),
+                tree_update: None,
+            },
+            ScriptedExec {
+                output: ScriptedOutput::AgentOutput(AgentOutput {
+                    status: AgentStatus::Retry,
+                    summary: "started".to_string(),
+                    follow_ups: Vec::new(),
+                }),
+                tree_update: None,
+            },
+        ]);
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+        let outcome =
+            run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+        executor.assert_drained().expect("planner and executor ran");
+
+        let prompt = executor.last_request().expect("request").prompt;
+        assert!(prompt.contains(
+            "### Planner Notes\n\n<planner>1. Add the parser\n\nFiles to touch:\n- src/parse.rs"
+        ));
+        let iter_dir = root
+            .join(".runner/iterations")
+            .join(&outcome.run_id)
+            .join("1");
+        assert!(iter_dir.join("planner_output.json").exists());
+        assert!(iter_dir.join("planner_prompt.md").exists());
+        let meta = crate::io::iteration_log::read_meta(root, &outcome.run_id, 1)
+            .expect("read meta")
+            .expect("meta");
+        let names: Vec<&str> = meta
+            .prompt_templates
+            .iter()
+            .map(|template| template.name.as_str())
+            .collect();
+        assert_eq!(names, ["planner", "executor"]);
+        assert!(meta.phases.planning_ms.is_some());
+    }
+
+    /// Verifies a planner that edits files is an agent error and its edits are discarded.
+    #[test]
+    fn planner_edits_are_rejected_and_discarded() {
+        struct EditingPlanner {
+            inner: ScriptedExecutor,
+        }
+        impl Executor for EditingPlanner {
+            fn exec(&self, request: &crate::io::executor::ExecRequest) -> Result<()> {
+                let state = request.workdir.join(".runner/state");
+                fs::write(request.workdir.join("README.md"), "planned over\n")?;
+                fs::write(request.workdir.join("scratch.txt"), "x\n")?;
+                fs::write(state.join("assumptions.md"), "rewritten\n")?;
+                fs::create_dir_all(state.join("notes"))?;
+                fs::write(state.join("notes/root.md"), "planner note\n")?;
+                self.inner.exec(request)
+            }
+        }
+
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        repo.write_config(&RunnerConfig {
+            planner: crate::io::config::PlannerConfig {
+                enabled: true,
+                nodes: Vec::new(),
+            },
+            ..RunnerConfig::default()
+        })
+        .expect("config");
+        set_root_next(&repo, root, NodeNext::Execute);
+
+        let executor = EditingPlanner {
+            inner: ScriptedExecutor::new(vec![ScriptedExec {
+                output: ScriptedOutput::PlanOutput(crate::core::types::PlanOutput {
+                    plan: "plan".to_string(),
+                    files: Vec::new(),
+                }),
+                tree_update: Some({
+                    let mut tree = repo.read_tree().expect("tree");
+                    tree.title = "planner title".to_string();
+                    tree
+                }),
+            }]),
+        };
+        let assumptions_before =
+            fs::read_to_string(root.join(".runner/state/assumptions.md")).expect("assumptions");
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+        let outcome =
+            run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+        executor.inner.assert_drained().expect("executor skipped");
+
+        assert_eq!(outcome.status, AgentStatus::Retry);
+        assert_eq!(
+            fs::read_to_string(root.join("README.md")).expect("readme"),
+            "hi\n"
+        );
+        assert!(!root.join("scratch.txt").exists());
+        let agent_error = fs::read_to_string(
+            root.join(".runner/iterations")
+                .join(&outcome.run_id)
+                .join("1/agent_error.log"),
+        )
+        .expect("agent error");
+        assert!(agent_error.contains("planner must not modify files"));
+        assert!(agent_error.contains("README.md"));
+        assert!(
+            agent_error.contains(".runner/state/tree.json"),
+            "{agent_error}"
+        );
+        assert!(agent_error.contains(".runner/state/notes/root.md"));
+        assert_eq!(
+            fs::read_to_string(root.join(".runner/state/assumptions.md")).expect("assumptions"),
+            assumptions_before
+        );
+        assert!(!root.join(".runner/state/notes/root.md").exists());
+        let tree = repo.read_tree().expect("tree");
+        assert_eq!(tree.attempts, 1);
+        assert_ne!(tree.title, "planner title");
+        Git::new(root).ensure_clean().expect("clean");
+    }
+
+    /// Verifies a rejected review fails the attempt, is recorded apart from the guard outcome,
+    /// feeds the next attempt as failure context, and that the next review sees the node's
+    /// earlier commit.
+    #[test]
+    fn reviewer_rejection_fails_attempt_with_review_as_failure() {
+        /// Executor that edits `feature.txt` whenever the executor (not the reviewer) runs.
+        struct EditingExecutor {
+            inner: ScriptedExecutor,
+        }
+        impl Executor for EditingExecutor {
+            fn exec(&self, request: &crate::io::executor::ExecRequest) -> Result<()> {
+                if request.output_path.ends_with("output.json")
+                    && !request.output_path.ends_with("reviewer_output.json")
+                {
+                    let path = request.workdir.join("feature.txt");
+                    let mut contents = fs::read_to_string(&path).unwrap_or_default();
+                    contents.push_str("line\n");
+                    fs::write(path, contents)?;
+                }
+                self.inner.exec(request)
+            }
+        }
+        let done = || ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Done,
+                summary: "feature".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        };
+        let review = |verdict, reason: &str| ScriptedExec {
+            output: ScriptedOutput::ReviewOutput(ReviewOutput {
+                verdict,
+                reasons: vec![reason.to_string()],
+            }),
+            tree_update: None,
+        };
+        let pass = || ScriptedGuard {
+            outcome: GuardOutcome::Pass,
+            log: "guard output".to_string(),
+        };
+
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        repo.write_config(&RunnerConfig {
+            max_iterations: 5,
+            reviewer: ReviewerConfig {
+                enabled: true,
+                ..ReviewerConfig::default()
+            },
+            ..RunnerConfig::default()
+        })
+        .expect("config");
+        set_root_next(&repo, root, NodeNext::Execute);
+
+        let executor = EditingExecutor {
+            inner: ScriptedExecutor::new(vec![
+                done(),
+                review(ReviewVerdict::Reject, "acceptance 2 is not covered"),
+                done(),
+                review(ReviewVerdict::Approve, "all criteria met"),
+            ]),
+        };
+        let guard_runner = ScriptedGuardRunner::new(vec![pass(), pass()]);
+
+        let first =
+            run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step 1");
+        assert_eq!(first.status, AgentStatus::Done);
+        assert_eq!(first.guard, GuardOutcome::Pass);
+        assert_eq!(first.review, Some(ReviewVerdict::Reject));
+        let tree = repo.read_tree().expect("tree");
+        assert!(!tree.passes);
+        assert_eq!(tree.attempts, 1);
+        let iter_dir = root.join(".runner/iterations").join(&first.run_id);
+        let review_prompt =
+            fs::read_to_string(iter_dir.join("1/reviewer_prompt.md")).expect("review prompt");
+        assert!(review_prompt.contains("Changes in this attempt:"));
+        assert!(review_prompt.contains("+line"));
+        assert!(iter_dir.join("1/reviewer_output.json").exists());
+        let guard_log = fs::read_to_string(iter_dir.join("1/guard.log")).expect("guard log");
+        assert_eq!(guard_log, "guard output\n");
+        let review_log = fs::read_to_string(iter_dir.join("1/review.log")).expect("review log");
+        assert_eq!(
+            review_log,
+            "Reviewer rejected the changes:\n- acceptance 2 is not covered\n"
+        );
+        let meta = crate::io::iteration_log::read_meta(root, &first.run_id, 1)
+            .expect("read meta")
+            .expect("meta");
+        assert_eq!(meta.guard, GuardOutcome::Pass);
+        assert_eq!(meta.review, Some(ReviewVerdict::Reject));
+        assert!(meta.phases.review_ms.is_some());
+        let message = Git::new(root).log_subjects().expect("log")[0].clone();
+        assert!(
+            message.ends_with("status=done guard=pass review=reject"),
+            "{message}"
+        );
+        let records = crate::io::attempts::read_node_attempts(
+            &crate::io::attempts::attempts_path(root),
+            &first.run_id,
+            "root",
+        )
+        .expect("read attempts");
+        assert_eq!(records[0].review, Some(ReviewVerdict::Reject));
+        assert!(
+            records[0]
+                .failure
+                .as_deref()
+                .unwrap()
+                .contains("acceptance 2 is not covered")
+        );
+        assert_eq!(
+            meta.prompt_templates.last().map(|t| t.name.as_str()),
+            Some("reviewer")
+        );
+
+        let second =
+            run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step 2");
+        executor.inner.assert_drained().expect("all agents ran");
+        assert_eq!(second.guard, GuardOutcome::Pass);
+        assert_eq!(second.review, Some(ReviewVerdict::Approve));
+        assert!(repo.read_tree().expect("tree").passes);
+        let executor_prompt =
+            fs::read_to_string(iter_dir.join("2/prompt.md")).expect("executor prompt");
+        assert!(executor_prompt.contains("acceptance 2 is not covered"));
+        let review_prompt =
+            fs::read_to_string(iter_dir.join("2/reviewer_prompt.md")).expect("review prompt");
+        assert!(review_prompt.contains("Changes in this attempt:"));
+        assert!(review_prompt.contains("Changes from "));
+    }
+
+    /// Verifies reviewer edits to runner state are an agent error and discarded, while the
+    /// executor's staged work is kept.
+    #[test]
+    fn reviewer_state_edits_are_rejected_and_discarded() {
+        /// Executor that edits `feature.txt` as the executor and `questions.md` as the reviewer.
+        struct EditingAgents {
+            inner: ScriptedExecutor,
+        }
+        impl Executor for EditingAgents {
+            fn exec(&self, request: &crate::io::executor::ExecRequest) -> Result<()> {
+                if request.output_path.ends_with("reviewer_output.json") {
+                    let path = request.workdir.join(".runner/state/questions.md");
+                    fs::write(path, "reviewer question\n")?;
+                } else {
+                    fs::write(request.workdir.join("feature.txt"), "line\n")?;
+                }
+                self.inner.exec(request)
+            }
+        }
+
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        repo.write_config(&RunnerConfig {
+            reviewer: ReviewerConfig {
+                enabled: true,
+                ..ReviewerConfig::default()
+            },
+            ..RunnerConfig::default()
+        })
+        .expect("config");
+        set_root_next(&repo, root, NodeNext::Execute);
+        let questions_before =
+            fs::read_to_string(root.join(".runner/state/questions.md")).expect("questions");
+
+        let mut reviewed_tree = repo.read_tree().expect("tree");
+        reviewed_tree.title = "reviewer title".to_string();
+        let executor = EditingAgents {
+            inner: ScriptedExecutor::new(vec![
+                ScriptedExec {
+                    output: ScriptedOutput::AgentOutput(AgentOutput {
+                        status: AgentStatus::Done,
+                        summary: "feature".to_string(),
+                        follow_ups: Vec::new(),
+                    }),
+                    tree_update: None,
+                },
+                ScriptedExec {
+                    output: ScriptedOutput::ReviewOutput(ReviewOutput {
+                        verdict: ReviewVerdict::Approve,
+                        reasons: Vec::new(),
+                    }),
+                    tree_update: Some(reviewed_tree),
+                },
+            ]),
+        };
+        let guard_runner = ScriptedGuardRunner::new(vec![ScriptedGuard {
+            outcome: GuardOutcome::Pass,
+            log: "ok".to_string(),
+        }]);
+        let outcome =
+            run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+        executor.inner.assert_drained().expect("all agents ran");
+
+        assert_eq!(outcome.status, AgentStatus::Retry);
+        let agent_error = fs::read_to_string(
+            root.join(".runner/iterations")
+                .join(&outcome.run_id)
+                .join("1/agent_error.log"),
+        )
+        .expect("agent error");
+        assert!(agent_error.contains("reviewer must not modify files"));
+        assert!(agent_error.contains(".runner/state/questions.md"));
+        assert!(agent_error.contains(".runner/state/tree.json"));
+        let tree = repo.read_tree().expect("tree");
+        assert!(!tree.passes);
+        assert_ne!(tree.title, "reviewer title");
+        assert_eq!(
+            fs::read_to_string(root.join(".runner/state/questions.md")).expect("questions"),
+            questions_before
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("feature.txt")).expect("feature"),
+            "line\n"
+        );
+        Git::new(root).ensure_clean().expect("clean");
+    }
+
+    /// Verifies verifier edits to runner state are an agent error and discarded, so an approval
+    /// that came with them does not verify the goal.
+    #[test]
+    fn verifier_state_edits_are_rejected_and_discarded() {
+        struct EditingVerifier {
+            inner: ScriptedExecutor,
+        }
+        impl Executor for EditingVerifier {
+            fn exec(&self, request: &crate::io::executor::ExecRequest) -> Result<()> {
+                let notes = request.workdir.join(".runner/state/notes");
+                fs::create_dir_all(&notes)?;
+                fs::write(notes.join("root.md"), "verifier note\n")?;
+                self.inner.exec(request)
+            }
+        }
+
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        repo.write_config(&RunnerConfig {
+            verifier: crate::io::config::VerifierConfig {
+                enabled: true,
+                max_rounds: 2,
+            },
+            ..RunnerConfig::default()
+        })
+        .expect("config");
+        let mut tree = node_with_children("root", 0, vec![leaf("root.1", 0, true)]);
+        tree.passes = true;
+        repo.write_tree(&tree).expect("write tree");
+        let git = Git::new(root);
+        git.add_all().expect("git add");
+        assert!(git.commit_staged("chore: complete tree").expect("commit"));
+
+        let mut edited = tree.clone();
+        edited.children[0].goal = "rewritten by the verifier".to_string();
+        let executor = EditingVerifier {
+            inner: ScriptedExecutor::new(vec![ScriptedExec {
+                output: ScriptedOutput::VerificationOutput(
+                    crate::core::types::VerificationOutput {
+                        approved: true,
+                        summary: "goal met".to_string(),
+                        children: Vec::new(),
+                    },
+                ),
+                tree_update: Some(edited),
+            }]),
+        };
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+        let outcome =
+            run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+        executor.inner.assert_drained().expect("verifier ran");
+
+        assert_eq!(outcome.selected_id, "root");
+        assert_eq!(outcome.status, AgentStatus::Retry);
+        let agent_error = fs::read_to_string(
+            root.join(".runner/iterations")
+                .join(&outcome.run_id)
+                .join("1/agent_error.log"),
+        )
+        .expect("agent error");
+        assert!(agent_error.contains("verifier must not modify files"));
+        assert!(
+            agent_error.contains(".runner/state/tree.json"),
+            "{agent_error}"
+        );
+        assert_eq!(repo.read_tree().expect("tree"), tree);
+        assert!(!root.join(".runner/state/notes/root.md").exists());
+        let run_state = repo.read_run_state().expect("run state");
+        assert!(!run_state.goal_verified);
+        assert_eq!(run_state.verify_rounds, 1);
+        git.ensure_clean().expect("clean");
+    }
+
+    /// Verifies each attempt is recorded in `attempts.jsonl`, and that later
+    /// attempts on the node see all earlier ones in the history section.
+    #[test]
+    fn attempts_sidecar_feeds_history_of_every_attempt() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        repo.write_config(&RunnerConfig {
+            max_iterations: 5,
+            ..RunnerConfig::default()
+        })
+        .expect("config");
+        set_root_next(&repo, root, NodeNext::Execute);
+
+        let exec = |status, summary: &str| ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status,
+                summary: summary.to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        };
+        let executor = ScriptedExecutor::new(vec![
+            exec(AgentStatus::Done, "first try"),
+            exec(AgentStatus::Retry, "second try"),
+            exec(AgentStatus::Retry, "third try"),
+        ]);
+        let guard_runner = ScriptedGuardRunner::new(vec![ScriptedGuard {
+            outcome: GuardOutcome::Fail,
+            log: "compiling\nerror: boom\n".to_string(),
+        }]);
+
+        run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step 1");
+        run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step 2");
+        run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step 3");
+
+        let run_id = repo
+            .read_run_state()
+            .expect("run state")
+            .run_id
+            .expect("run id");
+        let records = crate::io::attempts::read_node_attempts(
+            &crate::io::attempts::attempts_path(root),
+            &run_id,
+            "root",
+        )
+        .expect("read attempts");
+        let git = Git::new(root);
+        let records = keep_landed_attempts(&git, records).expect("landed attempts");
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].guard, GuardOutcome::Fail);
+        let first_commit = git
+            .last_commit_with_trailers(&[("Runner-Run", &run_id), ("Runner-Iter", "1")])
+            .expect("find commit")
+            .expect("iter 1 commit");
+        assert_eq!(
+            records[0].commit.as_deref(),
+            Some(first_commit.sha.as_str())
+        );
+        assert!(records.iter().all(|record| record.commit.is_some()));
+        assert!(
+            records[0]
+                .failure
+                .as_deref()
+                .unwrap()
+                .ends_with("error: boom")
+        );
+
+        let prompt = executor.last_request().expect("request").prompt;
+        assert!(
+            prompt.contains("Attempt 1 (iter 1, status=done, guard=fail"),
+            "{prompt}"
+        );
+        assert!(prompt.contains("error: boom"));
+        assert!(prompt.contains("Attempt 2 (iter 2, status=retry, guard=skipped"));
+        assert!(
+            prompt.contains(&format!("commit {}", &first_commit.sha[..12])),
+            "{prompt}"
+        );
+        git.ensure_clean().expect("attempts.jsonl is ignored");
+    }
+
+    /// Verifies appended notes are committed and shown on the next attempt, and that rewriting
+    /// notes is an agent error that restores the previous notes.
+    #[test]
+    fn notes_are_append_only_and_reach_the_next_prompt() {
+        struct NotesExecutor {
+            inner: ScriptedExecutor,
+            calls: std::cell::Cell<u32>,
+        }
+        impl Executor for NotesExecutor {
+            fn exec(&self, request: &crate::io::executor::ExecRequest) -> Result<()> {
+                let notes = request.workdir.join(".runner/state/notes/root.md");
+                self.calls.set(self.calls.get() + 1);
+                if self.calls.get() == 1 {
+                    fs::create_dir_all(notes.parent().unwrap())?;
+                    fs::write(&notes, "- parser lives in src/parse.rs\n")?;
+                } else {
+                    assert!(request.prompt.contains("### Node Notes"));
+                    assert!(request.prompt.contains("parser lives in src/parse.rs"));
+                    fs::write(&notes, "rewritten\n")?;
+                }
+                self.inner.exec(request)
+            }
+        }
+
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        set_root_next(&repo, root, NodeNext::Execute);
+
+        let retry = || ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Retry,
+                summary: "not yet".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        };
+        let executor = NotesExecutor {
+            inner: ScriptedExecutor::new(vec![retry(), retry()]),
+            calls: Default::default(),
+        };
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+
+        run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step 1");
+        let outcome =
+            run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step 2");
+
+        let agent_error = fs::read_to_string(
+            root.join(".runner/iterations")
+                .join(&outcome.run_id)
+                .join("2/agent_error.log"),
+        )
+        .expect("agent error log");
+        assert!(
+            agent_error.contains("'root.md' was rewritten"),
+            "{agent_error}"
         );
+        let notes = fs::read_to_string(root.join(".runner/state/notes/root.md")).expect("notes");
+        assert_eq!(notes, "- parser lives in src/parse.rs\n");
+        Git::new(root).ensure_clean().expect("notes are committed");
     }
 
-    /// Verifies a retry iteration updates run_state and writes iteration logs.
-    ///
-    /// Uses scripted executor returning Retry status. Asserts:
-    /// - run_state.next_iter increments
-    /// - run_state.last_status is Retry
-    /// - Iteration logs (meta, output, tree snapshots) exist
-    /// - No guard.log (guards skipped for retry)
+    /// Verifies `run_step` journals each phase of an executed iteration in order and records
+    /// timestamps and phase timings in `meta.json`.
     #[test]
-    fn step_updates_run_state_and_tree_on_retry() {
+    fn step_journals_events_and_records_phase_timings() {
         let repo = TestRepo::new().expect("repo");
         let root = repo.root();
         repo.start_run().expect("start");
@@ -851,91 +2871,245 @@ mod tests {
 
         let executor = ScriptedExecutor::new(vec![ScriptedExec {
             output: ScriptedOutput::AgentOutput(AgentOutput {
-                status: AgentStatus::Retry,
-                summary: "needs more".to_string(),
+                status: AgentStatus::Done,
+                summary: "done".to_string(),
+                follow_ups: Vec::new(),
             }),
             tree_update: None,
         }]);
         let guard_runner = ScriptedGuardRunner::new(vec![ScriptedGuard {
             outcome: GuardOutcome::Pass,
-            log: "guard output".to_string(),
+            log: "ok".to_string(),
         }]);
-
         let outcome =
             run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
 
-        assert_eq!(outcome.iter, 1);
-        let run_state =
-            load_run_state(&root.join(".runner/state/run_state.json")).expect("run state");
-        assert_eq!(run_state.next_iter, 2);
-        assert_eq!(run_state.last_status, Some(AgentStatus::Retry));
-        assert_eq!(run_state.last_summary, Some("needs more".to_string()));
+        let records = read_journal(&journal_path(root, &outcome.run_id)).expect("journal");
+        let types: Vec<String> = records
+            .iter()
+            .map(|record| {
+                serde_json::to_value(&record.event).expect("json")["type"]
+                    .as_str()
+                    .expect("type")
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(
+            types,
+            [
+                "step_started",
+                "node_selected",
+                "context_written",
+                "exec_started",
+                "exec_finished",
+                "guard_started",
+                "guard_finished",
+                "state_updated",
+                "committed",
+            ]
+        );
+        assert!(records.iter().all(|record| record.iter == Some(1)));
+        assert!(records.windows(2).all(|pair| pair[0].ts <= pair[1].ts));
+        assert!(matches!(
+            records[6].event,
+            JournalEvent::GuardFinished {
+                outcome: Some(GuardOutcome::Pass),
+                ..
+            }
+        ));
 
-        let iter_dir = root
-            .join(".runner/iterations")
-            .join(&outcome.run_id)
-            .join(outcome.iter.to_string());
-        assert!(iter_dir.join("meta.json").exists());
-        assert!(iter_dir.join("output.json").exists());
-        assert!(iter_dir.join("tree.before.json").exists());
-        assert!(iter_dir.join("tree.after.json").exists());
-        assert!(!iter_dir.join("guard.log").exists());
-        assert_eq!(guard_runner.remaining(), 1);
-        executor.assert_drained().expect("executor drained");
+        let meta = crate::io::iteration_log::read_meta(root, &outcome.run_id, 1)
+            .expect("read meta")
+            .expect("meta");
+        let started_at = meta.started_at.expect("started_at");
+        let ended_at = meta.ended_at.expect("ended_at");
+        assert!(chrono::DateTime::parse_from_rfc3339(&started_at).is_ok());
+        assert!(started_at <= ended_at);
+        assert_eq!(
+            meta.prompt_templates,
+            [PromptTemplates::embedded()
+                .get(TemplateKind::Executor)
+                .reference()]
+        );
+        let timed: Vec<&str> = meta
+            .phases
+            .entries()
+            .iter()
+            .filter(|(_, ms)| ms.is_some())
+            .map(|(name, _)| *name)
+            .collect();
+        assert_eq!(
+            timed,
+            [
+                "context_build",
+                "prompt_render",
+                "agent_exec",
+                "tree_validation",
+                "guards",
+                "state_update",
+                "commit",
+            ]
+        );
     }
 
-    /// Verifies Done + Pass marks the node as passed and writes guard log.
+    /// Verifies a step that stops early is journaled as an error.
+    #[test]
+    fn step_journals_stuck_leaf_as_error() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        let started = repo.start_run().expect("start");
+        let mut tree = repo.read_tree().expect("read tree");
+        tree.next = NodeNext::Execute;
+        tree.attempts = tree.max_attempts;
+        repo.write_tree(&tree).expect("write tree");
+        let git = Git::new(root);
+        git.add_all().expect("git add");
+        git.commit_staged("chore: exhaust attempts")
+            .expect("commit");
+
+        let executor = ScriptedExecutor::new(Vec::new());
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+        let err = run_step(root, &executor, &guard_runner, &StepConfig::default()).unwrap_err();
+        assert!(err.downcast_ref::<StuckLeafError>().is_some());
+
+        let records = read_journal(&journal_path(root, &started.run_id)).expect("journal");
+        let last = records.last().expect("records");
+        assert!(
+            matches!(&last.event, JournalEvent::Error { message } if message.contains("stuck")),
+            "{last:?}"
+        );
+    }
+
+    /// Verifies lifecycle hooks fire for the transitions an iteration causes.
     ///
-    /// Uses scripted executor returning Done status with passing guards. Asserts:
-    /// - outcome.guard is Pass
-    /// - guard.log exists (guards ran)
-    /// - tree.passes is true (node completed successfully)
-    /// - Iteration logs exist
+    /// A single-node tree passes in one iteration, so `pre_step`, `post_step`,
+    /// `on_node_passed` and `on_complete` run (and are logged); `on_stuck` does not. Files the
+    /// hooks write are discarded instead of being committed or dirtying the worktree.
+    #[cfg(unix)]
     #[test]
-    fn step_marks_done_and_writes_guard_log() {
+    fn step_runs_lifecycle_hooks() {
         let repo = TestRepo::new().expect("repo");
         let root = repo.root();
         repo.start_run().expect("start");
+        let echo_event = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            "echo \"$RUNNER_HOOK\"".to_string(),
+        ];
+        let echo_and_write = |file: &str| {
+            vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                format!("echo \"$RUNNER_HOOK\"; echo hook > {file}"),
+            ]
+        };
+        crate::io::config::write_config(
+            &root.join(".runner/state/config.toml"),
+            &RunnerConfig {
+                hooks: crate::io::config::HooksConfig {
+                    pre_step: echo_and_write("pre.txt"),
+                    post_step: echo_and_write("post.txt"),
+                    on_node_passed: echo_event.clone(),
+                    on_stuck: echo_event.clone(),
+                    on_complete: echo_event.clone(),
+                    ..Default::default()
+                },
+                ..RunnerConfig::default()
+            },
+        )
+        .expect("write config");
         set_root_next(&repo, root, NodeNext::Execute);
 
         let executor = ScriptedExecutor::new(vec![ScriptedExec {
             output: ScriptedOutput::AgentOutput(AgentOutput {
                 status: AgentStatus::Done,
                 summary: "done".to_string(),
+                follow_ups: Vec::new(),
             }),
             tree_update: None,
         }]);
         let guard_runner = ScriptedGuardRunner::new(vec![ScriptedGuard {
             outcome: GuardOutcome::Pass,
-            log: "guard output".to_string(),
+            log: "ok".to_string(),
         }]);
-
         let outcome =
             run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
-        assert_eq!(outcome.guard, GuardOutcome::Pass);
 
-        let guard_log = root
+        let hooks_dir = root
             .join(".runner/iterations")
             .join(&outcome.run_id)
-            .join(outcome.iter.to_string())
-            .join("guard.log");
-        assert!(guard_log.exists());
+            .join("1/hooks");
+        for event in ["pre_step", "post_step", "on_node_passed", "on_complete"] {
+            let log = fs::read_to_string(hooks_dir.join(format!("{event}.log"))).expect(event);
+            assert!(log.contains(&format!("{event}\n")), "{event}: {log}");
+        }
+        assert!(!hooks_dir.join("on_stuck.log").exists());
+        for (event, file) in [("pre_step", "pre.txt"), ("post_step", "post.txt")] {
+            assert!(!root.join(file).exists(), "{file} discarded");
+            let log = fs::read_to_string(hooks_dir.join(format!("{event}.log"))).expect(event);
+            assert!(log.contains("worktree changes discarded"), "{event}: {log}");
+        }
+        Git::new(root)
+            .ensure_clean()
+            .expect("hooks leave worktree clean");
+    }
 
-        let tree = load_tree(
-            &root.join(".runner/state/schema.json"),
-            &root.join(".runner/state/tree.json"),
+    /// Verifies a forced interrupt records `interrupted.json`, discards the agent's edits, and
+    /// leaves the run resumable at the same iteration number.
+    #[test]
+    fn step_forced_interrupt_discards_changes_and_is_resumable() {
+        struct InterruptingExecutor;
+        impl Executor for InterruptingExecutor {
+            fn exec(&self, request: &crate::io::executor::ExecRequest) -> Result<()> {
+                fs::write(request.workdir.join("half-done.txt"), "partial\n")?;
+                request.interrupt.request();
+                request.interrupt.request();
+                Err(InterruptedError { phase: "executor" }.into())
+            }
+        }
+
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        set_root_next(&repo, root, NodeNext::Execute);
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+
+        let config = StepConfig::default();
+        let err =
+            run_step(root, &InterruptingExecutor, &guard_runner, &config).expect_err("interrupted");
+        assert!(err.downcast_ref::<InterruptedError>().is_some());
+
+        let run_state = load_run_state(&root.join(".runner/state/run_state.json")).expect("state");
+        let run_id = run_state.run_id.clone().expect("run id");
+        assert_eq!(run_state.next_iter, 1);
+        let iter_dir = root.join(".runner/iterations").join(&run_id).join("1");
+        let record: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(iter_dir.join("interrupted.json")).expect("read record"),
         )
-        .expect("load tree");
-        assert!(tree.passes);
+        .expect("parse record");
+        assert_eq!(record["phase"], "executor");
+        assert_eq!(record["node_id"], "root");
+        assert!(!root.join("half-done.txt").exists());
+        Git::new(root).ensure_clean().expect("clean worktree");
 
-        let iter_dir = root
-            .join(".runner/iterations")
-            .join(&outcome.run_id)
-            .join(outcome.iter.to_string());
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Retry,
+                summary: "resumed".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        }]);
+        let outcome =
+            run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("resume");
+        assert_eq!(outcome.iter, 1);
+        assert!(
+            root.join(".runner/iterations")
+                .join(&run_id)
+                .join("1.aborted-1/interrupted.json")
+                .exists()
+        );
         assert!(iter_dir.join("meta.json").exists());
-        assert!(iter_dir.join("output.json").exists());
-        guard_runner.assert_drained().expect("guard drained");
-        executor.assert_drained().expect("executor drained");
     }
 
     /// Verifies guard failure produces a failure log and that the next iteration includes it in
@@ -952,6 +3126,7 @@ mod tests {
                 output: ScriptedOutput::AgentOutput(AgentOutput {
                     status: AgentStatus::Done,
                     summary: "done".to_string(),
+                    follow_ups: Vec::new(),
                 }),
                 tree_update: None,
             },
@@ -959,6 +3134,7 @@ mod tests {
                 output: ScriptedOutput::AgentOutput(AgentOutput {
                     status: AgentStatus::Retry,
                     summary: "retry".to_string(),
+                    follow_ups: Vec::new(),
                 }),
                 tree_update: None,
             },
@@ -1004,6 +3180,7 @@ mod tests {
                 output: ScriptedOutput::AgentOutput(AgentOutput {
                     status: AgentStatus::Done,
                     summary: "ignored".to_string(),
+                    follow_ups: Vec::new(),
                 }),
                 tree_update: None,
             },
@@ -1011,6 +3188,7 @@ mod tests {
                 output: ScriptedOutput::AgentOutput(AgentOutput {
                     status: AgentStatus::Retry,
                     summary: "needs work".to_string(),
+                    follow_ups: Vec::new(),
                 }),
                 tree_update: None,
             },
@@ -1044,6 +3222,16 @@ mod tests {
         .expect("load tree");
         assert_eq!(tree.attempts, 0);
 
+        let run_id = run_state.run_id.expect("run id");
+        let records = crate::io::attempts::read_node_attempts(
+            &crate::io::attempts::attempts_path(root),
+            &run_id,
+            "root",
+        )
+        .expect("read attempts");
+        assert_eq!(records.len(), 1);
+        assert!(records[0].error.as_deref().unwrap().contains("boom"));
+
         // Next step should not include the runner error in history/failure context.
         let guard_runner2 = ScriptedGuardRunner::new(Vec::new());
         run_step(root, &executor, &guard_runner2, &StepConfig::default()).expect("step2");
@@ -1079,6 +3267,7 @@ mod tests {
                     goal: "Do child work".to_string(),
                     acceptance: Vec::new(),
                     next: NodeNext::Execute,
+                    depends_on: Vec::new(),
                 }],
             }),
             tree_update: None,
@@ -1105,6 +3294,50 @@ mod tests {
         executor.assert_drained().expect("executor drained");
     }
 
+    /// Sibling indexes in decomposer `depends_on` become the allocated ids, and selection
+    /// follows them instead of sibling order.
+    #[test]
+    fn step_decomposition_resolves_sibling_dependencies() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+
+        let child = |goal: &str, depends_on: Vec<usize>| TreeChildSpec {
+            title: goal.to_string(),
+            goal: goal.to_string(),
+            acceptance: Vec::new(),
+            next: NodeNext::Execute,
+            depends_on,
+        };
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::DecompositionOutput(DecompositionOutput {
+                summary: "split".to_string(),
+                children: vec![
+                    child("Wire the API", vec![1]),
+                    child("Add schema", Vec::new()),
+                ],
+            }),
+            tree_update: None,
+        }]);
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+
+        run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+
+        let tree = repo.read_tree().expect("tree");
+        assert_eq!(tree.children[0].depends_on, ["root.2"]);
+        assert!(tree.children[1].depends_on.is_empty());
+        assert_eq!(
+            crate::select::select_leaf(&tree).expect("select"),
+            crate::select::SelectOutcome::Open(crate::select::SelectedLeaf {
+                id: "root.2".to_string(),
+                path: "root/root.2".to_string(),
+                attempts: 0,
+                max_attempts: tree.children[1].max_attempts,
+            })
+        );
+        executor.assert_drained().expect("executor drained");
+    }
+
     /// Verifies retry then done writes history context and guard log on the second iter.
     #[test]
     fn step_retries_then_done_writes_history_and_guards() {
@@ -1118,6 +3351,7 @@ mod tests {
                 output: ScriptedOutput::AgentOutput(AgentOutput {
                     status: AgentStatus::Retry,
                     summary: "needs more".to_string(),
+                    follow_ups: Vec::new(),
                 }),
                 tree_update: None,
             },
@@ -1125,6 +3359,7 @@ mod tests {
                 output: ScriptedOutput::AgentOutput(AgentOutput {
                     status: AgentStatus::Done,
                     summary: "done".to_string(),
+                    follow_ups: Vec::new(),
                 }),
                 tree_update: None,
             },
@@ -1250,6 +3485,286 @@ mod tests {
         executor.assert_drained().expect("executor drained");
     }
 
+    /// Verifies decompositions over the configured limits are retried with an explanation.
+    #[test]
+    fn step_retries_when_decomposition_exceeds_limits() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        let start = repo.start_run().expect("start");
+        repo.write_config(&RunnerConfig {
+            decomposition: crate::core::decomposition::DecompositionLimits {
+                max_children: 2,
+                ..Default::default()
+            },
+            ..RunnerConfig::default()
+        })
+        .expect("config");
+        set_root_next(&repo, root, NodeNext::Decompose);
+        let before = repo.read_tree().expect("tree");
+
+        let children = ["one", "two", "three"]
+            .map(|goal| TreeChildSpec {
+                title: goal.to_string(),
+                goal: goal.to_string(),
+                acceptance: Vec::new(),
+                next: NodeNext::Execute,
+                depends_on: Vec::new(),
+            })
+            .to_vec();
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::DecompositionOutput(DecompositionOutput {
+                summary: "too many".to_string(),
+                children,
+            }),
+            tree_update: None,
+        }]);
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+
+        let outcome =
+            run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+        assert_eq!(outcome.status, AgentStatus::Retry);
+
+        let after = repo.read_tree().expect("tree");
+        assert!(after.children.is_empty());
+        assert_eq!(after.attempts, before.attempts + 1);
+        let agent_error = fs::read_to_string(
+            root.join(".runner/iterations")
+                .join(&start.run_id)
+                .join("1/agent_error.log"),
+        )
+        .expect("read agent_error.log");
+        assert!(
+            agent_error.contains("has 3 children (max_children = 2)"),
+            "{agent_error}"
+        );
+
+        guard_runner.assert_drained().expect("guard drained");
+        executor.assert_drained().expect("executor drained");
+    }
+
+    /// Verifies executor follow-ups become open siblings right after the node once it passes,
+    /// moving later open siblings down around passed ones, which keep their order.
+    #[test]
+    fn step_inserts_follow_ups_after_passing_node() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        repo.write_tree(&node_with_children(
+            "root",
+            0,
+            vec![
+                node("root.1", 0),
+                leaf("root.2", 1, true),
+                node("root.3", 2),
+            ],
+        ))
+        .expect("write tree");
+        let git = Git::new(root);
+        git.add_all().expect("git add");
+        assert!(git.commit_staged("chore: fixture").expect("git commit"));
+
+        let follow_ups = ["Add a --json flag", "Document the flag"]
+            .map(|goal| TreeChildSpec {
+                title: goal.to_string(),
+                goal: goal.to_string(),
+                acceptance: Vec::new(),
+                next: NodeNext::Execute,
+                depends_on: Vec::new(),
+            })
+            .to_vec();
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Done,
+                summary: "done".to_string(),
+                follow_ups,
+            }),
+            tree_update: None,
+        }]);
+        let guard_runner = ScriptedGuardRunner::new(vec![ScriptedGuard {
+            outcome: GuardOutcome::Pass,
+            log: "ok".to_string(),
+        }]);
+
+        let outcome =
+            run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+        assert_eq!(outcome.status, AgentStatus::Done);
+
+        let tree = repo.read_tree().expect("tree");
+        let children: Vec<_> = tree
+            .children
+            .iter()
+            .map(|child| (child.id.as_str(), child.order, child.passes))
+            .collect();
+        assert_eq!(
+            children,
+            [
+                ("root.1", 0, true),
+                ("root.2", 1, true),
+                ("root.4", 2, false),
+                ("root.5", 3, false),
+                ("root.3", 4, false),
+            ]
+        );
+        assert_eq!(tree.children[2].goal, "Add a --json flag");
+        assert!(!tree.passes);
+
+        guard_runner.assert_drained().expect("guard drained");
+        executor.assert_drained().expect("executor drained");
+    }
+
+    /// Verifies `status=blocked` holds the node without consuming an attempt, records the
+    /// reason as an open question, and stops selection once nothing else is left.
+    #[test]
+    fn step_blocked_status_holds_node_and_lists_question() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        repo.write_tree(&node_with_children("root", 0, vec![node("root.1", 0)]))
+            .expect("write tree");
+        let git = Git::new(root);
+        git.add_all().expect("git add");
+        assert!(git.commit_staged("chore: fixture").expect("git commit"));
+
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Blocked,
+                summary: "Needs a Stripe API key.".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        }]);
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+
+        let outcome =
+            run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+        assert_eq!(outcome.status, AgentStatus::Blocked);
+        assert_eq!(outcome.guard, GuardOutcome::Skipped);
+
+        let tree = repo.read_tree().expect("tree");
+        let blocked = &tree.children[0];
+        assert_eq!(blocked.blocked.as_deref(), Some("Needs a Stripe API key."));
+        assert_eq!(blocked.attempts, 0);
+        assert!(!tree.passes);
+        let questions =
+            fs::read_to_string(root.join(".runner/state/questions.md")).expect("questions");
+        assert!(
+            questions.contains("- [blocked] root.1 (root/root.1): Needs a Stripe API key.\n"),
+            "{questions}"
+        );
+
+        let err = run_step(root, &executor, &guard_runner, &StepConfig::default())
+            .expect_err("blocked tree");
+        let blocked = err
+            .downcast_ref::<BlockedTreeError>()
+            .expect("blocked error");
+        assert_eq!(blocked.blocked[0].id, "root.1");
+
+        guard_runner.assert_drained().expect("guard drained");
+        executor.assert_drained().expect("executor drained");
+    }
+
+    /// A run started with the v1 tree schema gets the current schema in its own commit before
+    /// the step, so a `blocked` node written by the step still loads afterwards.
+    #[test]
+    fn step_upgrades_v1_tree_schema_before_blocking() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        repo.start_run().expect("start");
+        repo.write_tree(&node_with_children("root", 0, vec![node("root.1", 0)]))
+            .expect("write tree");
+        let schema_path = root.join(".runner/state/schema.json");
+        fs::write(
+            &schema_path,
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../schemas/task_tree/v1.schema.json"
+            )),
+        )
+        .expect("write v1 schema");
+        let git = Git::new(root);
+        git.add_all().expect("git add");
+        assert!(git.commit_staged("chore: v1 run").expect("git commit"));
+
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Blocked,
+                summary: "Needs a Stripe API key.".to_string(),
+                follow_ups: Vec::new(),
+            }),
+            tree_update: None,
+        }]);
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+        let outcome =
+            run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+        assert_eq!(outcome.status, AgentStatus::Blocked);
+
+        let schema = fs::read_to_string(&schema_path).expect("read schema");
+        assert!(schema.contains(crate::io::init::TREE_SCHEMA_ID), "{schema}");
+        let tree = repo.read_tree().expect("tree loads with blocked node");
+        assert!(tree.children[0].blocked.is_some());
+        let subjects = git.log_subjects().expect("log");
+        assert_eq!(
+            subjects[1],
+            "chore(loop): upgrade .runner/state/schema.json"
+        );
+        git.ensure_clean().expect("clean");
+
+        executor.assert_drained().expect("executor drained");
+    }
+
+    /// Verifies follow-ups on the root are rejected as an agent error.
+    #[test]
+    fn step_rejects_follow_ups_on_root() {
+        let repo = TestRepo::new().expect("repo");
+        let root = repo.root();
+        let start = repo.start_run().expect("start");
+        set_root_next(&repo, root, NodeNext::Execute);
+
+        let executor = ScriptedExecutor::new(vec![ScriptedExec {
+            output: ScriptedOutput::AgentOutput(AgentOutput {
+                status: AgentStatus::Done,
+                summary: "done".to_string(),
+                follow_ups: vec![TreeChildSpec {
+                    title: "More".to_string(),
+                    goal: "More work".to_string(),
+                    acceptance: Vec::new(),
+                    next: NodeNext::Execute,
+                    depends_on: Vec::new(),
+                }],
+            }),
+            tree_update: None,
+        }]);
+        let guard_runner = ScriptedGuardRunner::new(Vec::new());
+
+        let outcome =
+            run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+        assert_eq!(outcome.status, AgentStatus::Retry);
+        assert!(repo.read_tree().expect("tree").children.is_empty());
+        let agent_error = fs::read_to_string(
+            root.join(".runner/iterations")
+                .join(&start.run_id)
+                .join("1/agent_error.log"),
+        )
+        .expect("read agent_error.log");
+        assert!(
+            agent_error.contains("follow_ups: 'root' is the root"),
+            "{agent_error}"
+        );
+        let dropped = fs::read_to_string(
+            root.join(".runner/iterations")
+                .join(&start.run_id)
+                .join("1/dropped_follow_ups.json"),
+        )
+        .expect("read dropped_follow_ups.json");
+        let dropped: DroppedFollowUps = serde_json::from_str(&dropped).expect("parse dropped");
+        assert_eq!(dropped.reason, "agent error");
+        assert_eq!(dropped.follow_ups.len(), 1);
+        assert_eq!(dropped.follow_ups[0].title, "More");
+
+        guard_runner.assert_drained().expect("guard drained");
+        executor.assert_drained().expect("executor drained");
+    }
+
     /// Verifies passed-node immutability violations are detected and logged.
     #[test]
     fn step_detects_passed_node_immutability_violation() {
@@ -1272,6 +3787,7 @@ mod tests {
             output: ScriptedOutput::AgentOutput(AgentOutput {
                 status: AgentStatus::Retry,
                 summary: "retry".to_string(),
+                follow_ups: Vec::new(),
             }),
             tree_update: Some(mutated),
         }]);
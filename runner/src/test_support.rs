@@ -17,7 +17,9 @@ use anyhow::{Context, Result, anyhow};
 #[cfg(feature = "test-support")]
 use tempfile::TempDir;
 
-use crate::core::types::{AgentOutput, DecompositionOutput, GuardOutcome};
+use crate::core::types::{
+    AgentOutput, DecompositionOutput, GuardOutcome, PlanOutput, ReviewOutput, VerificationOutput,
+};
 use crate::io::config::RunnerConfig;
 #[cfg(feature = "test-support")]
 use crate::io::config::write_config;
@@ -163,12 +165,15 @@ pub struct ScriptedExec {
 
 /// Supported scripted outputs for the executor interface.
 ///
-/// The runner invokes the executor for multiple roles (decomposer agent, executor agent),
-/// so tests must be able to script different JSON shapes.
+/// The runner invokes the executor for multiple roles (decomposer, planner, executor,
+/// reviewer and verifier agents), so tests must be able to script different JSON shapes.
 #[derive(Debug, Clone, serde::Serialize)]
 #[serde(untagged)]
 pub enum ScriptedOutput {
     DecompositionOutput(DecompositionOutput),
+    PlanOutput(PlanOutput),
+    ReviewOutput(ReviewOutput),
+    VerificationOutput(VerificationOutput),
     AgentOutput(AgentOutput),
 }
 
@@ -321,6 +326,9 @@ pub fn node(id: &str, order: i64) -> Node {
         passes: false,
         attempts: 0,
         max_attempts: 3,
+        blocked: None,
+        skipped: false,
+        depends_on: Vec::new(),
         children: Vec::new(),
     }
 }
@@ -48,10 +48,31 @@ pub struct Node {
     pub attempts: u32,
     /// Upper bound on attempts before the node is considered failed.
     pub max_attempts: u32,
+    /// Reason the executor reported `status=blocked`; blocked nodes are never selected and
+    /// keep their ancestors from passing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked: Option<String>,
+    /// Set by `runner tree skip`; skipped nodes are never selected and count as done for
+    /// their parent.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub skipped: bool,
+    /// Ids of nodes that must pass (or be skipped) before this node and its subtree are
+    /// selected (task tree v2).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
     pub children: Vec<Node>,
 }
 
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
 impl Node {
+    /// Blocked or skipped: the node and its subtree are left out of selection.
+    pub fn is_held(&self) -> bool {
+        self.blocked.is_some() || self.skipped
+    }
+
     /// Recursively sorts children by `(order, id)` for deterministic traversal.
     pub fn sort_children(&mut self) {
         self.children
@@ -79,6 +100,9 @@ pub fn default_tree_with_max_attempts(max_attempts: u32) -> Node {
         passes: false,
         attempts: 0,
         max_attempts,
+        blocked: None,
+        skipped: false,
+        depends_on: Vec::new(),
         children: Vec::new(),
     }
 }
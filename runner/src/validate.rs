@@ -7,7 +7,10 @@ use anyhow::{Context, Result, anyhow};
 use crate::io::config::load_config;
 use crate::io::git::Git;
 use crate::io::goal::read_goal_id;
-use crate::io::init::RunnerPaths;
+use crate::io::init::{
+    RunnerPaths, TREE_SCHEMA_ID, deployed_tree_schema_id, missing_gitignore_lines,
+};
+use crate::io::prompt::PromptTemplates;
 use crate::io::run_state::load_run_state;
 use crate::io::tree_store::load_tree;
 
@@ -26,7 +29,7 @@ pub struct ValidateOutcome {
     pub run: RunValidation,
 }
 
-/// Validate `.runner/` layout, config, tree, and run identity.
+/// Validate `.runner/` layout, config, tree, prompt template overrides, and run identity.
 pub fn validate_runner(root: &Path) -> Result<ValidateOutcome> {
     let paths = RunnerPaths::new(root);
 
@@ -48,9 +51,11 @@ pub fn validate_runner(root: &Path) -> Result<ValidateOutcome> {
     ensure_file(&paths.context_failure_path)?;
 
     ensure_gitignore(&paths.gitignore_path)?;
+    ensure_tree_schema(&paths.schema_path)?;
 
     load_config(&paths.config_path).with_context(|| "load config.toml")?;
     load_tree(&paths.schema_path, &paths.tree_path).with_context(|| "load tree.json")?;
+    PromptTemplates::load(root).with_context(|| "load prompt templates")?;
 
     let run_state = load_run_state(&paths.run_state_path).with_context(|| "load run_state.json")?;
     let run_id = match run_state.run_id {
@@ -106,14 +111,31 @@ fn ensure_file(path: &Path) -> Result<()> {
 }
 
 fn ensure_gitignore(path: &Path) -> Result<()> {
-    let contents =
-        std::fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
-    for required in ["context/", "iterations/"] {
-        if !contents.lines().any(|line| line.trim() == required) {
-            return Err(anyhow!("missing '{}' in {}", required, path.display()));
-        }
+    let missing = missing_gitignore_lines(path)?;
+    if missing.is_empty() {
+        return Ok(());
     }
-    Ok(())
+    Err(anyhow!(
+        "missing {} in {} (`runner start` or `runner step` adds them)",
+        missing
+            .iter()
+            .map(|line| format!("'{line}'"))
+            .collect::<Vec<_>>()
+            .join(", "),
+        path.display()
+    ))
+}
+
+fn ensure_tree_schema(path: &Path) -> Result<()> {
+    let id = deployed_tree_schema_id(path)?;
+    if id == TREE_SCHEMA_ID {
+        return Ok(());
+    }
+    Err(anyhow!(
+        "tree schema '{id}' in {} is older than '{TREE_SCHEMA_ID}' \
+         (`runner start` or `runner step` upgrades it)",
+        path.display()
+    ))
 }
 
 #[cfg(test)]
@@ -162,6 +184,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_errors_on_older_tree_schema() {
+        let repo = TestRepo::new().expect("repo");
+        init_runner(repo.root(), &InitOptions { force: false }).expect("init");
+        let paths = RunnerPaths::new(repo.root());
+        std::fs::write(
+            &paths.schema_path,
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/../schemas/task_tree/v1.schema.json"
+            )),
+        )
+        .expect("write v1 schema");
+
+        let err = validate_runner(repo.root()).expect_err("validate should fail");
+        assert!(
+            format!("{err:#}").contains("`runner start` or `runner step` upgrades it"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn validate_errors_on_invalid_prompt_override() {
+        let repo = TestRepo::new().expect("repo");
+        init_runner(repo.root(), &InitOptions { force: false }).expect("init");
+        let prompts_dir = repo.root().join(".runner/prompts");
+        std::fs::create_dir_all(&prompts_dir).expect("mkdir");
+        std::fs::write(prompts_dir.join("executor.md"), "no sections here").expect("write");
+
+        let err = validate_runner(repo.root()).expect_err("validate should fail");
+        assert!(
+            format!("{err:#}").contains("section:contract required"),
+            "{err:#}"
+        );
+    }
+
     #[test]
     fn validate_errors_on_goal_id_mismatch() {
         let repo = TestRepo::new().expect("repo");
@@ -0,0 +1,49 @@
+//! CLI tests for `runner step` command.
+//!
+//! Spawns the runner binary and verifies exit codes for hard stops that happen before the
+//! agent runs.
+
+use std::process::Command;
+
+use runner::exit_codes;
+use runner::io::config::RunnerConfig;
+use runner::io::git::Git;
+use runner::io::run_state::write_run_state;
+use runner::test_support::TestRepo;
+
+#[test]
+fn step_past_max_iterations_exits_with_max_iterations_code() {
+    let repo = TestRepo::new().expect("repo");
+    repo.start_run().expect("start");
+    repo.write_config(&RunnerConfig {
+        max_iterations: 1,
+        ..RunnerConfig::default()
+    })
+    .expect("write config");
+    let mut run_state = repo.read_run_state().expect("run state");
+    run_state.next_iter = 2;
+    write_run_state(
+        &repo.root().join(".runner/state/run_state.json"),
+        &run_state,
+    )
+    .expect("write run state");
+    let git = Git::new(repo.root());
+    git.add_all().expect("git add");
+    assert!(
+        git.commit_staged("chore: cap iterations")
+            .expect("git commit")
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_runner"))
+        .current_dir(repo.root())
+        .arg("step")
+        .output()
+        .expect("runner step");
+
+    assert_eq!(output.status.code(), Some(exit_codes::MAX_ITERATIONS));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("max iterations exceeded: next_iter=2 max_iterations=1"),
+        "{stderr}"
+    );
+}
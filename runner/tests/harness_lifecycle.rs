@@ -11,6 +11,7 @@ use runner::core::types::{
 };
 use runner::io::config::RunnerConfig;
 use runner::io::git::Git;
+use runner::io::init::TREE_SCHEMA_ID;
 use runner::io::run_state::load_run_state;
 use runner::io::tree_store::load_tree;
 use runner::step::{StepConfig, run_step};
@@ -19,6 +20,7 @@ use runner::test_support::{
     leaf, node_with_children,
 };
 use runner::tree::Node;
+use runner::validate::validate_runner;
 
 /// Full lifecycle test: drives runner loop from start → retries → tree complete.
 ///
@@ -72,6 +74,7 @@ fn full_lifecycle_completes_tree_with_retries() {
             output: ScriptedOutput::AgentOutput(AgentOutput {
                 status: AgentStatus::Retry,
                 summary: "need another attempt".to_string(),
+                follow_ups: Vec::new(),
             }),
             tree_update: None,
         },
@@ -79,6 +82,7 @@ fn full_lifecycle_completes_tree_with_retries() {
             output: ScriptedOutput::AgentOutput(AgentOutput {
                 status: AgentStatus::Done,
                 summary: "leaf-a complete".to_string(),
+                follow_ups: Vec::new(),
             }),
             tree_update: None,
         },
@@ -86,6 +90,7 @@ fn full_lifecycle_completes_tree_with_retries() {
             output: ScriptedOutput::AgentOutput(AgentOutput {
                 status: AgentStatus::Done,
                 summary: "leaf-b complete".to_string(),
+                follow_ups: Vec::new(),
             }),
             tree_update: None,
         },
@@ -227,6 +232,7 @@ fn guard_fail_reselection_loop() {
             output: ScriptedOutput::AgentOutput(AgentOutput {
                 status: AgentStatus::Done,
                 summary: "attempt 1".to_string(),
+                follow_ups: Vec::new(),
             }),
             tree_update: None,
         },
@@ -234,6 +240,7 @@ fn guard_fail_reselection_loop() {
             output: ScriptedOutput::AgentOutput(AgentOutput {
                 status: AgentStatus::Done,
                 summary: "attempt 2".to_string(),
+                follow_ups: Vec::new(),
             }),
             tree_update: None,
         },
@@ -241,6 +248,7 @@ fn guard_fail_reselection_loop() {
             output: ScriptedOutput::AgentOutput(AgentOutput {
                 status: AgentStatus::Done,
                 summary: "attempt 3 success".to_string(),
+                follow_ups: Vec::new(),
             }),
             tree_update: None,
         },
@@ -248,6 +256,7 @@ fn guard_fail_reselection_loop() {
             output: ScriptedOutput::AgentOutput(AgentOutput {
                 status: AgentStatus::Done,
                 summary: "leaf-b complete".to_string(),
+                follow_ups: Vec::new(),
             }),
             tree_update: None,
         },
@@ -361,12 +370,14 @@ fn decomposition_changes_selection_path() {
                         goal: "Do child 1".to_string(),
                         acceptance: Vec::new(),
                         next: runner::tree::NodeNext::Execute,
+                        depends_on: Vec::new(),
                     },
                     TreeChildSpec {
                         title: "Child 2".to_string(),
                         goal: "Do child 2".to_string(),
                         acceptance: Vec::new(),
                         next: runner::tree::NodeNext::Execute,
+                        depends_on: Vec::new(),
                     },
                 ],
             }),
@@ -376,6 +387,7 @@ fn decomposition_changes_selection_path() {
             output: ScriptedOutput::AgentOutput(AgentOutput {
                 status: AgentStatus::Done,
                 summary: "child-1 complete".to_string(),
+                follow_ups: Vec::new(),
             }),
             tree_update: None,
         },
@@ -383,6 +395,7 @@ fn decomposition_changes_selection_path() {
             output: ScriptedOutput::AgentOutput(AgentOutput {
                 status: AgentStatus::Done,
                 summary: "child-2 complete".to_string(),
+                follow_ups: Vec::new(),
             }),
             tree_update: None,
         },
@@ -486,6 +499,10 @@ fn resumption_from_saved_state() {
         last_status: Some(AgentStatus::Done),
         last_summary: Some("previous work".to_string()),
         last_guard: Some(GuardOutcome::Pass),
+        last_review: None,
+        started_at: None,
+        verify_rounds: 0,
+        goal_verified: false,
     };
     write_run_state(&root.join(".runner/state/run_state.json"), &run_state).expect("write state");
 
@@ -501,6 +518,7 @@ fn resumption_from_saved_state() {
         output: ScriptedOutput::AgentOutput(AgentOutput {
             status: AgentStatus::Done,
             summary: "open-child complete".to_string(),
+            follow_ups: Vec::new(),
         }),
         tree_update: None,
     }]);
@@ -568,6 +586,72 @@ fn resumption_from_saved_state() {
     assert!(err.to_string().contains("tree already complete"));
 }
 
+/// Upgrade test: a run started by a runner that deployed the v1 tree schema is resumed by
+/// `runner start`, which deploys v2, so a decomposition with dependencies loads afterwards.
+///
+/// Tests: `runner validate` reports the v1 schema, `runner start` upgrades and commits it,
+/// and `depends_on` (v2 only) survives the next `load_tree`.
+#[test]
+fn start_upgrades_tree_schema_of_older_run() {
+    let repo = TestRepo::new().expect("repo");
+    let root = repo.path();
+    repo.start_run().expect("start");
+    let schema_path = root.join(".runner/state/schema.json");
+    fs::write(
+        &schema_path,
+        include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../schemas/task_tree/v1.schema.json"
+        )),
+    )
+    .expect("write v1 schema");
+    let git = Git::new(root);
+    git.add_all().expect("git add");
+    assert!(
+        git.commit_staged("chore: run started by an older runner")
+            .expect("git commit")
+    );
+
+    let err = validate_runner(root).expect_err("v1 schema is reported");
+    assert!(format!("{err:#}").contains("is older than"), "{err:#}");
+
+    repo.start_run().expect("resume");
+    let schema = fs::read_to_string(&schema_path).expect("read schema");
+    assert!(schema.contains(TREE_SCHEMA_ID), "{schema}");
+    git.ensure_clean().expect("upgrade is committed");
+    validate_runner(root).expect("validate after upgrade");
+
+    let executor = ScriptedExecutor::new(vec![ScriptedExec {
+        output: ScriptedOutput::DecompositionOutput(DecompositionOutput {
+            summary: "split with a dependency".to_string(),
+            children: vec![
+                TreeChildSpec {
+                    title: "Schema".to_string(),
+                    goal: "Add the schema".to_string(),
+                    acceptance: Vec::new(),
+                    next: runner::tree::NodeNext::Execute,
+                    depends_on: Vec::new(),
+                },
+                TreeChildSpec {
+                    title: "Migration".to_string(),
+                    goal: "Migrate existing data".to_string(),
+                    acceptance: Vec::new(),
+                    next: runner::tree::NodeNext::Execute,
+                    depends_on: vec![0],
+                },
+            ],
+        }),
+        tree_update: None,
+    }]);
+    let guard_runner = ScriptedGuardRunner::new(Vec::new());
+    let outcome = run_step(root, &executor, &guard_runner, &StepConfig::default()).expect("step");
+    assert_eq!(outcome.status, AgentStatus::Decomposed);
+
+    let tree = repo.read_tree().expect("tree loads with depends_on");
+    assert_eq!(tree.children[1].depends_on, vec!["root.1".to_string()]);
+    executor.assert_drained().expect("executor drained");
+}
+
 fn must_find<'a>(node: &'a Node, id: &str) -> &'a Node {
     find_node(node, id).unwrap_or_else(|| panic!("missing node id={id}"))
 }
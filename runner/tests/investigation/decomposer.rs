@@ -73,12 +73,18 @@ fn prompt_inputs_for(title: &str, goal: &str, acceptance: &[&str]) -> PromptInpu
             passes: false,
             attempts: 0,
             max_attempts: 3,
+            blocked: None,
+            skipped: false,
+            depends_on: Vec::new(),
             children: Vec::new(),
         },
         tree_summary: "- root (passes=false, attempts=0/3)".to_string(),
         context_goal,
         context_history: None,
         context_failure: None,
+        context_diff: None,
+        notes: None,
+        repo_map: None,
         assumptions: String::new(),
         questions: String::new(),
     }
@@ -127,7 +133,8 @@ fn decomposer_suggests_children_for_complex_goal() {
             &inputs,
             Instant::now() + CODEX_TIMEOUT,
         )
-        .expect("decomposer run");
+        .expect("decomposer run")
+        .output;
 
     info!(children = output.children.len(), "decomposer returned");
     debug!(summary = %output.summary, "decomposition summary");
@@ -195,7 +202,8 @@ fn decomposer_targets_execute_for_simple_goal() {
             &inputs,
             Instant::now() + CODEX_TIMEOUT,
         )
-        .expect("decomposer run");
+        .expect("decomposer run")
+        .output;
 
     info!(children = output.children.len(), "decomposer returned");
     debug!(summary = %output.summary, "decomposition summary");
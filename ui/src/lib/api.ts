@@ -1,6 +1,7 @@
 import type {
   Node,
   RunState,
+  LockHolder,
   RunEntry,
   IterationMeta,
   AgentOutput,
@@ -26,6 +27,10 @@ export async function fetchRunState(): Promise<RunState> {
   return fetchJson<RunState>('/run-state');
 }
 
+export async function fetchLock(): Promise<LockHolder | null> {
+  return fetchJson<LockHolder | null>('/lock');
+}
+
 export async function fetchIterations(): Promise<{ runs: RunEntry[] }> {
   return fetchJson<{ runs: RunEntry[] }>('/iterations');
 }
@@ -11,7 +11,8 @@ interface ChangeEvent {
     | 'stream_updated'
     | 'config_changed'
     | 'assumptions_changed'
-    | 'questions_changed';
+    | 'questions_changed'
+    | 'lock_changed';
   run_id?: string;
   iter?: number;
 }
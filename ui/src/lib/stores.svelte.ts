@@ -1,6 +1,7 @@
 import type {
   Node,
   RunState,
+  LockHolder,
   RunnerConfig,
   StreamEvent,
   TimelineEntry,
@@ -21,6 +22,7 @@ export const connection = $state({
 export const data = $state({
   tree: null as Node | null,
   runState: null as RunState | null,
+  lock: null as LockHolder | null,
   config: null as RunnerConfig | null,
   assumptions: '',
   questions: '',
@@ -10,15 +10,30 @@ export interface Node {
   passes: boolean;
   attempts: number;
   max_attempts: number;
+  blocked?: string;
+  skipped?: boolean;
+  depends_on?: string[];
   children: Node[];
 }
 
 export interface RunState {
   run_id: string | null;
   next_iter: number;
-  last_status: 'done' | 'retry' | 'decomposed' | null;
+  last_status: 'done' | 'retry' | 'decomposed' | 'blocked' | null;
   last_summary: string | null;
   last_guard: 'pass' | 'fail' | 'skipped' | null;
+  started_at?: string;
+  verify_rounds?: number;
+  goal_verified?: boolean;
+}
+
+/** Holder of `.runner/runner.lock` (GET /api/lock). */
+export interface LockHolder {
+  pid: number;
+  host: string;
+  started_at: string;
+  command: string;
+  holder: 'alive' | 'stale' | 'unknown';
 }
 
 export interface IterationMeta {
@@ -30,6 +45,35 @@ export interface IterationMeta {
   started_at: string | null;
   ended_at: string | null;
   duration_ms: number | null;
+  diffstat?: DiffStat | null;
+  phases?: PhaseTimings;
+  prompt_templates?: PromptTemplateRef[];
+}
+
+/** Prompt template version rendered for an iteration. */
+export interface PromptTemplateRef {
+  name: string;
+  source: string;
+  sha256: string;
+}
+
+/** Per-phase durations in ms; null for phases that did not run. */
+export interface PhaseTimings {
+  context_build_ms: number | null;
+  planning_ms?: number | null;
+  prompt_render_ms: number | null;
+  agent_exec_ms: number | null;
+  tree_validation_ms: number | null;
+  guards_ms: number | null;
+  review_ms?: number | null;
+  state_update_ms: number | null;
+  commit_ms: number | null;
+}
+
+export interface DiffStat {
+  files_changed: number;
+  insertions: number;
+  deletions: number;
 }
 
 export interface AgentOutput {
@@ -46,6 +90,9 @@ export interface RunnerConfig {
   max_iterations?: number;
   max_attempts_default?: number;
   iteration_timeout_secs?: number;
+  max_run_duration_secs?: number;
+  prompt_budget_tokens?: number;
+  prompt_tokenizer?: 'bytes' | 'words' | 'bpe';
   iteration_output_limit?: number;
   guard_command?: string;
   [key: string]: unknown;